@@ -0,0 +1,58 @@
+//! `--alert` and `--on-done`: what happens when `--timeout` runs out or
+//! `--countdown` gets to zero, so the rain can get someone's attention
+//! rather than just carrying on.
+
+#[cfg(not(target_arch = "wasm32"))]
+use std::io::Write;
+
+#[cfg(not(target_arch = "wasm32"))]
+use crate::{job, term};
+
+/// The ways to tell someone a timer's done; any number of them.
+#[derive(Clone, Default)]
+pub struct Alert {
+    pub bell: bool,              // The terminal bell
+    pub notify: bool,            // An OSC 9 desktop notification
+    pub osc777: bool,            // An OSC 777 one, for terminals that read that instead
+    pub command: Option<String>, // A shell command, run without waiting for it
+}
+
+impl Alert {
+    /// Switch on the ways in a comma-separated list like `bell,notify`.
+    pub fn add(&mut self, ways: &str) -> Result<(), String> {
+        for way in ways.split(',').map(str::trim) {
+            match way.to_lowercase().as_str() {
+                "bell" => self.bell = true,
+                "notify" | "osc9" => self.notify = true,
+                "osc777" => self.osc777 = true,
+                _ => {
+                    return Err(format!(
+                        "unknown alert '{}': expected bell, notify or osc777",
+                        way
+                    ))
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Ring, notify and start the command, whichever were asked for, saying
+    /// `text` in a notification.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn fire(&self, out: &mut impl Write, text: &str) -> std::io::Result<()> {
+        if self.bell {
+            write!(out, "\x07")?;
+        }
+        if self.notify {
+            term::notify(out, text)?;
+        }
+        if self.osc777 {
+            term::notify_777(out, "matrix-rain", text)?;
+        }
+        out.flush()?;
+        if let Some(cmd) = &self.command {
+            job::start(cmd)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,59 @@
+use rand::Rng;
+
+use crate::{
+    color::{dim, ColorScheme, Sweep},
+    drop::random_char,
+    platform::Color,
+};
+
+/// Faint field of static glyphs filling the screen behind the rain, with the
+/// odd glyph twinkling now and then.
+pub struct Backdrop {
+    width: u16,
+    chars: Vec<char>,
+    glow: Vec<u8>, // Frames a cell stays brightened after twinkling
+}
+
+impl Backdrop {
+    pub fn new(width: u16, height: u16, rng: &mut impl Rng) -> Self {
+        let cells = width as usize * height as usize;
+        Backdrop {
+            width,
+            chars: (0..cells).map(|_| random_char(rng)).collect(),
+            glow: vec![0; cells],
+        }
+    }
+
+    /// The glyph at (`x`, `y`), as it stands.
+    pub fn char_at(&self, x: u16, y: u16) -> char {
+        self.chars[y as usize * self.width as usize + x as usize]
+    }
+
+    pub fn glyph(&self, x: u16, y: u16, color_scheme: ColorScheme, sweep: Sweep) -> (char, Color) {
+        let idx = y as usize * self.width as usize + x as usize;
+        let brightness = if self.glow[idx] > 0 { 0.7 } else { 0.3 };
+        // Borrow the scheme's darkest trail color so the field matches the rain
+        let color = color_scheme.get_colors(10, 10, x, sweep);
+        (self.chars[idx], dim(color, brightness))
+    }
+
+    /// Advance twinkles by a frame.
+    pub fn twinkle(&mut self, rng: &mut impl Rng) {
+        for glow in self.glow.iter_mut() {
+            *glow = glow.saturating_sub(1);
+        }
+
+        if self.chars.is_empty() {
+            return;
+        }
+        let twinkles = self.chars.len() / 800 + 1;
+        for _ in 0..twinkles {
+            if rng.gen_bool(0.3) {
+                let idx = rng.gen_range(0..self.chars.len());
+                self.chars[idx] = random_char(rng);
+                self.glow[idx] = rng.gen_range(3..10);
+            }
+        }
+    }
+}
+
@@ -0,0 +1,71 @@
+//! `matrix banner`: a still of the rain with a line of text set into it,
+//! for sshd `Banner` files, `/etc/issue.net` and the like.
+
+use crate::{cli, matrix::Matrix, platform::Color, render::Glyph};
+
+/// Run `matrix banner [--width N] [--height N] [--text TEXT] [--no-color]
+/// [OPTIONS]`, printing the banner to stdout.
+pub fn run(args: &[String]) -> std::io::Result<()> {
+    let mut width: u16 = 80;
+    let mut height: u16 = 12;
+    let mut text = String::new();
+    let mut color = true;
+    let mut options = vec![];
+    let mut i = 0;
+    while i < args.len() {
+        let val = args.get(i + 1);
+        match (args[i].as_str(), val) {
+            ("--width", Some(val)) => width = val.parse().ok().filter(|&n| n > 0).unwrap_or(width),
+            ("--height", Some(val)) => {
+                height = val.parse().ok().filter(|&n| n > 0).unwrap_or(height)
+            }
+            ("--text", Some(val)) => text = val.clone(),
+            ("--no-color", _) => {
+                color = false;
+                i += 1;
+                continue;
+            }
+            _ => {
+                options.push(args[i].clone());
+                i += 1;
+                continue;
+            }
+        }
+        i += 2;
+    }
+
+    let mut matrix = Matrix::with_size(cli::parse(&options), width, height);
+    matrix.warm_up();
+    let mut frame = matrix.render_frame();
+
+    // Clear a band across the middle row and set the text in it
+    let text: Vec<char> = text.chars().take(width as usize).collect();
+    if !text.is_empty() {
+        let band = (text.len() + 4).min(width as usize) as u16;
+        let left = (width - band) / 2;
+        let y = height / 2;
+        for x in left..left + band {
+            frame.set(x, y, Glyph::BLANK);
+        }
+        let start = left + (band - text.len() as u16) / 2;
+        for (x, &ch) in (start..).zip(&text) {
+            let fg = Color::White;
+            frame.set(
+                x,
+                y,
+                Glyph {
+                    ch,
+                    fg,
+                    ..Glyph::BLANK
+                },
+            );
+        }
+    }
+
+    if color {
+        println!("{}", frame.to_ansi_string());
+    } else {
+        print!("{}", frame.to_plain_string());
+    }
+    Ok(())
+}
@@ -0,0 +1,88 @@
+//! Noticing when a laptop is running on battery, so the rain can throttle
+//! itself until it's plugged back in.
+
+use std::time::Duration;
+
+use crate::platform::Instant;
+
+/// How often the power source is checked; it rarely changes and reading it
+/// isn't free.
+const CHECK_EVERY: Duration = Duration::from_secs(30);
+
+/// Whether the machine is running on battery. None where that can't be
+/// told, e.g. desktops without a battery or unsupported platforms.
+#[cfg(target_os = "linux")]
+pub fn on_battery() -> Option<bool> {
+    let read = |path: std::path::PathBuf| std::fs::read_to_string(path).ok();
+    let mut discharging = None;
+    for entry in std::fs::read_dir("/sys/class/power_supply").ok()?.flatten() {
+        let path = entry.path();
+        match read(path.join("type")).as_deref().map(str::trim) {
+            Some("Mains") | Some("USB")
+                if read(path.join("online")).as_deref().map(str::trim) == Some("1") =>
+            {
+                return Some(false);
+            }
+            Some("Battery") => {
+                let status = read(path.join("status")).unwrap_or_default();
+                let this = status.trim() == "Discharging";
+                discharging = Some(discharging.unwrap_or(false) || this);
+            }
+            _ => {}
+        }
+    }
+    discharging
+}
+
+/// Whether the machine is running on battery, asked of `pmset`.
+#[cfg(target_os = "macos")]
+pub fn on_battery() -> Option<bool> {
+    let output = std::process::Command::new("pmset")
+        .args(["-g", "batt"])
+        .output()
+        .ok()?;
+    let text = String::from_utf8_lossy(&output.stdout);
+    if text.contains("'Battery Power'") {
+        Some(true)
+    } else if text.contains("'AC Power'") {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+pub fn on_battery() -> Option<bool> {
+    None
+}
+
+/// Rechecks the power source now and then and reports when it changes.
+pub struct BatteryWatch {
+    next_check: Instant,
+    on_battery: bool,
+}
+
+impl BatteryWatch {
+    pub fn new() -> Self {
+        BatteryWatch {
+            next_check: Instant::now(),
+            on_battery: false,
+        }
+    }
+
+    /// The new state if the machine just went onto or off battery. The
+    /// first check reports being on battery straight away.
+    pub fn changed(&mut self) -> Option<bool> {
+        let now = Instant::now();
+        if now < self.next_check {
+            return None;
+        }
+        self.next_check = now + CHECK_EVERY;
+        let on_battery = on_battery().unwrap_or(false);
+        if on_battery == self.on_battery {
+            return None;
+        }
+        self.on_battery = on_battery;
+        Some(on_battery)
+    }
+}
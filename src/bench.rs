@@ -0,0 +1,77 @@
+//! `matrix bench`: render a fixed, heavy workload as fast as possible and
+//! report how long frames took, so optimizations can be measured.
+
+use std::time::{Duration, Instant};
+
+use crate::{cli, matrix::Matrix};
+
+/// The workload: the chaos preset, seeded so every run draws the same rain.
+const WORKLOAD: &[&str] = &["--preset", "chaos", "--seed", "1"];
+
+/// The `pct` percentile of sorted `times`.
+fn percentile(times: &[Duration], pct: f64) -> Duration {
+    if times.is_empty() {
+        return Duration::ZERO;
+    }
+    let idx = ((times.len() - 1) as f64 * pct / 100.0).round() as usize;
+    times[idx]
+}
+
+/// Run `matrix bench [--frames N] [--width N] [--height N] [OPTIONS]`.
+/// Options go on top of the workload, so e.g. `--hires` benchmarks that
+/// render mode.
+pub fn run(args: &[String]) -> std::io::Result<()> {
+    let mut frames: usize = 1000;
+    let mut width: u16 = 300;
+    let mut height: u16 = 100;
+    let mut options: Vec<String> = WORKLOAD.iter().map(|arg| arg.to_string()).collect();
+    let mut i = 0;
+    while i < args.len() {
+        let val = args.get(i + 1);
+        match (args[i].as_str(), val) {
+            ("--frames", Some(val)) => frames = val.parse().unwrap_or(frames).max(1),
+            ("--width", Some(val)) => width = val.parse().ok().filter(|&n| n > 0).unwrap_or(width),
+            ("--height", Some(val)) => {
+                height = val.parse().ok().filter(|&n| n > 0).unwrap_or(height)
+            }
+            _ => {
+                options.push(args[i].clone());
+                i += 1;
+                continue;
+            }
+        }
+        i += 2;
+    }
+
+    let mut matrix = Matrix::with_size(cli::parse(&options), width, height);
+    // Let the screen fill up so every measured frame is a busy one
+    matrix.warm_up();
+
+    let mut times = Vec::with_capacity(frames);
+    let mut bytes = 0;
+    let mut out = vec![];
+    let started = Instant::now();
+    for _ in 0..frames {
+        out.clear();
+        let frame_started = Instant::now();
+        matrix.draw(&mut out)?;
+        times.push(frame_started.elapsed());
+        bytes += out.len();
+    }
+    let elapsed = started.elapsed();
+    times.sort();
+
+    println!("Benchmarked {} frames at {}x{}", frames, width, height);
+    println!(
+        "  fps:      {:.0}",
+        frames as f64 / elapsed.as_secs_f64().max(f64::EPSILON)
+    );
+    println!("  p50:      {:.2?}", percentile(&times, 50.0));
+    println!("  p99:      {:.2?}", percentile(&times, 99.0));
+    println!(
+        "  max:      {:.2?}",
+        times.last().copied().unwrap_or_default()
+    );
+    println!("  output:   {} bytes/frame", bytes / frames);
+    Ok(())
+}
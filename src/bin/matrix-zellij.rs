@@ -0,0 +1,6 @@
+//! The zellij plugin, built with
+//! `cargo build --release --target wasm32-wasip1 --features zellij --bin matrix-zellij`.
+
+use zellij_tile::prelude::*;
+
+register_plugin!(matrix::zellij::ZellijRain);
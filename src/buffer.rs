@@ -0,0 +1,117 @@
+
+use crate::{color::dim, platform::Color};
+
+/// Below this intensity a cell has faded out completely.
+const CUTOFF: f32 = 0.05;
+
+/// One cell of the phosphor buffer: the last glyph written there, the color
+/// it was written with, and how much of that brightness is left.
+#[derive(Clone, Copy)]
+pub struct Cell {
+    pub ch: char,
+    pub color: Color,
+    pub intensity: f32,
+}
+
+impl Cell {
+    const DARK: Cell = Cell {
+        ch: ' ',
+        color: Color::Black,
+        intensity: 0.0,
+    };
+
+    pub fn is_lit(&self) -> bool {
+        self.intensity > 0.0
+    }
+
+    /// The color the cell glows with right now.
+    pub fn shade(&self) -> Color {
+        dim(self.color, self.intensity)
+    }
+}
+
+/// Per-cell intensity buffer modelled on a phosphor screen: drops light up
+/// the cells under them at full brightness and every frame all cells decay
+/// toward black, so nothing ever has to be explicitly erased.
+pub struct PhosphorBuffer {
+    width: u16,
+    height: u16,
+    cells: Vec<Cell>,
+    clipped: usize, // Stamps that fell outside, which effects should never make
+}
+
+impl PhosphorBuffer {
+    pub fn new(width: u16, height: u16) -> Self {
+        PhosphorBuffer {
+            width,
+            height,
+            cells: vec![Cell::DARK; width as usize * height as usize],
+            clipped: 0,
+        }
+    }
+
+    pub fn resize(&mut self, width: u16, height: u16) {
+        *self = PhosphorBuffer::new(width, height);
+    }
+
+    pub fn width(&self) -> u16 {
+        self.width
+    }
+
+    pub fn height(&self) -> u16 {
+        self.height
+    }
+
+    /// How many stamps have missed the buffer since it was last resized.
+    pub fn clipped(&self) -> usize {
+        self.clipped
+    }
+
+    pub fn get(&self, x: u16, y: u16) -> Cell {
+        self.cells[y as usize * self.width as usize + x as usize]
+    }
+
+    /// Light a cell at full brightness. Later stamps win, so stamping back
+    /// to front gives correct z-ordering.
+    pub fn stamp(&mut self, x: u16, y: u16, ch: char, color: Color) {
+        if x < self.width && y < self.height {
+            self.cells[y as usize * self.width as usize + x as usize] = Cell {
+                ch,
+                color,
+                intensity: 1.0,
+            };
+        } else {
+            self.clipped += 1;
+        }
+    }
+
+    /// Every cell, row-major.
+    pub fn cells(&self) -> &[Cell] {
+        &self.cells
+    }
+
+    /// A buffer made of `cells`, row-major, e.g. to combine others into.
+    pub fn from_cells(width: u16, height: u16, cells: Vec<Cell>) -> Self {
+        debug_assert_eq!(cells.len(), width as usize * height as usize);
+        PhosphorBuffer {
+            width,
+            height,
+            cells,
+            clipped: 0,
+        }
+    }
+
+    /// Fade every cell by a frame. `persistence` is the fraction of
+    /// brightness a cell keeps, so 0.0 clears trails the instant a drop
+    /// moves on and values near 1.0 leave long ghostly after-images.
+    pub fn decay(&mut self, persistence: f32) {
+        for cell in &mut self.cells {
+            if cell.is_lit() {
+                cell.intensity *= persistence;
+                if cell.intensity < CUTOFF {
+                    *cell = Cell::DARK;
+                }
+            }
+        }
+    }
+}
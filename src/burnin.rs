@@ -0,0 +1,56 @@
+use std::time::Duration;
+
+use crate::{color::dim, platform::Color, render::Glyph};
+
+/// Seconds for the brightness to fade down and back up again.
+const BREATH: f64 = 600.0;
+
+/// Every this many seconds, the last `INVERTED` of them swap bright and dim.
+const INVERT_EVERY: f64 = 1800.0;
+const INVERTED: f64 = 120.0;
+
+/// Every this many seconds, a dark band takes `SWEEP` of them to cross the
+/// screen, so every cell gets a rest, the overlays' too.
+const SWEEP_EVERY: f64 = 900.0;
+const SWEEP: f64 = 60.0;
+
+/// `--oled`: vary `frame`, `elapsed` into the run, so nothing stays lit
+/// the same for hours. The whole screen slowly breathes between 60% and
+/// full brightness, now and then heads go dim and trails bright, and a dark
+/// band sweeps across every so often.
+pub fn apply(frame: &mut [Glyph], cols: u16, elapsed: Duration) {
+    let t = elapsed.as_secs_f64();
+    let brightness = 0.8 + 0.2 * (t / BREATH * std::f64::consts::TAU).cos();
+    let inverted = t % INVERT_EVERY >= INVERT_EVERY - INVERTED;
+    let band = (t % SWEEP_EVERY < SWEEP).then(|| {
+        let width = (cols / 8).max(1) as f64;
+        let left = (t % SWEEP_EVERY) / SWEEP * (cols as f64 + width) - width;
+        left..left + width
+    });
+    for (idx, glyph) in frame.iter_mut().enumerate() {
+        let x = (idx % cols.max(1) as usize) as f64;
+        let mut factor = brightness as f32;
+        if band.as_ref().is_some_and(|band| band.contains(&x)) {
+            factor *= 0.2;
+        }
+        if inverted {
+            glyph.fg = invert(glyph.fg);
+        }
+        glyph.fg = dim(glyph.fg, factor);
+        glyph.bg = dim(glyph.bg, factor);
+    }
+}
+
+/// `color` with its brightness turned around, keeping its hue: bright heads
+/// come out dim and faint trails bright.
+fn invert(color: Color) -> Color {
+    let color = dim(color, 1.0); // White as RGB
+    let Color::Rgb { r, g, b } = color else {
+        return color;
+    };
+    let level = r.max(g).max(b) as f32 / 255.0;
+    if level == 0.0 {
+        return color;
+    }
+    dim(color, (1.1 - level).clamp(0.1, 1.0) / level)
+}
@@ -0,0 +1,164 @@
+use crate::{
+    color::{dim, ColorScheme, Sweep},
+    matrix::Matrix,
+    platform::Color,
+    render::Glyph,
+    settings::Settings,
+};
+
+/// Frames of the tiles that aren't picked.
+const FRAME: Color = Color::Rgb { r: 90, g: 90, b: 90 };
+/// What the keys do, along the bottom.
+const HINT: &str = "arrows pick  Enter rains  q quits";
+
+/// `--chooser`: before the rain, every color scheme raining in a little
+/// tile of its own, to pick one by eye with the arrows and Enter rather
+/// than by remembering its name for `-c`.
+pub struct Chooser {
+    tiles: Vec<Matrix>, // One per scheme, in `ColorScheme::ALL` order
+    picked: usize,
+    across: usize, // Tiles per row of the grid
+    cols: u16,
+    rows: u16,
+}
+
+impl Chooser {
+    /// Tiles raining as `settings` would in each scheme, on a `cols` x
+    /// `rows` screen, with the scheme it has picked to begin with.
+    pub fn new(settings: &Settings, cols: u16, rows: u16) -> Self {
+        let tiles = ColorScheme::ALL
+            .into_iter()
+            .map(|scheme| {
+                let preview = Settings {
+                    color_scheme: scheme,
+                    rainbow_axis: settings.rainbow_axis,
+                    density: settings.density,
+                    direction: settings.direction,
+                    charset: settings.charset.clone(),
+                    head_glyphs: settings.head_glyphs.clone(),
+                    ..Settings::default()
+                };
+                Matrix::with_size(preview, 1, 1)
+            })
+            .collect();
+        let picked = ColorScheme::ALL.iter().position(|&scheme| scheme == settings.color_scheme).unwrap_or(0);
+        let mut chooser = Chooser { tiles, picked, across: 1, cols: 0, rows: 0 };
+        chooser.resize(cols, rows);
+        chooser
+    }
+
+    /// Lay the tiles out again for a `cols` x `rows` screen.
+    pub fn resize(&mut self, cols: u16, rows: u16) {
+        self.cols = cols;
+        self.rows = rows;
+        self.across = match cols {
+            0..=47 => 1,
+            48..=89 => 2,
+            _ => 3,
+        };
+        let (width, height) = self.tile_size();
+        for tile in &mut self.tiles {
+            tile.resize(width.saturating_sub(2).max(1), height.saturating_sub(2).max(1));
+            tile.warm_up();
+        }
+    }
+
+    /// Move the pick `dx` tiles along and `dy` down, staying on the grid.
+    pub fn go(&mut self, dx: i32, dy: i32) {
+        let count = self.tiles.len() as i32;
+        let next = self.picked as i32 + dx + dy * self.across as i32;
+        if (0..count).contains(&next) {
+            self.picked = next as usize;
+        }
+    }
+
+    pub fn scheme(&self) -> ColorScheme {
+        ColorScheme::ALL[self.picked]
+    }
+
+    /// Advance every tile's rain by a frame.
+    pub fn step(&mut self) {
+        for tile in &mut self.tiles {
+            tile.step();
+        }
+    }
+
+    /// The screen: a title, the tiles in a grid, each framed with its name
+    /// on top and the picked one lit up, and the keys at the bottom.
+    pub fn frame(&self) -> Vec<Glyph> {
+        let (cols, rows) = (self.cols, self.rows);
+        let mut frame = vec![Glyph::BLANK; cols as usize * rows as usize];
+        let mut put = |x: u16, y: u16, ch: char, fg: Color| {
+            if x < cols && y < rows {
+                frame[y as usize * cols as usize + x as usize] = Glyph::new(ch, fg);
+            }
+        };
+        let lit = self.scheme().get_colors(1, 10, 0, Sweep::Horizontal);
+        for (x, ch) in "Pick a color scheme".chars().enumerate() {
+            put(x as u16 + 1, 0, ch, lit);
+        }
+        for (x, ch) in HINT.chars().enumerate() {
+            put(x as u16 + 1, rows.saturating_sub(1), ch, dim(lit, 0.6));
+        }
+
+        let (width, height) = self.tile_size();
+        for (i, tile) in self.tiles.iter().enumerate() {
+            let left = (i % self.across) as u16 * (width + 1) + 1;
+            let top = (i / self.across) as u16 * height + 1;
+            let color = if i == self.picked { lit } else { FRAME };
+            let (right, bottom) = (left + width.saturating_sub(1), top + height.saturating_sub(1));
+            for x in left..=right {
+                let (upper, lower) = match x {
+                    _ if x == left => ('┌', '└'),
+                    _ if x == right => ('┐', '┘'),
+                    _ => ('─', '─'),
+                };
+                put(x, top, upper, color);
+                put(x, bottom, lower, color);
+            }
+            for y in top + 1..bottom {
+                put(left, y, '│', color);
+                put(right, y, '│', color);
+            }
+            let name = format!(" {} ", ColorScheme::ALL[i].name());
+            for (x, ch) in name.chars().enumerate() {
+                if left + 2 + (x as u16) < right {
+                    put(left + 2 + x as u16, top, ch, color);
+                }
+            }
+
+            let (inner, glyphs) = (tile.size(), tile.frame());
+            for (idx, glyph) in glyphs.iter().enumerate() {
+                let (x, y) = ((idx % inner.0 as usize) as u16, (idx / inner.0 as usize) as u16);
+                // A double width glyph in the last column would cover the frame
+                let reach = if is_wide(glyph.ch) { x + 2 } else { x + 1 };
+                if reach < width.saturating_sub(1) && y + 1 < height.saturating_sub(1) && glyph.ch != ' ' {
+                    put(left + 1 + x, top + 1 + y, glyph.ch, glyph.fg);
+                }
+            }
+        }
+        frame
+    }
+
+    /// Each tile's size, frame included, with a column between tiles and
+    /// the title and keys above and below.
+    fn tile_size(&self) -> (u16, u16) {
+        let down = self.tiles.len().div_ceil(self.across) as u16;
+        let width = self.cols.saturating_sub(1) / self.across as u16;
+        let height = self.rows.saturating_sub(2) / down.max(1);
+        (width.saturating_sub(1), height)
+    }
+}
+
+/// Whether `ch` takes up two terminal columns, as katakana and the other
+/// East Asian scripts do.
+fn is_wide(ch: char) -> bool {
+    matches!(ch,
+        '\u{1100}'..='\u{115f}'
+        | '\u{2e80}'..='\u{a4cf}'
+        | '\u{ac00}'..='\u{d7a3}'
+        | '\u{f900}'..='\u{faff}'
+        | '\u{fe30}'..='\u{fe4f}'
+        | '\u{ff00}'..='\u{ff60}'
+        | '\u{ffe0}'..='\u{ffe6}')
+}
@@ -0,0 +1,1154 @@
+use std::{env, fmt::Display, ops::RangeInclusive, str::FromStr, time::Duration};
+#[cfg(not(target_arch = "wasm32"))]
+use std::path::PathBuf;
+#[cfg(feature = "plugins")]
+use std::rc::Rc;
+
+#[cfg(not(target_arch = "wasm32"))]
+use crate::config;
+#[cfg(feature = "lua")]
+use crate::lua::LuaHooks;
+#[cfg(feature = "image")]
+use crate::picture::Picture;
+#[cfg(feature = "plugins")]
+use crate::plugin::{self, Plugin};
+#[cfg(all(feature = "watch", not(target_arch = "wasm32")))]
+use crate::watch::ConfigWatch;
+use crate::{
+    color::{ColorScheme, Sweep},
+    countdown::Countdown,
+    drop::{glyph_set, Collisions, Direction, Distribution, Shimmer},
+    keymap::{Action, Keymap},
+    kiosk::Kiosk,
+    mask::Mask,
+    daylight::{Daylight, DEFAULT_CURVE},
+    dither::ColorDepth,
+    night::Night,
+    render::{Mirror, RenderMode, Style},
+    schedule::Schedule,
+    script::Timeline,
+    settings::{CursorEffect, Dilation, EffectKind, Rect, Segments, Settings, Split},
+    watermark,
+};
+
+/// Keys the running screensaver responds to, for `--help` and the `?`
+/// overlay. Rows whose actions `keymap` has moved list the keys they're on
+/// now.
+pub(crate) fn controls(keymap: &Keymap) -> Vec<(String, &'static str)> {
+    let mut rows: Vec<(&str, &[Action], &str)> = vec![
+        ("↑/↓", &[Action::SpeedUp, Action::SpeedDown], "Adjust speed (more/fewer frames per second)"),
+        ("←/→", &[Action::DensityDown, Action::DensityUp], "Adjust density (less/more drops)"),
+        ("+/-", &[Action::LengthUp, Action::LengthDown], "Adjust drop length"),
+        (
+            "1-6",
+            &[Action::Green, Action::Blue, Action::Red, Action::Purple, Action::Cyan, Action::Rainbow],
+            "Color schemes (green/blue/red/purple/cyan/rainbow)",
+        ),
+        ("w/W", &[Action::WindLeft, Action::WindRight], "Adjust wind (lean left/right)"),
+        ("{/}", &[Action::BrightnessDown, Action::BrightnessUp], "Adjust brightness (dimmer/brighter)"),
+        ("(/)", &[Action::GhostingDown, Action::GhostingUp], "Adjust ghosting (shorter/longer after-images)"),
+        ("u", &[Action::Reverse], "Reverse rain direction"),
+        ("g", &[Action::Shimmer], "Cycle shimmer (whole trail/head only/off)"),
+        ("d", &[Action::Debug], "Toggle debug overlay"),
+        ("f", &[Action::Fps], "Toggle frame rate overlay"),
+        ("b", &[Action::BulletTime], "Bullet time: slow the rain to a crawl for a few seconds"),
+        ("p", &[Action::Pause], "Pause/resume the animation"),
+        ("./,", &[Action::Step, Action::StepTen], "While paused, advance one/ten frames"),
+        ("Tab/[/]", &[Action::NextPreset, Action::PrevPreset], "Cycle through the presets"),
+        ("r", &[Action::Reset], "Reset speed, density, length, colors and wind to how they started"),
+        ("?", &[Action::Help], "Show these keys and the current settings"),
+    ];
+    #[cfg(not(target_arch = "wasm32"))]
+    rows.push((
+        "s/S",
+        &[Action::Screenshot, Action::ScreenshotPlain],
+        "Save the screen to ~/matrix-<timestamp>.ans/.txt",
+    ));
+    #[cfg(not(target_arch = "wasm32"))]
+    rows.push((
+        "Ctrl+S",
+        &[Action::SaveConfig],
+        "Save speed, density, length, colors and wind to the config file",
+    ));
+    #[cfg(feature = "clipboard")]
+    rows.push((
+        "y/Y",
+        &[Action::Copy, Action::CopyAnsi],
+        "Copy the screen to the clipboard (plain text/with colors)",
+    ));
+    rows.push(("q/Esc/Enter/Space/Ctrl+C", &[Action::Quit], "Quit"));
+    rows.into_iter()
+        .map(|(keys, actions, text)| {
+            if keymap.is_default(actions) {
+                (keys.to_string(), text)
+            } else {
+                (keymap.describe(actions), text)
+            }
+        })
+        .collect()
+}
+
+/// A preset's name and the options it stands for.
+pub(crate) type Preset = (String, Vec<String>);
+
+/// Built-in `--preset`s and the options each stands for.
+pub(crate) const PRESETS: &[(&str, &[&str])] = &[
+    ("gentle", &["-s", "40", "-d", "20", "-n", "3", "-l", "20"]),
+    ("sparse", &["-s", "50", "-d", "10", "-n", "2", "-l", "15"]),
+    ("chaos", &["-s", "5", "-d", "90", "-n", "15", "-l", "45", "-c", "rainbow"]),
+];
+
+fn print_help() {
+    println!("Matrix Rain Terminal Screensaver");
+    println!();
+    println!("USAGE: matrix [OPTIONS]");
+    println!("       matrix serve [--port PORT] [OPTIONS]  Stream the rain over telnet");
+    println!("                                             (default port: 2323)");
+    println!("       matrix play <FILE.cast> [--speed FACTOR]  Replay a --record session");
+    #[cfg(unix)]
+    println!("       matrix idle [--after DURATION] [OPTIONS] [-- COMMAND...]  Run your shell and");
+    #[cfg(unix)]
+    println!("                     rain over it after DURATION (default: 5m) without a key");
+    println!("       matrix banner [--width N] [--height N] [--text TEXT] [--no-color] [OPTIONS]");
+    println!("                     Print a rain still with text in it, for SSH banners");
+    println!("       matrix motd [--rows N] [--width N] [--host NAME] [--no-color] [OPTIONS]");
+    println!("                     Print rain with this machine's name in big glyphs, for /etc/motd");
+    println!("       matrix colors [--width N] [--colors DEPTH]");
+    println!("                     List the color schemes, each with a swatch from head to tail");
+    println!("       matrix simulate [--frames N] [--width N] [--height N] [OPTIONS]");
+    println!("                     Run headless, checking invariants, and print a summary");
+    println!("       matrix bench [--frames N] [--width N] [--height N] [OPTIONS]");
+    println!("                     Time a fixed heavy workload and report frame statistics");
+    #[cfg(feature = "export")]
+    println!("       matrix export [--frames N] [--out DIR] [--size COLSxROWS] [--cell WxH]");
+    #[cfg(feature = "export")]
+    println!("                     [--font FILE] [OPTIONS]  Render frames to numbered PNGs");
+    println!();
+    println!("OPTIONS: (values can also be given as --option=value)");
+    println!("      --preset <NAME>    Start from a preset: gentle, sparse, chaos, or one from");
+    println!("                         the config file; later options override it");
+    println!("  -e, --effect <NAME>    Animation: rain, snow, sakura (default: rain)");
+    println!("      --fps <N>          Frames per second, e.g. 24, 30 or 60 (default: 20)");
+    println!("  -s, --speed <MS>       Frame delay in ms instead of --fps (lower = faster)");
+    println!("      --sim-rate <HZ>    Simulate in HZ steps a second, however often it's drawn;");
+    println!("                         drops keep their speed (default: a step a frame)");
+    println!("  -d, --density <0-100>  Target screen coverage percentage (default: 40)");
+    println!("  -n, --spawns <N>       Max spawns per frame (default: 4)");
+    println!("  -l, --length <N>       Max drop length (default: 30)");
+    println!("      --min-length <N>   Min drop length, up to --length (default: 10)");
+    println!("      --speed-range <MIN:MAX>  Frames each drop waits per cell, picked per drop;");
+    println!("                         lower is faster (default: 2:4)");
+    println!("      --length-distribution <NAME>  How drop lengths spread between --min-length");
+    println!("                         and --length: uniform, normal, or heavy-tail for mostly");
+    println!("                         short drops and the odd very long one (default: uniform)");
+    println!("      --speed-distribution <NAME>  The same for --speed-range; heavy-tail gives");
+    println!("                         mostly fast drops and the odd slow one (default: uniform)");
+    println!("  -c, --color <SCHEME>   Color: green, blue, red, purple, cyan, rainbow");
+    println!("      --rainbow-axis <AXIS>  Rainbow hues run horizontal, vertical, diagonal, or");
+    println!("                         cycle over time (default: horizontal)");
+    println!("  -m, --max-drops <N>    Max concurrently active drops (default: 2000)");
+    println!("  -w, --wind <-10..10>   Sideways drift, negative = left (default: 0)");
+    println!("  -a, --angle <DEG>      Fall angle from vertical, -60 to 60 (default: 0)");
+    println!("      --direction <DIR>  Direction: down, up, left, right (default: down)");
+    println!("      --mirror <AXIS>    Mirror the rain: vertical, horizontal");
+    println!("      --gravity [1-10]   Drops accelerate as they fall (default: 3)");
+    println!("      --branch-chance <0-100>  Chance a drop splits in two (default: 0)");
+    println!("      --dissolve-chance <0-100>  Chance a drop stops in mid-air and evaporates, tail");
+    println!("                         first, after a random lifetime (default: 0)");
+    println!("      --gaps <0-100>     Chance a drop's trail has dark gaps in it (default: 0)");
+    println!("      --layers           Add a dim, slow background layer for depth");
+    println!("      --column-speeds    Give each column one speed its drops share, so they never overrun each other");
+    println!("      --shimmer <RATE>   Glyphs a drop changes per step on average, 0 = none (default: 0.5)");
+    println!("      --shimmer-head     Only let the glyphs nearest the head shimmer");
+    println!("      --charset <SET>    Glyphs drops are made of: katakana, latin, digits, symbols,");
+    println!("                         or your own characters (default: all of those)");
+    println!("      --back-charset <SET>  Glyphs for the --layers background drops (default: as --charset)");
+    println!("      --head-glyphs <SET>  Glyphs for drop heads: katakana, latin, digits, symbols, or your own");
+    println!("      --head-mutate <0-100>  Chance a head changes glyph each step, apart from the shimmer");
+    println!("      --head-style <STYLE>  Drop heads: plain, underline, blink (default: plain)");
+    println!("      --collisions <WAY> When a drop catches up with the one ahead: pass, merge, block (default: pass)");
+    println!("      --backdrop         Fill the screen with faint, twinkling static glyphs");
+    println!("      --inverse          Fill the screen with dim glyphs that the drops cut dark");
+    println!("                         trails through: black heads, fading back in behind");
+    println!("      --glow             Bloom around drop heads (costs extra draw calls)");
+    println!("      --transparent      Clear fading cells instead of darkening them, so a");
+    println!("                         translucent or picture background shows through");
+    println!("      --glitch <PER_MIN> Briefly corrupt a band of rows now and then (default: 0)");
+    println!("      --lightning [PER_MIN]  Rare white flashes over the rain (default: 4)");
+    println!("      --stuck-glyphs [PER_MIN]  Now and then a glyph freezes until swept (default: 2)");
+    println!("      --persistence <0-99>  How much of its light a cell keeps each frame after a drop");
+    println!("                         passes: 0 clears at once, 90+ leaves long after-images (default: 0)");
+    println!("      --splash [CELLS]   Drops landing splash dim glyphs CELLS to each side (default: 3)");
+    println!("      --reduced-motion   Slow, steady drops with soft fading trails, and no shimmer,");
+    println!("                         glitches or flashes, whatever else asks for them");
+    println!("      --hires            Half-block rendering at double vertical resolution");
+    println!("      --braille          Braille dot rendering at 2x4 resolution per cell");
+    println!("      --linux-console    16 colors and ASCII glyphs for a bare Linux console; on");
+    println!("                         by itself where TERM=linux, --no-linux-console turns it off");
+    println!("      --colors <DEPTH>   Colors the terminal shows: truecolor, 256 or 16; fewer are dithered");
+    println!("      --oled             Burn-in protection: slowly vary brightness, swap bright and");
+    println!("                         dim now and then, and sweep a dark band across the screen");
+    println!("      --night <HH:MM-HH:MM>  Dim the rain during these hours, e.g. 22:00-07:00");
+    println!("      --night-warm       Warmer, amber colors during the --night hours too");
+    println!("      --brightness <0-100>  Dim the whole screen, in percent of its light (default: 100)");
+    println!("      --time-of-day [CURVE]  Density and speed follow the clock along CURVE,");
+    println!("                         HH:MM=DENSITY[/FPS] points (default: {})", DEFAULT_CURVE);
+    println!("      --weather <LOCATION>  Follow the real weather at LOCATION, from wttr.in");
+    println!("      --storm [SECS]     Let the rain swell and calm on a cycle (default: 120)");
+    println!("      --demo [SECS]      Attract mode: wander to a new look every SECS (default: 20)");
+    println!("      --rotate <MINUTES> Take turns with rain, snow and sakura, MINUTES each, wiping");
+    println!("                         one away down the screen before the next");
+    println!("      --split <SCHEME>[:<0-100>]  Second half of the screen with its own");
+    println!("                         color scheme and density, e.g. --split red:70");
+    println!("      --segments <N>[,gutters][,schemes]  Cut a wide screen into N panels that rain");
+    println!("                         on their own, with blank columns between and a scheme each");
+    println!("      --hole <X,Y,W,H>   Keep a rectangle of cells free of rain (repeatable)");
+    println!("      --dilate <X,Y,W,H:SPEED>  Run time at SPEED times as fast in a rectangle,");
+    println!("                         so drops slow down through it or hurry (repeatable)");
+    println!("      --mask <FILE>      Rain heavier where a PGM image or text picture is bright,");
+    println!("                         sparser where it's dark, stretched across the screen");
+    println!("      --qr <TEXT|URL>    Rain gradually fills in a scannable QR code");
+    println!("      --script <FILE>    Play a TOML timeline of scene changes and messages");
+    println!("      --at <WHEN EVENT>  At a time of day, HH:MM with * or */N for either, or hourly:");
+    println!("                         burst, flash [SCHEME] or message TEXT (repeatable)");
+    println!("      --kiosk <FILE>     Show FILE's lines one by one as big messages over the rain");
+    println!("      --countdown <TIME> Count down to TIME, YYYY-MM-DDTHH:MM:SS local or with Z");
+    println!("                         for UTC, in big digits; the screen flashes at zero");
+    println!("      --label <TEXT>     What the countdown turns into at zero");
+    println!("      --watermark [TEXT] Set TEXT, or user@host, faintly into the rain near the");
+    println!("                         bottom right corner, to tell shared machines apart");
+    println!("      --kiosk-lock       Ignore all keys and the mouse but Ctrl+Alt+Q, which quits");
+    println!("      --forever          If the terminal fails or goes away, wait for it and start over");
+    println!("      --no-animation     Start and stop at once, without fading in or dissolving out");
+    println!("      --intro            Type out \"Wake up, Neo...\" on a black screen before the rain");
+    println!("      --chooser          Start by picking the color scheme from tiles of each one raining");
+    println!("      --hollywood        Movie hacking: code, hex dumps and ACCESS GRANTED flash up");
+    println!("      --config <FILE>    Read options from FILE instead of");
+    println!("                         ~/.config/matrix-rain/config.toml");
+    println!("      --no-config        Ignore the config file");
+    #[cfg(feature = "watch")]
+    println!("      --watch            Apply edits to the config file as soon as it's saved");
+    println!("      --record <FILE>    Record the session to an asciicast v2 file for asciinema");
+    println!("      --seed <N>         Seed the randomness so runs with the same settings and");
+    println!("                         size play out identically");
+    println!("      --sync-server [ADDR]  Lead the rain for --sync-client terminals, listening");
+    println!("                         on ADDR or a port (default: 0.0.0.0:4747)");
+    println!("      --sync-client <ADDR>  Show the same rain as the --sync-server at ADDR");
+    println!("      --low-power [CPU_PCT]  Save battery: at most 10 fps, slower still to stay");
+    println!("                         under a CPU ceiling (default: 2)");
+    println!("      --profile <FILE>   Log per-frame update/draw/flush/sleep times to a CSV file");
+    println!("      --static           Print one colored frame to stdout and exit (for MOTDs)");
+    println!("      --lines <N>        Height of the --static frame (default: terminal height)");
+    #[cfg(feature = "battery")]
+    println!("      --no-battery-saver  Keep full frame rate and density on battery power");
+    #[cfg(feature = "image")]
+    println!("      --image <PATH>     Tint the rain with a picture so it paints the image");
+    #[cfg(feature = "http")]
+    println!("      --serve-http <ADDR>  Serve a page and WebSocket stream of the rain, e.g. 0.0.0.0:8080");
+    #[cfg(feature = "lua")]
+    println!("      --lua <FILE>       Lua hooks for spawning and coloring drops");
+    #[cfg(feature = "plugins")]
+    println!("      --list-plugins     List plugin effects and color schemes, then exit");
+    #[cfg(feature = "sound")]
+    println!("      --sound [VOLUME]   Soft generated rain sounds at VOLUME 1-100 (default: 30)");
+    println!("      --debug            Show the debug overlay (active drop count)");
+    println!("      --show-fps         Show frame rate, frame time and drop count in a corner");
+    println!("      --status-bar       Keep the bottom row for scheme, fps, density, drops and time");
+    println!("      --reserve-bottom <N>  Rain above the bottom N rows and leave them and the");
+    println!("                         keyboard to the shell; run it in the background with &");
+    println!("      --width <N>        Rain N columns wide whatever the terminal's size, for");
+    println!("                         recording or piping; COLUMNS stands in if it can't say");
+    println!("      --height <N>       Rain N rows tall whatever the terminal's size; LINES");
+    println!("                         stands in if it can't say");
+    println!("      --mouse            Click to start a burst of drops, drag to paint rain; the");
+    println!("                         wheel changes the speed and Shift+wheel the density");
+    println!("      --cursor-effect <NAME>  spotlight: light up the rain around the mouse");
+    println!("                         pointer; avoid: drops bend around it");
+    println!("      --reactive         Every key also starts a drop, in a column of its own");
+    println!("      --vim              h/l and j/k change density and speed like the arrows, and");
+    println!("                         Space pauses instead of quitting");
+    println!("      --exit-on-any-key  Quit on any key, like a screensaver, instead of the controls");
+    println!("      --exit-on-mouse    Quit when the mouse moves or clicks in the terminal,");
+    println!("                         with exit code 3");
+    println!("      --grace <SECONDS>  Ignore keys but Ctrl+C for this long after starting, so the");
+    println!("                         keystroke that launched the screensaver doesn't end it");
+    println!("      --lock [PHRASE]    Only quit once PHRASE (default: your username) is typed");
+    println!("                         and Enter pressed; other keys do nothing");
+    println!("      --no-ctrl-c        Don't quit on Ctrl+C, e.g. so --lock can't be skipped");
+    println!("      --while <CMD>      Run CMD in the background and rain until it finishes, then");
+    println!("                         show its output and exit with its exit code");
+    println!("      --on-exit <CMD>    Run CMD once the terminal is restored");
+    println!("      --timeout <SECONDS>  Quit by itself after this long, with exit code 124");
+    println!("      --ramp <SECONDS>   Start slow and sparse, building to full speed over SECONDS,");
+    println!("                         and wind down the same way before --timeout runs out");
+    println!("      --print-key        Print the name of the key that quit, e.g. q or Ctrl+C");
+    println!("      --no-title         Leave the terminal's title alone instead of naming the");
+    println!("                         color scheme and frame rate in it");
+    println!("      --notify           Show --timeout's progress in the tab, and notify when it or");
+    println!("                         the --while command is done, in terminals that can");
+    println!("      --alert <WAYS>     When --timeout or --countdown is done, any of bell, notify");
+    println!("                         (OSC 9) and osc777 (OSC 777), comma separated");
+    println!("      --on-done <CMD>    Start CMD when --timeout or --countdown is done");
+    #[cfg(target_os = "linux")]
+    println!("      --on-lock          Wait, and rain whenever the desktop session is locked");
+    println!("  -h, --help             Show this help");
+    println!();
+    println!("RUNTIME CONTROLS:");
+    for (keys, action) in controls(&Keymap::default()) {
+        println!("  {:<11} {}", keys, action);
+    }
+    println!();
+    println!("PRESETS (--preset <NAME>, or define more in the config file):");
+    for (name, options) in PRESETS {
+        println!("  {:<9} {}", name, options.join(" "));
+    }
+}
+
+/// `args` with `--option=value` split in two, and each `--preset NAME`
+/// swapped for the options it stands for, so options after it override
+/// them. `user` presets come before the built-in ones.
+fn expand(args: &[String], user: &[Preset]) -> Result<Vec<String>, String> {
+    let mut split = vec![];
+    for arg in args {
+        match arg.split_once('=') {
+            Some((flag, val)) if flag.starts_with("--") => {
+                split.extend([flag.to_string(), val.to_string()])
+            }
+            _ => split.push(arg.clone()),
+        }
+    }
+    let args = &split;
+    let mut expanded = vec![];
+    let mut i = 0;
+    while i < args.len() {
+        match (args[i].as_str(), args.get(i + 1)) {
+            ("--preset", Some(name)) => {
+                let name = name.to_lowercase();
+                if let Some((_, options)) = user.iter().find(|(user, _)| user.to_lowercase() == name) {
+                    expanded.extend(options.iter().cloned());
+                } else if let Some((_, options)) = PRESETS.iter().find(|(preset, _)| *preset == name) {
+                    expanded.extend(options.iter().map(|option| option.to_string()));
+                } else {
+                    return Err(format!("unknown preset '{}'", name));
+                }
+                i += 2;
+            }
+            _ => {
+                expanded.push(args[i].clone());
+                i += 1;
+            }
+        }
+    }
+    Ok(expanded)
+}
+
+pub fn parse_args() -> Settings {
+    let args: Vec<String> = env::args().skip(1).collect();
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        #[cfg(feature = "watch")]
+        let given = args.clone();
+        let (args, presets, keymap) = with_config(args);
+        let mut settings = parse(&args);
+        settings.presets = presets;
+        settings.keymap = keymap;
+        #[cfg(feature = "watch")]
+        if given.iter().any(|arg| arg == "--watch") {
+            match ConfigWatch::start(given) {
+                Ok(watch) => settings.watch = Some(watch),
+                Err(err) => {
+                    eprintln!("matrix: {}", err);
+                    std::process::exit(1);
+                }
+            }
+        }
+        settings
+    }
+    #[cfg(target_arch = "wasm32")]
+    parse(&args)
+}
+
+/// Every preset, the user's first, for cycling through at runtime.
+pub(crate) fn all_presets(user: &[Preset]) -> Vec<Preset> {
+    let mut presets = user.to_vec();
+    for (name, options) in PRESETS {
+        if !presets.iter().any(|(user, _)| user.eq_ignore_ascii_case(name)) {
+            let options = options.iter().map(|option| option.to_string()).collect();
+            presets.push((name.to_string(), options));
+        }
+    }
+    presets
+}
+
+/// `args` with the config file's options in front, so the command line
+/// overrides them, and the presets and runtime keys the file defines.
+/// `--config <FILE>` reads another file and `--no-config` none at all.
+#[cfg(not(target_arch = "wasm32"))]
+fn with_config(args: Vec<String>) -> (Vec<String>, Vec<Preset>, Keymap) {
+    if args.iter().any(|arg| arg == "--no-config") {
+        return (args, vec![], Keymap::default());
+    }
+    let Some(path) = config_file(&args) else {
+        return (args, vec![], Keymap::default());
+    };
+    if !path.exists() {
+        // Only the default file is optional; one asked for by name must exist
+        if args.iter().any(|arg| arg == "--config") {
+            eprintln!("matrix: config file {} not found", path.display());
+            eprintln!("Try 'matrix --help' for the options.");
+            std::process::exit(2);
+        }
+        return (args, vec![], Keymap::default());
+    }
+    match config::load(&path) {
+        Ok((mut options, presets, keymap)) => {
+            options.extend(args);
+            match expand(&options, &presets) {
+                Ok(options) => (options, presets, keymap),
+                Err(err) => {
+                    eprintln!("matrix: {}", err);
+                    eprintln!("Try 'matrix --help' for the options.");
+                    std::process::exit(2);
+                }
+            }
+        }
+        Err(err) => {
+            eprintln!("matrix: could not load config {}: {}", path.display(), err);
+            eprintln!("Try 'matrix --help' for the options.");
+            std::process::exit(2);
+        }
+    }
+}
+
+/// The config file `args` names with `--config`, or else the default one.
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn config_file(args: &[String]) -> Option<PathBuf> {
+    let given = args.iter().position(|arg| arg == "--config").and_then(|i| args.get(i + 1));
+    given.map(PathBuf::from).or_else(config::path)
+}
+
+/// The settings the config file at `path` and the command line `args` make
+/// now, for `--watch`; unlike at startup, a mistake is returned, not fatal.
+#[cfg(all(feature = "watch", not(target_arch = "wasm32")))]
+pub(crate) fn reload(path: &PathBuf, args: &[String]) -> Result<Settings, String> {
+    let (mut options, presets, keymap) = config::load(path)?;
+    options.extend(args.iter().cloned());
+    let mut settings = try_parse(&expand(&options, &presets)?)?;
+    settings.presets = presets;
+    settings.keymap = keymap;
+    Ok(settings)
+}
+
+/// Walks a command line, handing out the values that follow options.
+struct Args<'a> {
+    args: &'a [String],
+    i: usize,
+}
+
+impl<'a> Args<'a> {
+    /// The value after `flag`.
+    fn value(&mut self, flag: &str) -> Result<&'a str, String> {
+        self.i += 1;
+        match self.args.get(self.i) {
+            Some(val) => Ok(val),
+            None => Err(format!("{} needs a value", flag)),
+        }
+    }
+
+    /// The number after `flag`, which must be within `range`.
+    fn number<T>(&mut self, flag: &str, range: RangeInclusive<T>) -> Result<T, String>
+    where
+        T: FromStr + PartialOrd + Display,
+    {
+        let val = self.value(flag)?;
+        match val.parse::<T>() {
+            Ok(number) if range.contains(&number) => Ok(number),
+            _ => Err(format!(
+                "invalid value '{}' for {}: expected a number from {} to {}",
+                val,
+                flag,
+                range.start(),
+                range.end()
+            )),
+        }
+    }
+
+    /// The number after `flag`, which must be at least `min`.
+    fn at_least<T>(&mut self, flag: &str, min: T) -> Result<T, String>
+    where
+        T: FromStr + PartialOrd + Display,
+    {
+        let val = self.value(flag)?;
+        match val.parse::<T>() {
+            Ok(number) if number >= min => Ok(number),
+            _ => Err(format!(
+                "invalid value '{}' for {}: expected a number of at least {}",
+                val, flag, min
+            )),
+        }
+    }
+
+    /// The number after an option whose value can be left out, if the next
+    /// argument is one.
+    fn optional<T>(&mut self, flag: &str, range: RangeInclusive<T>) -> Result<Option<T>, String>
+    where
+        T: FromStr + PartialOrd + Display,
+    {
+        match self.args.get(self.i + 1).map(|val| val.parse::<T>()) {
+            Some(Ok(_)) => self.number(flag, range).map(Some),
+            _ => Ok(None),
+        }
+    }
+}
+
+/// Settings for a command line, not counting the program name. Exits with
+/// a message saying what's wrong if it doesn't make sense.
+pub fn parse(args: &[String]) -> Settings {
+    match try_parse(args) {
+        Ok(settings) if settings.help => {
+            print_help();
+            std::process::exit(0);
+        }
+        #[cfg(feature = "plugins")]
+        Ok(settings) if settings.list_plugins => {
+            list_plugins();
+            std::process::exit(0);
+        }
+        Ok(settings) => settings,
+        Err(err) => {
+            eprintln!("matrix: {}", err);
+            eprintln!("Try 'matrix --help' for the options.");
+            std::process::exit(2);
+        }
+    }
+}
+
+/// Each plugin found, with what it provides, for `--list-plugins`.
+#[cfg(feature = "plugins")]
+fn list_plugins() {
+    for plugin in plugin::discover() {
+        let mut provides = vec![];
+        if plugin.has_effect() {
+            provides.push("effect");
+        }
+        if plugin.has_color() {
+            provides.push("color scheme");
+        }
+        println!("{} ({})", plugin.name, provides.join(", "));
+    }
+}
+
+/// Settings for a command line, or what's wrong with it, starting from
+/// what suits the terminal we're in. `--help` and `--list-plugins` only
+/// ask for it in the settings; printing and exiting is up to the caller.
+pub fn try_parse(args: &[String]) -> Result<Settings, String> {
+    let settings = Settings {
+        // A bare virtual console, say on a server without a desktop
+        linux_console: env::var("TERM").is_ok_and(|term| term == "linux"),
+        // Terminal.app has the 256-color palette but no 24-bit color
+        colors: match env::var("TERM_PROGRAM") {
+            Ok(program) if program == "Apple_Terminal" => ColorDepth::Ansi256,
+            _ => ColorDepth::TrueColor,
+        },
+        ..Settings::default()
+    };
+    parse_onto(args, settings)
+}
+
+/// Like `try_parse`, but from the plain defaults, for a program embedding
+/// the rain, whose environment says nothing about where it's drawn.
+pub fn try_parse_embedded(args: &[String]) -> Result<Settings, String> {
+    parse_onto(args, Settings::default())
+}
+
+/// `settings` with the command line `args` applied.
+fn parse_onto(args: &[String], mut settings: Settings) -> Result<Settings, String> {
+    let args = &expand(args, &[])?[..];
+    let mut label = None; // For --countdown, wherever that comes
+    // Plugins are only loaded once a name isn't built in
+    #[cfg(feature = "plugins")]
+    let mut plugins: Option<Vec<Rc<Plugin>>> = None;
+    #[cfg(feature = "plugins")]
+    let mut find_plugin = |name: &str| {
+        plugins
+            .get_or_insert_with(plugin::discover)
+            .iter()
+            .find(|plugin| plugin.name.eq_ignore_ascii_case(name))
+            .cloned()
+    };
+
+    let mut args = Args { args, i: 0 };
+    while let Some(arg) = args.args.get(args.i) {
+        let flag = arg.as_str();
+        match flag {
+            "-h" | "--help" => {
+                settings.help = true;
+                return Ok(settings);
+            }
+            "-s" | "--speed" => {
+                let ms = args.number(flag, 1..=10_000)?;
+                settings.frame_delay = Duration::from_millis(ms);
+            }
+            "--fps" => {
+                let fps = args.number(flag, 1.0..=1000.0)?;
+                settings.frame_delay = Duration::from_secs_f64(1.0 / fps);
+            }
+            "--sim-rate" => {
+                settings.sim_rate = Some(args.number(flag, 1.0..=1000.0)?);
+            }
+            "-e" | "--effect" => {
+                let val = args.value(flag)?;
+                match EffectKind::from_str(val) {
+                    Some(effect) => settings.effect = effect,
+                    #[cfg(feature = "plugins")]
+                    None if find_plugin(val).is_some_and(|plugin| plugin.has_effect()) => {
+                        settings.effect_plugin = find_plugin(val);
+                    }
+                    None => {
+                        return Err(format!(
+                            "unknown effect '{}': expected rain, snow or sakura",
+                            val
+                        ))
+                    }
+                }
+            }
+            "-d" | "--density" => {
+                settings.density = args.number(flag, 1.0..=100.0)? / 100.0;
+            }
+            "-n" | "--spawns" => {
+                settings.spawns_per_frame = args.number(flag, 1..=1000)?;
+            }
+            "-l" | "--length" => {
+                settings.max_length = args.number(flag, 1..=200)?;
+            }
+            "--min-length" => {
+                settings.min_length = args.number(flag, 1..=200)?;
+            }
+            "--speed-range" => {
+                let val = args.value(flag)?;
+                let range = val
+                    .split_once(':')
+                    .and_then(|(min, max)| Some((min.parse::<u8>().ok()?, max.parse::<u8>().ok()?)));
+                match range {
+                    Some((min, max)) if min >= 1 && min <= max => {
+                        settings.min_speed = min;
+                        settings.max_speed = max;
+                    }
+                    _ => {
+                        return Err(format!(
+                            "invalid value '{}' for --speed-range: expected MIN:MAX, \
+                             from 1 to 255 frames per cell with MIN no more than MAX",
+                            val
+                        ))
+                    }
+                }
+            }
+            "--length-distribution" | "--speed-distribution" => {
+                let val = args.value(flag)?;
+                let distribution = Distribution::from_str(val).ok_or_else(|| {
+                    format!("unknown distribution '{}' for {}: expected uniform, normal or heavy-tail", val, flag)
+                })?;
+                match flag {
+                    "--length-distribution" => settings.length_distribution = distribution,
+                    _ => settings.speed_distribution = distribution,
+                }
+            }
+            "-m" | "--max-drops" => {
+                settings.max_drops = args.number(flag, 1..=1_000_000)?;
+            }
+            "-w" | "--wind" => {
+                settings.wind = args.number(flag, -10.0..=10.0)? / 10.0;
+            }
+            "-a" | "--angle" => {
+                settings.angle = args.number(flag, -60.0..=60.0)?;
+            }
+            "--direction" => {
+                let val = args.value(flag)?;
+                settings.direction = Direction::from_str(val).ok_or_else(|| {
+                    format!(
+                        "unknown direction '{}': expected down, up, left or right",
+                        val
+                    )
+                })?;
+            }
+            "--mirror" => {
+                let val = args.value(flag)?;
+                let mirror = Mirror::from_str(val).ok_or_else(|| {
+                    format!(
+                        "unknown mirror axis '{}': expected vertical or horizontal",
+                        val
+                    )
+                })?;
+                settings.mirror = Some(mirror);
+            }
+            "--gravity" => {
+                let strength = args.optional(flag, 1.0..=10.0)?.unwrap_or(3.0);
+                settings.gravity = strength * 0.002;
+            }
+            "--branch-chance" => {
+                settings.branch_chance = args.number(flag, 0.0..=100.0)? / 100.0;
+            }
+            "--dissolve-chance" => {
+                settings.dissolve_chance = args.number(flag, 0.0..=100.0)? / 100.0;
+            }
+            "--gaps" => {
+                settings.gap_chance = args.number(flag, 0.0..=100.0)? / 100.0;
+            }
+            "--layers" => {
+                settings.layers = true;
+            }
+            "--column-speeds" => {
+                settings.column_speeds = true;
+            }
+            "--shimmer" => {
+                settings.shimmer = args.number(flag, 0.0..=10.0)?;
+            }
+            "--shimmer-head" => {
+                settings.shimmer_zone = Shimmer::Head;
+            }
+            "--charset" | "--back-charset" => {
+                let val = args.value(flag)?;
+                let charset = glyph_set(val).ok_or_else(|| format!("{} needs a set or some characters", flag))?;
+                match flag {
+                    "--charset" => settings.charset = Some(charset.into()),
+                    _ => settings.back_charset = Some(charset.into()),
+                }
+            }
+            "--head-glyphs" => {
+                let val = args.value(flag)?;
+                settings.head_glyphs = Some(glyph_set(val).ok_or("--head-glyphs needs a set or some characters")?);
+            }
+            "--head-mutate" => {
+                settings.head_mutate = Some(args.number(flag, 0.0..=100.0)? / 100.0);
+            }
+            "--head-style" => {
+                let val = args.value(flag)?;
+                settings.head_style = Style::from_str(val)
+                    .ok_or_else(|| format!("unknown head style '{}': expected plain, underline or blink", val))?;
+            }
+            "--collisions" => {
+                let val = args.value(flag)?;
+                settings.collisions = Collisions::from_str(val).ok_or_else(|| {
+                    format!("unknown collision policy '{}': expected pass, merge or block", val)
+                })?;
+            }
+            "--backdrop" => {
+                settings.backdrop = true;
+            }
+            "--inverse" => {
+                settings.inverse = true;
+            }
+            "--glow" => {
+                settings.glow = true;
+            }
+            "--transparent" => {
+                settings.transparent = true;
+            }
+            "--glitch" => {
+                settings.glitch = args.at_least(flag, 0.0)?;
+            }
+            "--lightning" => {
+                settings.lightning = args.optional(flag, 0.0..=600.0)?.unwrap_or(4.0);
+            }
+            "--stuck-glyphs" => {
+                settings.stuck_glyphs = args.optional(flag, 0.0..=600.0)?.unwrap_or(2.0);
+            }
+            "--reduced-motion" => {
+                settings.reduced_motion = true;
+            }
+            "--hires" => {
+                settings.render_mode = RenderMode::HalfBlock;
+            }
+            "--braille" => {
+                settings.render_mode = RenderMode::Braille;
+            }
+            "--linux-console" => {
+                settings.linux_console = true;
+            }
+            "--no-linux-console" => {
+                settings.linux_console = false;
+            }
+            "--colors" => {
+                let val = args.value(flag)?;
+                settings.colors = ColorDepth::from_str(val).ok_or_else(|| {
+                    format!("unknown color depth '{}': expected truecolor, 256 or 16", val)
+                })?;
+            }
+            "--oled" => {
+                settings.oled = true;
+            }
+            "--night" => {
+                let val = args.value(flag)?;
+                let night = Night::from_str(val).ok_or_else(|| {
+                    format!("invalid value '{}' for --night: expected HH:MM-HH:MM", val)
+                })?;
+                settings.night = Some(night);
+            }
+            "--night-warm" => {
+                settings.night_warm = true;
+            }
+            "--persistence" => {
+                settings.persistence = args.number(flag, 0.0..=99.0)? / 100.0;
+            }
+            "--splash" => {
+                settings.splash = Some(args.optional(flag, 1..=20)?.unwrap_or(3));
+            }
+            "--brightness" => {
+                settings.brightness = args.number(flag, 0.0..=100.0)? / 100.0;
+            }
+            "--time-of-day" => {
+                let curve = match args.args.get(args.i + 1) {
+                    Some(curve) if curve.contains('=') => args.value(flag)?,
+                    _ => DEFAULT_CURVE,
+                };
+                let daylight = Daylight::from_str(curve).ok_or_else(|| {
+                    format!(
+                        "invalid value '{}' for --time-of-day: expected HH:MM=DENSITY[/FPS],...",
+                        curve
+                    )
+                })?;
+                settings.time_of_day = Some(daylight);
+            }
+            "--storm" => {
+                settings.storm_period = Some(args.optional(flag, 1..=86_400)?.unwrap_or(120));
+            }
+            "--demo" => {
+                settings.demo = Some(args.optional(flag, 5..=3600)?.unwrap_or(20));
+            }
+            "--rotate" => {
+                settings.rotate = Some(Duration::from_secs_f64(args.number(flag, 0.1..=1440.0)? * 60.0));
+            }
+            "--split" => {
+                let val = args.value(flag)?;
+                let (scheme, density) = match val.split_once(':') {
+                    Some((scheme, density)) => (scheme, Some(density)),
+                    None => (val, None),
+                };
+                let color_scheme = ColorScheme::from_str(scheme)
+                    .ok_or_else(|| format!("unknown color scheme '{}' for --split", scheme))?;
+                let density = match density {
+                    Some(pct) => match pct.parse::<f64>() {
+                        Ok(pct) if (1.0..=100.0).contains(&pct) => Some(pct / 100.0),
+                        _ => {
+                            return Err(format!(
+                                "invalid density '{}' for --split: expected a number from 1 to 100",
+                                pct
+                            ))
+                        }
+                    },
+                    None => None,
+                };
+                settings.split = Some(Split {
+                    color_scheme,
+                    density,
+                });
+            }
+            "--segments" => {
+                let val = args.value(flag)?;
+                let segments = Segments::from_str(val).ok_or_else(|| {
+                    format!("invalid value '{}' for --segments: expected 2-16, then optionally ,gutters and ,schemes", val)
+                })?;
+                settings.segments = Some(segments);
+            }
+            "--hole" => {
+                let val = args.value(flag)?;
+                let hole = Rect::from_str(val).ok_or_else(|| {
+                    format!("invalid value '{}' for --hole: expected X,Y,W,H", val)
+                })?;
+                settings.holes.push(hole);
+            }
+            "--dilate" => {
+                let val = args.value(flag)?;
+                let zone = Dilation::from_str(val).ok_or_else(|| {
+                    format!("invalid value '{}' for --dilate: expected X,Y,W,H:SPEED, SPEED from 0.05 to 10", val)
+                })?;
+                settings.dilations.push(zone);
+            }
+            "--mask" => {
+                settings.mask = Some(Mask::load(args.value(flag)?)?);
+            }
+            "--at" => {
+                let val = args.value(flag)?;
+                settings
+                    .schedule
+                    .get_or_insert_with(Schedule::new)
+                    .add(val)
+                    .map_err(|err| format!("invalid value '{}' for --at: {}", val, err))?;
+            }
+            "--qr" => {
+                settings.qr = Some(args.value(flag)?.to_string());
+            }
+            // Handled before parsing, by `with_config`
+            "--config" => {
+                args.value(flag)?;
+            }
+            "--no-config" => {}
+            #[cfg(feature = "watch")]
+            "--watch" => {
+                // Started once the settings are made, by `parse_args`
+            }
+            "--script" => {
+                let val = args.value(flag)?;
+                let timeline = Timeline::load(val)
+                    .map_err(|err| format!("could not load script {}: {}", val, err))?;
+                settings.script = Some(timeline);
+            }
+            "--kiosk" => {
+                let val = args.value(flag)?;
+                let kiosk = Kiosk::load(val)
+                    .map_err(|err| format!("could not load kiosk messages {}: {}", val, err))?;
+                settings.kiosk = Some(kiosk);
+            }
+            "--countdown" => {
+                let val = args.value(flag)?;
+                let countdown = Countdown::from_str(val).ok_or_else(|| {
+                    format!("invalid value '{}' for --countdown: expected YYYY-MM-DDTHH:MM:SS", val)
+                })?;
+                settings.countdown = Some(countdown);
+            }
+            "--watermark" => {
+                let text = match args.args.get(args.i + 1) {
+                    Some(text) if !text.starts_with('-') => args.value(flag)?.to_string(),
+                    _ => watermark::user_at_host()
+                        .ok_or("--watermark needs text: no user or host name to use instead")?,
+                };
+                settings.watermark = Some(text);
+            }
+            "--label" => {
+                label = Some(args.value(flag)?.to_string());
+            }
+            "--no-animation" => {
+                settings.animation = false;
+            }
+            "--intro" => {
+                settings.intro = true;
+            }
+            "--chooser" => {
+                settings.chooser = true;
+            }
+            "--hollywood" => {
+                settings.hollywood = true;
+            }
+            "--kiosk-lock" => {
+                settings.kiosk_lock = true;
+            }
+            "--forever" => {
+                settings.forever = true;
+            }
+            "--record" => {
+                settings.record = Some(args.value(flag)?.to_string());
+            }
+            #[cfg(feature = "battery")]
+            "--no-battery-saver" => settings.battery_saver = false,
+            #[cfg(feature = "image")]
+            "--image" => {
+                let val = args.value(flag)?;
+                let image = image::open(val)
+                    .map_err(|err| format!("could not load image {}: {}", val, err))?;
+                settings.picture = Some(Picture::new(image.into_rgb8()));
+            }
+            #[cfg(feature = "http")]
+            "--serve-http" => {
+                settings.serve_http = Some(args.value(flag)?.to_string());
+            }
+            #[cfg(feature = "lua")]
+            "--lua" => {
+                let val = args.value(flag)?;
+                let hooks = LuaHooks::load(val)
+                    .map_err(|err| format!("could not load Lua script {}: {}", val, err))?;
+                settings.lua = Some(hooks);
+            }
+            #[cfg(feature = "sound")]
+            "--sound" => {
+                settings.sound = Some(args.optional(flag, 1.0..=100.0)?.unwrap_or(30.0) / 100.0);
+            }
+            #[cfg(feature = "plugins")]
+            "--list-plugins" => {
+                settings.list_plugins = true;
+                return Ok(settings);
+            }
+            "--seed" => {
+                settings.seed = Some(args.at_least(flag, 0)?);
+            }
+            "--sync-server" => {
+                let addr = match args.args.get(args.i + 1) {
+                    Some(addr) if !addr.starts_with('-') => args.value(flag)?,
+                    _ => "0.0.0.0:4747",
+                };
+                // Just a port listens everywhere
+                settings.sync_server = Some(match addr.parse::<u16>() {
+                    Ok(port) => format!("0.0.0.0:{}", port),
+                    Err(_) => addr.to_string(),
+                });
+            }
+            "--sync-client" => {
+                settings.sync_client = Some(args.value(flag)?.to_string());
+            }
+            "--weather" => {
+                settings.weather = Some(args.value(flag)?.to_string());
+            }
+            "--profile" => {
+                settings.profile = Some(args.value(flag)?.to_string());
+            }
+            "--low-power" => {
+                settings.low_power = Some(args.optional(flag, 0.1..=100.0)?.unwrap_or(2.0));
+            }
+            "--static" => {
+                settings.static_frame = true;
+            }
+            "--lines" => {
+                settings.lines = Some(args.at_least(flag, 1)?);
+            }
+            "--debug" => {
+                settings.show_debug = true;
+            }
+            "--show-fps" => {
+                settings.show_fps = true;
+            }
+            "--status-bar" => {
+                settings.status_bar = true;
+            }
+            "--reserve-bottom" => {
+                settings.reserve_bottom = args.number(flag, 0..=1000)?;
+            }
+            "--width" => {
+                settings.width = Some(args.number(flag, 1..=10_000)?);
+            }
+            "--height" => {
+                settings.height = Some(args.number(flag, 1..=10_000)?);
+            }
+            "--mouse" => {
+                settings.mouse = true;
+            }
+            "--cursor-effect" => {
+                let val = args.value(flag)?;
+                let effect = CursorEffect::from_str(val).ok_or_else(|| {
+                    format!("unknown cursor effect '{}': expected spotlight or avoid", val)
+                })?;
+                settings.cursor_effect = Some(effect);
+            }
+            "--reactive" => {
+                settings.reactive = true;
+            }
+            "--vim" => {
+                settings.vim = true;
+            }
+            "--exit-on-mouse" => {
+                settings.exit_on_mouse = true;
+            }
+            "--exit-on-any-key" => {
+                settings.exit_on_any_key = true;
+            }
+            "--lock" => {
+                let phrase = match args.args.get(args.i + 1) {
+                    Some(phrase) if !phrase.starts_with('-') => args.value(flag)?.to_string(),
+                    _ => ["USER", "USERNAME", "LOGNAME"]
+                        .iter()
+                        .find_map(|var| env::var(var).ok().filter(|name| !name.is_empty()))
+                        .ok_or("--lock needs a passphrase: no username to use instead")?,
+                };
+                settings.lock = Some(phrase);
+            }
+            "--no-ctrl-c" => {
+                settings.ctrl_c = false;
+            }
+            "--while" => {
+                settings.while_cmd = Some(args.value(flag)?.to_string());
+            }
+            "--on-exit" => {
+                settings.on_exit = Some(args.value(flag)?.to_string());
+            }
+            "--timeout" => {
+                settings.timeout = Some(Duration::from_secs_f64(args.number(flag, 0.1..=1e9)?));
+            }
+            "--ramp" => {
+                settings.ramp = Some(Duration::from_secs_f64(args.number(flag, 0.1..=3600.0)?));
+            }
+            "--print-key" => {
+                settings.print_key = true;
+            }
+            "--no-title" => {
+                settings.title = false;
+            }
+            "--notify" => {
+                settings.notify = true;
+                settings.alert.notify = true;
+            }
+            "--alert" => {
+                settings.alert.add(args.value(flag)?)?;
+            }
+            "--on-done" => {
+                settings.alert.command = Some(args.value(flag)?.to_string());
+            }
+            "--on-lock" => {
+                if !cfg!(target_os = "linux") {
+                    return Err("--on-lock needs systemd-logind, so Linux".to_string());
+                }
+                settings.on_lock = true;
+            }
+            "--grace" => {
+                settings.grace = Duration::from_secs_f64(args.number(flag, 0.0..=3600.0)?);
+            }
+            "-c" | "--color" => {
+                let val = args.value(flag)?;
+                match ColorScheme::from_str(val) {
+                    Some(scheme) => settings.color_scheme = scheme,
+                    #[cfg(feature = "plugins")]
+                    None if find_plugin(val).is_some_and(|plugin| plugin.has_color()) => {
+                        settings.color_plugin = find_plugin(val);
+                    }
+                    None => {
+                        return Err(format!(
+                            "unknown color scheme '{}': expected green, blue, red, purple, cyan or rainbow",
+                            val
+                        ))
+                    }
+                }
+            }
+            "--rainbow-axis" => {
+                let val = args.value(flag)?;
+                settings.rainbow_axis = Sweep::from_str(val).ok_or_else(|| {
+                    format!("unknown rainbow axis '{}': expected horizontal, vertical, diagonal or time", val)
+                })?;
+            }
+            _ if flag.starts_with('-') => return Err(format!("unknown option '{}'", flag)),
+            _ => return Err(format!("unexpected argument '{}'", flag)),
+        }
+        args.i += 1;
+    }
+
+    // The console font has half blocks but no braille
+    if settings.linux_console && settings.render_mode == RenderMode::Braille {
+        settings.render_mode = RenderMode::HalfBlock;
+    }
+    if settings.max_length < settings.min_length {
+        return Err(format!(
+            "--length {} is shorter than --min-length {}",
+            settings.max_length, settings.min_length
+        ));
+    }
+    // Whatever else asked for motion, for anyone it makes unwell
+    if settings.reduced_motion {
+        settings.min_speed = settings.min_speed.saturating_mul(3);
+        settings.max_speed = settings.max_speed.saturating_mul(3);
+        settings.gravity = 0.0;
+        settings.shimmer = 0.0;
+        settings.head_mutate = settings.head_mutate.map(|_| 0.0);
+        if settings.head_style == Style::Blink {
+            settings.head_style = Style::Plain;
+        }
+        settings.glitch = 0.0;
+        settings.lightning = 0.0;
+        settings.persistence = settings.persistence.max(0.8);
+        settings.countdown = settings.countdown.take().map(Countdown::steady);
+    }
+    if label.is_some() {
+        let countdown = settings.countdown.take().ok_or("--label goes with --countdown")?;
+        settings.countdown = Some(countdown.with_label(label));
+    }
+    if settings.sync_server.is_some() && settings.sync_client.is_some() {
+        return Err("--sync-server and --sync-client don't go together".to_string());
+    }
+    Ok(settings)
+}
+
@@ -0,0 +1,22 @@
+//! Copying frames to the system clipboard, behind the `clipboard` feature.
+
+/// The system clipboard, opened the first time something is copied. It's
+/// kept open afterwards because on X11 the copied text only stays
+/// available while its owner is around.
+#[derive(Default)]
+pub struct Clipboard {
+    inner: Option<arboard::Clipboard>,
+}
+
+impl Clipboard {
+    /// Put `text` on the clipboard. Errors are ignored: there may be no
+    /// clipboard at all, say over SSH, and that shouldn't stop the rain.
+    pub fn copy(&mut self, text: String) {
+        if self.inner.is_none() {
+            self.inner = arboard::Clipboard::new().ok();
+        }
+        if let Some(clipboard) = self.inner.as_mut() {
+            let _ = clipboard.set_text(text);
+        }
+    }
+}
@@ -0,0 +1,271 @@
+use crate::platform::{Color, SystemTime, UNIX_EPOCH};
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum ColorScheme {
+    Green,
+    Blue,
+    Red,
+    Purple,
+    Cyan,
+    Rainbow,
+}
+
+impl ColorScheme {
+    /// Every built-in scheme.
+    pub const ALL: [ColorScheme; 6] = [
+        Self::Green,
+        Self::Blue,
+        Self::Red,
+        Self::Purple,
+        Self::Cyan,
+        Self::Rainbow,
+    ];
+
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "green" => Some(Self::Green),
+            "blue" => Some(Self::Blue),
+            "red" => Some(Self::Red),
+            "purple" => Some(Self::Purple),
+            "cyan" => Some(Self::Cyan),
+            "rainbow" => Some(Self::Rainbow),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Green => "green",
+            Self::Blue => "blue",
+            Self::Red => "red",
+            Self::Purple => "purple",
+            Self::Cyan => "cyan",
+            Self::Rainbow => "rainbow",
+        }
+    }
+
+    /// The color of glyph `i` of a drop `length` long in column `x`, with
+    /// `sweep` saying where the Rainbow scheme's hue comes from.
+    pub fn get_colors(&self, i: usize, length: usize, x: u16, sweep: Sweep) -> Color {
+        // Calculate fade factor (0.0 at head, 1.0 at tail)
+        let fade = i as f32 / length as f32;
+
+        match self {
+            Self::Green => {
+                if i == 0 {
+                    Color::Rgb { r: 200, g: 255, b: 200 } // Bright white-green head
+                } else if i == 1 {
+                    Color::Rgb { r: 100, g: 255, b: 100 } // Near-head glow
+                } else {
+                    // Smooth fade from bright green to dark green
+                    let intensity = (1.0 - fade * 0.85).max(0.15);
+                    let g = (255.0 * intensity) as u8;
+                    let r = (30.0 * (1.0 - fade)) as u8;
+                    Color::Rgb { r, g, b: 0 }
+                }
+            }
+            Self::Blue => {
+                if i == 0 {
+                    Color::Rgb { r: 200, g: 220, b: 255 }
+                } else if i == 1 {
+                    Color::Rgb { r: 100, g: 150, b: 255 }
+                } else {
+                    let intensity = (1.0 - fade * 0.85).max(0.15);
+                    let b = (255.0 * intensity) as u8;
+                    let g = (100.0 * intensity) as u8;
+                    Color::Rgb { r: 0, g, b }
+                }
+            }
+            Self::Red => {
+                if i == 0 {
+                    Color::Rgb { r: 255, g: 220, b: 200 }
+                } else if i == 1 {
+                    Color::Rgb { r: 255, g: 100, b: 100 }
+                } else {
+                    let intensity = (1.0 - fade * 0.85).max(0.15);
+                    let r = (255.0 * intensity) as u8;
+                    let g = (30.0 * (1.0 - fade)) as u8;
+                    Color::Rgb { r, g, b: 0 }
+                }
+            }
+            Self::Purple => {
+                if i == 0 {
+                    Color::Rgb { r: 240, g: 200, b: 255 }
+                } else if i == 1 {
+                    Color::Rgb { r: 200, g: 100, b: 255 }
+                } else {
+                    let intensity = (1.0 - fade * 0.85).max(0.15);
+                    let r = (180.0 * intensity) as u8;
+                    let b = (255.0 * intensity) as u8;
+                    Color::Rgb { r, g: 0, b }
+                }
+            }
+            Self::Cyan => {
+                if i == 0 {
+                    Color::Rgb { r: 200, g: 255, b: 255 }
+                } else if i == 1 {
+                    Color::Rgb { r: 100, g: 255, b: 255 }
+                } else {
+                    let intensity = (1.0 - fade * 0.85).max(0.15);
+                    let g = (255.0 * intensity) as u8;
+                    let b = (255.0 * intensity) as u8;
+                    Color::Rgb { r: 0, g, b }
+                }
+            }
+            Self::Rainbow => {
+                if i == 0 {
+                    Color::White
+                } else {
+                    let degrees = match sweep {
+                        Sweep::Horizontal => x as f32 * 10.0 + i as f32 * 15.0,
+                        Sweep::Vertical(y) => y as f32 * 15.0,
+                        Sweep::Diagonal(y) => (x as f32 + y as f32) * 10.0,
+                        Sweep::Time(secs) => secs * 30.0 + i as f32 * 15.0,
+                    };
+                    let hue = (degrees % 360.0) / 360.0;
+                    let intensity = (1.0 - fade * 0.8).max(0.2);
+                    let (r, g, b) = hsv_to_rgb(hue, 1.0, intensity);
+                    Color::Rgb { r, g, b }
+                }
+            }
+        }
+    }
+}
+
+
+/// Which way the Rainbow scheme's hues run, `--rainbow-axis`, along with
+/// what that needs to know about the cell being colored.
+#[derive(Clone, Copy, PartialEq)]
+pub enum Sweep {
+    Horizontal,    // Across the columns, shifting along each trail
+    Vertical(u16), // Down the rows; the cell's row
+    Diagonal(u16), // Corner to corner; the cell's row
+    Time(f32),     // The whole screen cycling together; seconds into the cycle
+}
+
+impl Sweep {
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "horizontal" => Some(Self::Horizontal),
+            "vertical" => Some(Self::Vertical(0)),
+            "diagonal" => Some(Self::Diagonal(0)),
+            "time" => Some(Self::Time(0.0)),
+            _ => None,
+        }
+    }
+
+    /// The same sweep for a cell in row `y`, as of now.
+    pub fn at(self, y: u16) -> Self {
+        match self {
+            Self::Horizontal => Self::Horizontal,
+            Self::Vertical(_) => Self::Vertical(y),
+            Self::Diagonal(_) => Self::Diagonal(y),
+            Self::Time(_) => {
+                // Twelve seconds a cycle, kept small enough for an f32
+                let since = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+                Self::Time((since.as_millis() % 12_000) as f32 / 1000.0)
+            }
+        }
+    }
+}
+
+/// Scale a color's brightness, e.g. to push a drop into the background.
+pub fn dim(color: Color, factor: f32) -> Color {
+    let scale = |c: u8| (c as f32 * factor) as u8;
+    match color {
+        Color::Rgb { r, g, b } => Color::Rgb {
+            r: scale(r),
+            g: scale(g),
+            b: scale(b),
+        },
+        Color::White => Color::Rgb {
+            r: scale(255),
+            g: scale(255),
+            b: scale(255),
+        },
+        other => other,
+    }
+}
+
+/// A color `amount` of the way from `from` to `to`. Named colors can't be
+/// mixed, so they switch over halfway.
+pub fn mix(from: Color, to: Color, amount: f32) -> Color {
+    let amount = amount.clamp(0.0, 1.0);
+    match (from, to) {
+        (Color::Rgb { r, g, b }, Color::Rgb { r: r2, g: g2, b: b2 }) => {
+            let lerp = |from: u8, to: u8| (from as f32 + (to as f32 - from as f32) * amount).round() as u8;
+            Color::Rgb {
+                r: lerp(r, r2),
+                g: lerp(g, g2),
+                b: lerp(b, b2),
+            }
+        }
+        _ if amount < 0.5 => from,
+        _ => to,
+    }
+}
+
+/// How bright a color is, 0.0 for black to 1.0 for its brightest channel
+/// full on. Named colors other than black count as full on.
+pub fn lightness(color: Color) -> f32 {
+    match color {
+        Color::Rgb { r, g, b } => r.max(g).max(b) as f32 / 255.0,
+        Color::Black => 0.0,
+        _ => 1.0,
+    }
+}
+
+/// Scale a color to `level` of the light it gives off, 0.0 to 1.0. Terminal
+/// colors are gamma encoded, so half the light is about 73% of the value.
+pub fn gamma_dim(color: Color, level: f32) -> Color {
+    dim(color, level.clamp(0.0, 1.0).powf(1.0 / 2.2))
+}
+
+/// Blend a color toward white, `amount` 0.0 leaving it alone and 1.0 making
+/// it pure white.
+pub fn brighten(color: Color, amount: f32) -> Color {
+    let lift = |c: u8| (c as f32 + (255.0 - c as f32) * amount) as u8;
+    match color {
+        Color::Rgb { r, g, b } => Color::Rgb {
+            r: lift(r),
+            g: lift(g),
+            b: lift(b),
+        },
+        other => other,
+    }
+}
+
+/// Wash a color toward the grey of the same lightness, `amount` 0.0
+/// leaving it alone and 1.0 taking all its color out.
+pub fn desaturate(color: Color, amount: f32) -> Color {
+    match color {
+        Color::Rgb { r, g, b } => {
+            let grey = 0.3 * r as f32 + 0.59 * g as f32 + 0.11 * b as f32;
+            let wash = |c: u8| (c as f32 + (grey - c as f32) * amount) as u8;
+            Color::Rgb {
+                r: wash(r),
+                g: wash(g),
+                b: wash(b),
+            }
+        }
+        other => other,
+    }
+}
+
+pub fn hsv_to_rgb(h: f32, s: f32, v: f32) -> (u8, u8, u8) {
+    let i = (h * 6.0).floor() as i32;
+    let f = h * 6.0 - i as f32;
+    let p = v * (1.0 - s);
+    let q = v * (1.0 - f * s);
+    let t = v * (1.0 - (1.0 - f) * s);
+    let (r, g, b) = match i % 6 {
+        0 => (v, t, p),
+        1 => (q, v, p),
+        2 => (p, v, t),
+        3 => (p, q, v),
+        4 => (t, p, v),
+        _ => (v, p, q),
+    };
+    ((r * 255.0) as u8, (g * 255.0) as u8, (b * 255.0) as u8)
+}
+
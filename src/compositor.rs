@@ -0,0 +1,125 @@
+//! Every frame is built up from layers, bottom to top:
+//!
+//! 1. the background glyph field, `--backdrop`, or blank,
+//! 2. the back rain layer,
+//! 3. the front rain layer,
+//! 4. the overlays, highest z first (see `overlay`).
+//!
+//! The rain layers each have their own phosphor buffer, so they fade
+//! independently and a feature can light up one without disturbing the
+//! other. Where they meet:
+//!
+//! - any lit rain cell covers the background field under it,
+//! - a front cell covers a back one unless the back one is brighter, i.e.
+//!   was lit more recently, so a back drop still shows through the fading
+//!   end of a front trail but never through a front drop itself,
+//! - overlays cover everything, the first to claim a cell keeping it.
+//!
+//! `--inverse` turns the rain layers around: the background field is lit
+//! everywhere and the rain takes light away from it, as much as the rain
+//! would have given, so heads are black and trails fade back in.
+
+use crate::{
+    buffer::{Cell, PhosphorBuffer},
+    color::{dim, lightness},
+    drop::Layer,
+    platform::Color,
+    render::{Glyph, RenderMode},
+};
+
+/// The rain's layers, each in its own phosphor buffer.
+pub struct Compositor {
+    back: PhosphorBuffer,
+    front: PhosphorBuffer,
+}
+
+impl Compositor {
+    pub fn new(width: u16, height: u16) -> Self {
+        Compositor {
+            back: PhosphorBuffer::new(width, height),
+            front: PhosphorBuffer::new(width, height),
+        }
+    }
+
+    pub fn resize(&mut self, width: u16, height: u16) {
+        self.back.resize(width, height);
+        self.front.resize(width, height);
+    }
+
+    pub fn layer_mut(&mut self, layer: Layer) -> &mut PhosphorBuffer {
+        match layer {
+            Layer::Back => &mut self.back,
+            Layer::Front => &mut self.front,
+        }
+    }
+
+    /// Fade both layers by a frame.
+    pub fn decay(&mut self, persistence: f32) {
+        self.back.decay(persistence);
+        self.front.decay(persistence);
+    }
+
+    /// Stamps that missed either layer since the last resize.
+    pub fn clipped(&self) -> usize {
+        self.back.clipped() + self.front.clipped()
+    }
+
+    /// The rain layers blended into one buffer, as they'd be seen.
+    pub fn flatten(&self) -> PhosphorBuffer {
+        let cells = self.back.cells().iter().zip(self.front.cells());
+        PhosphorBuffer::from_cells(
+            self.front.width(),
+            self.front.height(),
+            cells.map(|(&back, &front)| blend(back, front)).collect(),
+        )
+    }
+
+    /// The frame, `cols` x `rows` terminal cells in `mode`, with the
+    /// background field from `background` wherever no rain is lit.
+    /// Overlays go on afterwards.
+    pub fn compose(
+        &self,
+        mode: RenderMode,
+        cols: u16,
+        rows: u16,
+        background: impl Fn(u16, u16) -> Glyph,
+    ) -> Vec<Glyph> {
+        let cell = |x, y| blend(self.back.get(x, y), self.front.get(x, y));
+        mode.compose(cell, cols, rows, background)
+    }
+
+    /// `--inverse`: the frame with the rain cutting dark trails through a lit
+    /// field, whose glyph and color at each cell of the simulation grid come
+    /// from `field`. The field is shaded in behind its glyphs too, so the
+    /// trails show up as dark streaks.
+    pub fn compose_inverse(
+        &self,
+        mode: RenderMode,
+        cols: u16,
+        rows: u16,
+        field: impl Fn(u16, u16) -> (char, Color),
+    ) -> Vec<Glyph> {
+        let cell = |x, y| {
+            let rain = blend(self.back.get(x, y), self.front.get(x, y));
+            let (ch, color) = field(x, y);
+            let intensity = if rain.is_lit() { 1.0 - lightness(rain.shade()) } else { 1.0 };
+            Cell { ch, color, intensity }
+        };
+        let mut frame = mode.compose(cell, cols, rows, |_, _| Glyph::BLANK);
+        for glyph in &mut frame {
+            if glyph.ch != ' ' && glyph.bg == Color::Reset {
+                glyph.bg = dim(glyph.fg, 0.3);
+            }
+        }
+        frame
+    }
+}
+
+/// What shows of a `back` cell and the `front` cell over it.
+fn blend(back: Cell, front: Cell) -> Cell {
+    if front.is_lit() && front.intensity >= back.intensity {
+        front
+    } else {
+        back
+    }
+}
@@ -0,0 +1,134 @@
+//! The config file, `~/.config/matrix-rain/config.toml`, or under
+//! `$XDG_CONFIG_HOME` if that's set. Keys are long option names and values
+//! are what the option takes, e.g.
+//!
+//! ```toml
+//! fps = 30
+//! density = 60
+//! color = "rainbow"
+//! glow = true           # Options without a value are switched on with true
+//! hole = ["0,0,20,5"]   # Repeatable options take a list
+//!
+//! [preset.neon]         # Used with --preset neon
+//! color = "cyan"
+//! glow = true
+//!
+//! [keys]                # Runtime keys, see `keymap`
+//! speed_up = "k"
+//! ```
+
+use std::{fs, io::ErrorKind, path::PathBuf};
+
+use toml::{Table, Value};
+
+use crate::{cli::Preset, keymap::Keymap, settings::Settings};
+
+/// Where the config file lives, None without a home directory.
+pub fn path() -> Option<PathBuf> {
+    let base = match std::env::var_os("XDG_CONFIG_HOME").filter(|dir| !dir.is_empty()) {
+        Some(dir) => PathBuf::from(dir),
+        None => PathBuf::from(std::env::var_os("HOME")?).join(".config"),
+    };
+    Some(base.join("matrix-rain").join("config.toml"))
+}
+
+/// The config file as a table, empty if there isn't one yet.
+fn read(path: &PathBuf) -> Result<Table, String> {
+    match fs::read_to_string(path) {
+        Ok(text) => text.parse().map_err(|err: toml::de::Error| err.to_string()),
+        Err(err) if err.kind() == ErrorKind::NotFound => Ok(Table::new()),
+        Err(err) => Err(err.to_string()),
+    }
+}
+
+/// The options the config file at `path` sets, as command-line arguments,
+/// the presets its `[preset.NAME]` tables define, and the runtime keys with
+/// its `[keys]` table's changes.
+pub fn load(path: &PathBuf) -> Result<(Vec<String>, Vec<Preset>, Keymap), String> {
+    let mut table = read(path)?;
+    let mut presets = vec![];
+    if let Some(value) = table.remove("preset") {
+        let Value::Table(named) = value else {
+            return Err("preset must hold [preset.NAME] tables".to_string());
+        };
+        for (name, value) in named {
+            let Value::Table(options) = value else {
+                return Err(format!("preset.{} must be a table", name));
+            };
+            presets.push((name, args(options)?));
+        }
+    }
+    let mut keymap = Keymap::default();
+    if let Some(value) = table.remove("keys") {
+        let Value::Table(keys) = value else {
+            return Err("keys must be a [keys] table".to_string());
+        };
+        for (action, value) in keys {
+            let values = match value {
+                Value::Array(values) => values,
+                value => vec![value],
+            };
+            let keys = values
+                .into_iter()
+                .map(|value| match value {
+                    Value::String(key) => Ok(key),
+                    _ => Err(format!("keys.{} must be a key name or a list of them", action)),
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            keymap.bind(&action, &keys).map_err(|err| format!("[keys] {}", err))?;
+        }
+    }
+    Ok((args(table)?, presets, keymap))
+}
+
+/// A table of options as command-line arguments.
+fn args(table: Table) -> Result<Vec<String>, String> {
+    let mut args = vec![];
+    for (key, value) in table {
+        let flag = format!("--{}", key);
+        let values = match value {
+            Value::Array(values) => values,
+            value => vec![value],
+        };
+        for value in values {
+            match value {
+                Value::Boolean(true) => args.push(flag.clone()),
+                Value::Boolean(false) => {}
+                Value::String(text) => args.extend([flag.clone(), text]),
+                Value::Integer(_) | Value::Float(_) => {
+                    args.extend([flag.clone(), value.to_string()])
+                }
+                _ => return Err(format!("{} has an unsupported value", key)),
+            }
+        }
+    }
+    Ok(args)
+}
+
+/// Write the settings the runtime keys tune into the config file, creating
+/// it if needed and keeping anything else it sets. Returns where it went.
+pub fn save(settings: &Settings) -> Result<PathBuf, String> {
+    let path = path().ok_or("no home directory")?;
+    let mut table = read(&path)?;
+    let fps = 1.0 / settings.frame_delay.as_secs_f64().max(f64::EPSILON);
+    // `fps` replaces a frame delay given with `speed`
+    table.remove("speed");
+    let mut set = |key: &str, value: Value| {
+        table.insert(key.to_string(), value);
+    };
+    set("fps", Value::Float((fps * 10.0).round() / 10.0));
+    set("density", Value::Integer((settings.density * 100.0).round() as i64));
+    set("spawns", Value::Integer(settings.spawns_per_frame as i64));
+    set("length", Value::Integer(settings.max_length as i64));
+    set("color", settings.color_scheme.name().into());
+    // Same -10..10 scale as `--wind`
+    set("wind", Value::Float((settings.wind as f64 * 100.0).round() / 10.0));
+    set("direction", settings.direction.name().into());
+
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|err| err.to_string())?;
+    }
+    let text = toml::to_string(&table).map_err(|err| err.to_string())?;
+    fs::write(&path, text).map_err(|err| err.to_string())?;
+    Ok(path)
+}
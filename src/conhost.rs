@@ -0,0 +1,128 @@
+//! The old Windows console, conhost before Windows 10, which prints escape
+//! codes as text rather than following them. Everything the rain writes is
+//! escape codes, so for it they're read back and done with crossterm's
+//! console API calls instead.
+
+use std::io::{self, Stdout, Write};
+
+use crossterm::{
+    cursor::MoveTo,
+    queue,
+    style::{Color, ResetColor, SetBackgroundColor, SetForegroundColor},
+    terminal::{Clear, ClearType},
+};
+
+use crate::console;
+
+/// The terminal, taking the rain's escape codes whether or not the console
+/// follows them. What's written is held until a flush, so a frame is read
+/// whole and no code is ever cut in two.
+pub struct Conhost {
+    out: Stdout,
+    legacy: bool, // No escape codes, so they're translated
+    held: Vec<u8>,
+}
+
+impl Conhost {
+    pub fn new(out: Stdout) -> Self {
+        Conhost {
+            out,
+            legacy: !crossterm::ansi_support::supports_ansi(),
+            held: vec![],
+        }
+    }
+
+    /// Do the escape code at the start of `code` and return how long it
+    /// is. Only colors, cursor moves and clearing the screen matter; the
+    /// rest, titles and styles and the like, the console can't do anyway.
+    fn escape(&mut self, code: &[u8]) -> io::Result<usize> {
+        match code.get(1) {
+            Some(b'[') => {
+                let Some(end) = code[2..].iter().position(|b| (0x40..=0x7e).contains(b)) else {
+                    return Ok(code.len());
+                };
+                let params = String::from_utf8_lossy(&code[2..end + 2]);
+                let params: Vec<u16> = params.split(';').map(|param| param.parse().unwrap_or(0)).collect();
+                match code[end + 2] {
+                    b'H' => {
+                        let (row, col) = (params[0].max(1), params.get(1).copied().unwrap_or(1).max(1));
+                        queue!(self.out, MoveTo(col - 1, row - 1))?;
+                    }
+                    b'J' if params[0] == 2 => queue!(self.out, Clear(ClearType::All))?,
+                    b'K' => queue!(self.out, Clear(ClearType::CurrentLine))?,
+                    b'm' => self.sgr(&params)?,
+                    _ => {}
+                }
+                Ok(end + 3)
+            }
+            // Titles and notifications, up to BEL or ESC \
+            Some(b']') => {
+                let bel = code.iter().position(|&b| b == 0x07).map(|at| at + 1);
+                let st = code.windows(2).position(|pair| pair == b"\x1b\\").map(|at| at + 2);
+                Ok(match (bel, st) {
+                    (Some(bel), Some(st)) => bel.min(st),
+                    (bel, st) => bel.or(st).unwrap_or(code.len()),
+                })
+            }
+            Some(_) => Ok(2),
+            None => Ok(1),
+        }
+    }
+
+    /// Set the colors an SGR code asks for, the nearest of the console's 16.
+    fn sgr(&mut self, params: &[u16]) -> io::Result<()> {
+        let mut params = params.iter().copied();
+        while let Some(param) = params.next() {
+            match param {
+                0 => queue!(self.out, ResetColor)?,
+                39 => queue!(self.out, SetForegroundColor(Color::Reset))?,
+                49 => queue!(self.out, SetBackgroundColor(Color::Reset))?,
+                38 | 48 => {
+                    let color = match params.next() {
+                        Some(5) => Color::AnsiValue(params.next().unwrap_or(0) as u8),
+                        Some(2) => {
+                            let mut rgb = || params.next().unwrap_or(0) as u8;
+                            Color::Rgb { r: rgb(), g: rgb(), b: rgb() }
+                        }
+                        _ => continue,
+                    };
+                    let color = console::nearest(color, 16);
+                    if param == 38 {
+                        queue!(self.out, SetForegroundColor(color))?;
+                    } else {
+                        queue!(self.out, SetBackgroundColor(color))?;
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Write for Conhost {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if !self.legacy {
+            return self.out.write(buf);
+        }
+        self.held.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        let held = std::mem::take(&mut self.held);
+        let mut rest = &held[..];
+        while !rest.is_empty() {
+            let text = rest.iter().position(|&b| b == 0x1b).unwrap_or(rest.len());
+            self.out.write_all(&rest[..text])?;
+            rest = &rest[text..];
+            if !rest.is_empty() {
+                rest = &rest[self.escape(rest)?.min(rest.len())..];
+            }
+        }
+        // Keep the memory for the next frame
+        self.held = held;
+        self.held.clear();
+        self.out.flush()
+    }
+}
@@ -0,0 +1,81 @@
+use crate::{platform::Color, render::Glyph};
+
+/// The Linux console's 16 colors, VGA style, in SGR order.
+pub(crate) const PALETTE: [(Color, (u8, u8, u8)); 16] = [
+    (Color::Black, (0, 0, 0)),
+    (Color::DarkRed, (170, 0, 0)),
+    (Color::DarkGreen, (0, 170, 0)),
+    (Color::DarkYellow, (170, 85, 0)),
+    (Color::DarkBlue, (0, 0, 170)),
+    (Color::DarkMagenta, (170, 0, 170)),
+    (Color::DarkCyan, (0, 170, 170)),
+    (Color::Grey, (170, 170, 170)),
+    (Color::DarkGrey, (85, 85, 85)),
+    (Color::Red, (255, 85, 85)),
+    (Color::Green, (85, 255, 85)),
+    (Color::Yellow, (255, 255, 85)),
+    (Color::Blue, (85, 85, 255)),
+    (Color::Magenta, (255, 85, 255)),
+    (Color::Cyan, (85, 255, 255)),
+    (Color::White, (255, 255, 255)),
+];
+
+/// Glyphs drops fall back to where the console font has no katakana.
+const ASCII: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789@#$%&*+=<>?";
+
+/// `--linux-console`: redraw `frame` with what a bare Linux virtual console
+/// can show, its 16 colors, and of those only the first 8 as backgrounds
+/// (the others blink), and glyphs its CP437-style font has.
+pub fn apply(frame: &mut [Glyph]) {
+    for glyph in frame {
+        glyph.ch = fallback(glyph.ch);
+        glyph.fg = nearest(glyph.fg, 16);
+        glyph.bg = nearest(glyph.bg, 8);
+    }
+}
+
+/// `ch` if the console font has it, or an ASCII stand-in that's always the
+/// same for the same glyph.
+fn fallback(ch: char) -> char {
+    match ch {
+        ' '..='~' => ch,
+        // Box drawing and block elements, which overlays and --hires use
+        '\u{2500}'..='\u{259f}' => ch,
+        _ => ASCII[ch as usize % ASCII.len()] as char,
+    }
+}
+
+/// The closest of the first `count` palette colors to `color`.
+pub(crate) fn nearest(color: Color, count: usize) -> Color {
+    let (r, g, b) = match color {
+        Color::Reset => return Color::Reset,
+        Color::Rgb { r, g, b } => (r, g, b),
+        Color::AnsiValue(index) => ansi_rgb(index),
+        named => match PALETTE.iter().position(|&(c, _)| c == named) {
+            Some(index) if index < count => return named,
+            Some(index) => PALETTE[index].1,
+            None => return named,
+        },
+    };
+    let distance = |&&(_, (pr, pg, pb)): &&(Color, (u8, u8, u8))| {
+        let d = |a: u8, b: u8| (a as i32 - b as i32).pow(2);
+        d(r, pr) + d(g, pg) + d(b, pb)
+    };
+    PALETTE[..count].iter().min_by_key(distance).map_or(Color::Reset, |&(c, _)| c)
+}
+
+/// The RGB value of a 256-color palette index.
+pub(crate) fn ansi_rgb(index: u8) -> (u8, u8, u8) {
+    match index {
+        0..=15 => PALETTE[index as usize].1,
+        16..=231 => {
+            let level = |n: u8| if n == 0 { 0 } else { 55 + n * 40 };
+            let n = index - 16;
+            (level(n / 36), level(n / 6 % 6), level(n % 6))
+        }
+        _ => {
+            let grey = 8 + (index - 232) * 10;
+            (grey, grey, grey)
+        }
+    }
+}
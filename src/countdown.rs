@@ -0,0 +1,197 @@
+use std::time::Duration;
+
+use crate::{
+    kiosk::pixel,
+    overlay::{Canvas, Overlay, Z_REVEAL},
+    platform::{Color, SystemTime, UNIX_EPOCH},
+    render::Glyph,
+};
+
+/// How many times the screen flashes when the countdown hits zero...
+const FLASHES: u32 = 3;
+/// ...how far apart...
+const FLASH_EVERY: Duration = Duration::from_millis(400);
+/// ...and how long the label blinks along with them before it stays.
+const BLINK: Duration = Duration::from_millis(2000);
+
+/// `--countdown`: the days, hours, minutes and seconds left until a moment,
+/// in big block digits over the rain. At zero the screen flashes and the
+/// `--label` takes their place.
+pub struct Countdown {
+    target: SystemTime,
+    label: Option<String>,
+    flashed: u32, // Flashes so far, all of them if it was over before we started
+    announced: bool, // Whether `just_over` has said so, or it was over before we started
+    steady: bool, // No flashing or blinking at zero, for --reduced-motion
+}
+
+impl Countdown {
+    /// Parse `YYYY-MM-DDTHH:MM:SS` in local time, or UTC with a `Z` on the
+    /// end. The seconds, or the whole time of day, can be left off.
+    pub fn from_str(s: &str) -> Option<Self> {
+        let (s, utc) = match s.trim().strip_suffix(['Z', 'z']) {
+            Some(s) => (s, true),
+            None => (s.trim(), false),
+        };
+        let (date, time) = s.split_once(['T', ' ']).unwrap_or((s, "00:00"));
+        let mut date = date.splitn(3, '-').map(str::parse::<u32>);
+        let (year, month, day) = (date.next()?.ok()?, date.next()?.ok()?, date.next()?.ok()?);
+        let mut time = time.splitn(3, ':').map(str::parse::<u32>);
+        let (hour, minute) = (time.next()?.ok()?, time.next()?.ok()?);
+        let second = time.next().map_or(Some(0), Result::ok)?;
+        let days_in_month = days_from_civil(year as i64 + month as i64 / 12, month % 12 + 1, 1)
+            - days_from_civil(year as i64, month, 1);
+        if !(1..=12).contains(&month) || day == 0 || day as i64 > days_in_month || hour > 23 || minute > 59 || second > 59 {
+            return None;
+        }
+        let secs = match utc {
+            true => None,
+            false => local_secs(year, month, day, hour, minute, second),
+        }
+        .unwrap_or_else(|| {
+            days_from_civil(year as i64, month, day) * 86_400 + (hour * 3600 + minute * 60 + second) as i64
+        });
+        let target = match u64::try_from(secs) {
+            Ok(secs) => UNIX_EPOCH + Duration::from_secs(secs),
+            Err(_) => UNIX_EPOCH - Duration::from_secs(secs.unsigned_abs()),
+        };
+        let over = SystemTime::now() >= target;
+        Some(Countdown {
+            target,
+            label: None,
+            flashed: if over { FLASHES } else { 0 },
+            announced: over,
+            steady: false,
+        })
+    }
+
+    /// What to show instead of the digits once it's over.
+    pub fn with_label(mut self, label: Option<String>) -> Self {
+        self.label = label;
+        self
+    }
+
+    /// The same countdown, turning into its label at zero without any
+    /// flashing.
+    pub fn steady(mut self) -> Self {
+        self.steady = true;
+        self
+    }
+
+    /// True the once, as it gets to zero, for `--alert`.
+    pub fn just_over(&mut self) -> bool {
+        if self.announced || SystemTime::now() < self.target {
+            return false;
+        }
+        self.announced = true;
+        true
+    }
+
+    /// True when the screen should flash this frame: as the countdown hits
+    /// zero, then a couple more times.
+    pub fn tick(&mut self) -> bool {
+        let Ok(since) = SystemTime::now().duration_since(self.target) else {
+            return false;
+        };
+        let due = (since.as_millis() / FLASH_EVERY.as_millis()) as u32 + 1;
+        if self.steady || self.flashed >= due.min(FLASHES) {
+            return false;
+        }
+        self.flashed = due.min(FLASHES);
+        true
+    }
+
+    /// What goes on screen now, None while the label blinks off.
+    fn text(&self) -> Option<String> {
+        let since = match self.target.duration_since(SystemTime::now()) {
+            // Rounded up, so zero only shows once it's over
+            Ok(left) => {
+                let secs = left.as_secs() + u64::from(left.subsec_nanos() > 0);
+                let (days, hours, minutes, secs) = (secs / 86_400, secs / 3600 % 24, secs / 60 % 60, secs % 60);
+                return Some(match days {
+                    0 => format!("{:02}:{:02}:{:02}", hours, minutes, secs),
+                    _ => format!("{}D {:02}:{:02}:{:02}", days, hours, minutes, secs),
+                });
+            }
+            Err(over) => over.duration(),
+        };
+        let Some(label) = &self.label else {
+            return Some("00:00:00".to_string());
+        };
+        let blinking = !self.steady && since < BLINK && since.as_millis() / 250 % 2 == 1;
+        (!blinking).then(|| label.clone())
+    }
+}
+
+impl Overlay for Countdown {
+    fn z(&self) -> u8 {
+        Z_REVEAL
+    }
+
+    /// The time left in the middle of the screen, in big blocks if it fits
+    /// and as plain text if not, with a cell of clear margin round it.
+    fn draw(&self, canvas: &mut Canvas) {
+        let Some(text) = self.text() else {
+            return;
+        };
+        let (cols, rows) = (canvas.cols, canvas.rows);
+        let chars: Vec<char> = text.chars().collect();
+        let big_width = (chars.len() * 4).saturating_sub(1);
+        let big = big_width + 4 <= cols as usize && rows >= 7;
+        let (width, height) = if big {
+            (big_width as u16, 5)
+        } else {
+            ((chars.len() as u16).min(cols.saturating_sub(4)), 1)
+        };
+        let (box_width, box_height) = ((width + 4).min(cols), (height + 2).min(rows));
+        let (left, top) = ((cols - box_width) / 2, (rows - box_height) / 2);
+        for y in 0..box_height {
+            for x in 0..box_width {
+                let (cx, cy) = (x as i32 - 2, y as i32 - 1);
+                let inside = cx >= 0 && cy >= 0 && cx < width as i32 && cy < height as i32;
+                let glyph = match (cx as usize, cy as usize) {
+                    (cx, cy) if inside && big => {
+                        let ch = chars.get(cx / 4).copied().unwrap_or(' ');
+                        match pixel(ch.to_ascii_uppercase(), cx % 4, cy) {
+                            true => Glyph::new('█', Color::White),
+                            false => Glyph::BLANK,
+                        }
+                    }
+                    (cx, _) if inside => Glyph::new(chars[cx], Color::White),
+                    _ => Glyph::BLANK,
+                };
+                canvas.put(left + x, top + y, glyph);
+            }
+        }
+    }
+}
+
+/// Days from 1970-01-01 to a date in the Gregorian calendar.
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let of_era = year - era * 400;
+    let of_year = (153 * ((month as i64 + 9) % 12) + 2) / 5 + day as i64 - 1;
+    era * 146_097 + of_era * 365 + of_era / 4 - of_era / 100 + of_year - 719_468
+}
+
+/// Seconds since the epoch at a local date and time.
+#[cfg(unix)]
+fn local_secs(year: u32, month: u32, day: u32, hour: u32, minute: u32, second: u32) -> Option<i64> {
+    let mut local: libc::tm = unsafe { std::mem::zeroed() };
+    local.tm_year = year as i32 - 1900;
+    local.tm_mon = month as i32 - 1;
+    local.tm_mday = day as i32;
+    local.tm_hour = hour as i32;
+    local.tm_min = minute as i32;
+    local.tm_sec = second as i32;
+    local.tm_isdst = -1; // Whichever applies then
+    let secs = unsafe { libc::mktime(&mut local) };
+    (secs != -1).then_some(secs as i64)
+}
+
+/// Without a portable way to the time zone here, the time is taken as UTC.
+#[cfg(not(unix))]
+fn local_secs(_: u32, _: u32, _: u32, _: u32, _: u32, _: u32) -> Option<i64> {
+    None
+}
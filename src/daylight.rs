@@ -0,0 +1,97 @@
+use std::time::Duration;
+
+use crate::{
+    night::{minute_of_day, parse_time},
+    settings::Settings,
+};
+
+/// `--time-of-day` without a curve: sparse and slow at 4am, fullest and
+/// fastest at 2pm.
+pub const DEFAULT_CURVE: &str = "04:00=15/12,14:00=80/35";
+
+const MINUTES_PER_DAY: i32 = 24 * 60;
+
+/// One point of the curve: at `minute` past midnight, this density and,
+/// if given, frame rate.
+struct Point {
+    minute: u16,
+    density: f64,
+    fps: Option<f64>,
+}
+
+/// `--time-of-day`: density and speed following the clock along a curve,
+/// straight lines between its points, round the clock and back.
+pub struct Daylight {
+    points: Vec<Point>,
+    applied: Option<u16>, // The minute last put into the settings
+}
+
+impl Daylight {
+    /// Parse a curve such as `04:00=15/12,14:00=80/35`: at each time, a
+    /// density in percent and optionally a frame rate after `/`.
+    pub fn from_str(s: &str) -> Option<Self> {
+        let mut points = s
+            .split(',')
+            .map(|point| {
+                let (time, value) = point.split_once('=')?;
+                let (density, fps) = match value.split_once('/') {
+                    Some((density, fps)) => (density, Some(fps)),
+                    None => (value, None),
+                };
+                let density: f64 = density.trim().parse().ok()?;
+                let fps = match fps {
+                    Some(fps) => Some(fps.trim().parse::<f64>().ok()?),
+                    None => None,
+                };
+                let valid = (1.0..=100.0).contains(&density)
+                    && fps.is_none_or(|fps| (1.0..=1000.0).contains(&fps));
+                valid.then_some(Point {
+                    minute: parse_time(time)?,
+                    density: density / 100.0,
+                    fps,
+                })
+            })
+            .collect::<Option<Vec<_>>>()?;
+        points.sort_by_key(|point| point.minute);
+        Some(Daylight {
+            points,
+            applied: None,
+        })
+    }
+
+    /// Put the curve's density and speed for now into `settings`, once a
+    /// minute, so the runtime keys still hold until the next.
+    pub fn tick(&mut self, settings: &mut Settings) {
+        let minute = minute_of_day();
+        if self.applied == Some(minute) {
+            return;
+        }
+        self.applied = Some(minute);
+        settings.density = self.at(minute, |point| Some(point.density)).unwrap_or(settings.density);
+        if let Some(fps) = self.at(minute, |point| point.fps) {
+            settings.frame_delay = Duration::from_secs_f64(1.0 / fps);
+        }
+    }
+
+    /// The curve's `value` at `minute`, between the points either side of
+    /// it that have one.
+    fn at(&self, minute: u16, value: impl Fn(&Point) -> Option<f64>) -> Option<f64> {
+        let known: Vec<(i32, f64)> = self
+            .points
+            .iter()
+            .filter_map(|point| Some((point.minute as i32, value(point)?)))
+            .collect();
+        let minute = minute as i32;
+        // The last point before now and the first after, wrapping round
+        // midnight
+        let before = known.iter().rev().find(|(at, _)| *at <= minute).copied().or_else(|| {
+            known.last().map(|&(at, v)| (at - MINUTES_PER_DAY, v))
+        })?;
+        let after = known.iter().find(|(at, _)| *at > minute).copied().or_else(|| {
+            known.first().map(|&(at, v)| (at + MINUTES_PER_DAY, v))
+        })?;
+        let span = (after.0 - before.0).max(1) as f64;
+        let along = (minute - before.0) as f64 / span;
+        Some(before.1 + (after.1 - before.1) * along)
+    }
+}
@@ -0,0 +1,162 @@
+use rand::{seq::SliceRandom, Rng};
+use std::time::Duration;
+
+use crate::{
+    color::ColorScheme,
+    platform::Instant,
+    render::RenderMode,
+    settings::{EffectKind, Settings},
+};
+
+/// How long the numbers take to glide from one scene to the next.
+const TRANSITION: Duration = Duration::from_secs(4);
+
+/// How long the screen takes to fade out before a change of effect or
+/// rendering, and back in after.
+const FADE: Duration = Duration::from_millis(800);
+
+/// The settings that glide between scenes.
+#[derive(Clone, Copy)]
+struct Scene {
+    density: f64,
+    fps: f64,
+    length: f64,
+    wind: f32,
+}
+
+impl Scene {
+    fn of(settings: &Settings) -> Self {
+        Scene {
+            density: settings.density,
+            fps: 1.0 / settings.frame_delay.as_secs_f64().max(f64::EPSILON),
+            length: settings.max_length as f64,
+            wind: settings.wind,
+        }
+    }
+
+    fn random(rng: &mut impl Rng) -> Self {
+        Scene {
+            density: rng.gen_range(0.15..0.95),
+            fps: rng.gen_range(15.0..60.0),
+            length: rng.gen_range(6.0..30.0),
+            // Calm most of the time
+            wind: if rng.gen_bool(0.3) { rng.gen_range(-0.5..0.5) } else { 0.0 },
+        }
+    }
+}
+
+/// `--demo`: an attract mode that wanders to a new scene every so often,
+/// a new color scheme, density, speed, length and wind, and now and then
+/// another effect or rendering, gliding or fading rather than cutting.
+pub struct Demo {
+    every: Duration,
+    next: Instant, // When the next scene starts
+    from: Scene,
+    to: Scene,
+    since: Instant, // When the glide to `to` started
+    switch: Option<(Instant, EffectKind, RenderMode)>, // Change due once faded out
+    switched: Option<Instant>, // When the last one happened, to fade back in
+}
+
+impl Demo {
+    /// A demo that changes scene every `secs` seconds, starting from
+    /// `settings`.
+    pub fn new(secs: u64, settings: &Settings) -> Self {
+        let now = Instant::now();
+        let scene = Scene::of(settings);
+        Demo {
+            every: Duration::from_secs(secs),
+            next: now + Duration::from_secs(secs),
+            from: scene,
+            to: scene,
+            since: now,
+            switch: None,
+            switched: None,
+        }
+    }
+
+    /// Move `settings` along, starting a new scene when it's time. True
+    /// when the effect or rendering just changed and has to be rebuilt.
+    pub fn tick(&mut self, settings: &mut Settings, rng: &mut impl Rng) -> bool {
+        let now = Instant::now();
+        let mut rebuild = false;
+        if let Some((at, effect, mode)) = self.switch {
+            if now >= at {
+                settings.effect = effect;
+                settings.render_mode = mode;
+                self.switch = None;
+                self.switched = Some(at);
+                rebuild = true;
+            }
+        }
+
+        if now >= self.next {
+            self.next = now + self.every;
+            self.from = Scene::of(settings);
+            self.to = Scene::random(rng);
+            self.since = now;
+            let current = settings.color_scheme.name();
+            let others: Vec<ColorScheme> =
+                ColorScheme::ALL.into_iter().filter(|scheme| scheme.name() != current).collect();
+            settings.color_scheme = *others.choose(rng).unwrap_or(&settings.color_scheme);
+            #[cfg(feature = "plugins")]
+            {
+                settings.color_plugin = None;
+            }
+            if self.switch.is_none() {
+                self.switch = Self::restyle(settings, rng).map(|(effect, mode)| (now + FADE, effect, mode));
+            }
+        }
+
+        let along = (now.duration_since(self.since).as_secs_f64() / TRANSITION.as_secs_f64()).min(1.0);
+        let eased = along * along * (3.0 - 2.0 * along);
+        let lerp = |from: f64, to: f64| from + (to - from) * eased;
+        settings.density = lerp(self.from.density, self.to.density);
+        settings.frame_delay = Duration::from_secs_f64(1.0 / lerp(self.from.fps, self.to.fps));
+        settings.max_length = (lerp(self.from.length, self.to.length).round() as usize).max(settings.min_length);
+        settings.wind = lerp(self.from.wind as f64, self.to.wind as f64) as f32;
+        rebuild
+    }
+
+    /// Now and then, another effect or way of rendering for the next scene.
+    fn restyle(settings: &Settings, rng: &mut impl Rng) -> Option<(EffectKind, RenderMode)> {
+        let mut effect = settings.effect;
+        // A plugin effect stays put: there's no getting back to it
+        #[cfg(feature = "plugins")]
+        let fixed = settings.effect_plugin.is_some();
+        #[cfg(not(feature = "plugins"))]
+        let fixed = false;
+        if !fixed && rng.gen_bool(0.2) {
+            // Mostly back to the rain itself
+            effect = match effect {
+                EffectKind::Rain => *[EffectKind::Snow, EffectKind::Sakura].choose(rng)?,
+                _ => EffectKind::Rain,
+            };
+        }
+        let mut mode = settings.render_mode;
+        if rng.gen_bool(0.25) {
+            let mut modes = vec![RenderMode::Text, RenderMode::HalfBlock];
+            // No braille in a bare Linux console's font
+            if !settings.linux_console {
+                modes.push(RenderMode::Braille);
+            }
+            modes.retain(|&other| other != mode);
+            mode = *modes.choose(rng)?;
+        }
+        (effect != settings.effect || mode != settings.render_mode).then_some((effect, mode))
+    }
+
+    /// How bright the screen should be, dipping to nothing around a change
+    /// of effect or rendering.
+    pub fn fade(&self) -> f32 {
+        let now = Instant::now();
+        let fade = FADE.as_secs_f32();
+        if let Some((at, ..)) = self.switch {
+            return (at.saturating_duration_since(now).as_secs_f32() / fade).min(1.0);
+        }
+        match self.switched {
+            Some(at) => (now.duration_since(at).as_secs_f32() / fade).min(1.0),
+            None => 1.0,
+        }
+    }
+}
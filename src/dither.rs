@@ -0,0 +1,81 @@
+use crate::{console::PALETTE, platform::Color, render::Glyph};
+
+/// How many colors the terminal can show, `--colors`.
+#[derive(Clone, Copy, PartialEq)]
+pub enum ColorDepth {
+    TrueColor, // Any RGB color, as is
+    Ansi256,   // The 6x6x6 color cube of the 256-color palette
+    Ansi16,    // The 16 basic colors
+}
+
+impl ColorDepth {
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "truecolor" | "24bit" => Some(Self::TrueColor),
+            "256" => Some(Self::Ansi256),
+            "16" => Some(Self::Ansi16),
+            _ => None,
+        }
+    }
+}
+
+/// A 4x4 Bayer matrix: neighboring cells get thresholds far apart, so a
+/// color between two palette entries comes out as an even mix of both.
+const BAYER: [[u8; 4]; 4] = [[0, 8, 2, 10], [12, 4, 14, 6], [3, 11, 1, 9], [15, 7, 13, 5]];
+
+/// The levels each channel of the 256-color cube takes.
+const CUBE: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+/// Bring the RGB colors in `frame`, `cols` wide, down to what `depth` can
+/// show. Foregrounds are ordered-dithered, so a trail fading between two
+/// palette colors mixes them cell by cell instead of jumping from one to
+/// the other in bands. Backgrounds, mostly flat, take the nearest color.
+pub fn apply(frame: &mut [Glyph], cols: u16, depth: ColorDepth) {
+    if depth == ColorDepth::TrueColor || cols == 0 {
+        return;
+    }
+    for (i, glyph) in frame.iter_mut().enumerate() {
+        let (x, y) = (i % cols as usize, i / cols as usize);
+        let threshold = (BAYER[y % 4][x % 4] as f32 + 0.5) / 16.0;
+        glyph.fg = quantize(glyph.fg, depth, threshold);
+        glyph.bg = quantize(glyph.bg, depth, 0.5);
+    }
+}
+
+/// `color` in `depth`, rounding up between two colors where it's further
+/// along than `threshold`. Named colors are left alone.
+fn quantize(color: Color, depth: ColorDepth, threshold: f32) -> Color {
+    let Color::Rgb { r, g, b } = color else {
+        return color;
+    };
+    match depth {
+        ColorDepth::TrueColor => color,
+        ColorDepth::Ansi256 => {
+            let level = |c: u8| {
+                let i = CUBE.iter().rposition(|&level| level <= c).unwrap_or(0).min(4);
+                let along = (c as f32 - CUBE[i] as f32) / (CUBE[i + 1] - CUBE[i]) as f32;
+                i as u8 + (along > threshold) as u8
+            };
+            Color::AnsiValue(16 + 36 * level(r) + 6 * level(g) + level(b))
+        }
+        ColorDepth::Ansi16 => mix(r, g, b, threshold),
+    }
+}
+
+/// The nearer of the two palette colors closest to `r`, `g`, `b`, as
+/// `threshold` falls along the line between them.
+fn mix(r: u8, g: u8, b: u8, threshold: f32) -> Color {
+    let rgb = [r as f32, g as f32, b as f32];
+    let distance = |p: [f32; 3]| (0..3).map(|i| (rgb[i] - p[i]).powi(2)).sum::<f32>();
+    let mut palette = PALETTE.map(|(color, (pr, pg, pb))| (color, [pr as f32, pg as f32, pb as f32]));
+    palette.sort_by(|a, b| distance(a.1).total_cmp(&distance(b.1)));
+    let ((near, a), (far, b)) = (palette[0], palette[1]);
+    // How far the color lies from the nearest toward the second nearest
+    let span: f32 = (0..3).map(|i| (b[i] - a[i]).powi(2)).sum();
+    let along = (0..3).map(|i| (rgb[i] - a[i]) * (b[i] - a[i])).sum::<f32>() / span;
+    if along.clamp(0.0, 1.0) > threshold {
+        far
+    } else {
+        near
+    }
+}
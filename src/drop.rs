@@ -0,0 +1,700 @@
+use rand::{distributions::uniform::SampleUniform, Rng};
+use std::{collections::VecDeque, rc::Rc};
+
+use crate::{color::dim, platform::Color, settings::Settings, splash::Splash};
+
+/// Glyphs the rain is made of, as a slice so picking one costs nothing.
+pub const CHARS: &[char] = &[
+    'a', 'b', 'c', 'd', 'e', 'f', 'g', 'h', 'i', 'j', 'k', 'l', 'm', 'n', 'o', 'p', 'q', 'r',
+    's', 't', 'u', 'v', 'w', 'x', 'y', 'z',
+    'A', 'B', 'C', 'D', 'E', 'F', 'G', 'H', 'I', 'J', 'K', 'L', 'M', 'N', 'O', 'P', 'Q', 'R',
+    'S', 'T', 'U', 'V', 'W', 'X', 'Y', 'Z',
+    '0', '1', '2', '3', '4', '5', '6', '7', '8', '9',
+    '@', '#', '$', '%', '^', '&', '*', '(', ')', '_', '+', '-', '=', '[', ']', '{', '}', '|',
+    ';', ':', ',', '.', '<', '>', '?',
+    'ア', 'イ', 'ウ', 'エ', 'オ', 'カ', 'キ', 'ク', 'ケ', 'コ', 'サ', 'シ', 'ス', 'セ', 'ソ', 'タ',
+    'チ', 'ツ', 'テ', 'ト', 'ナ', 'ニ', 'ヌ', 'ネ', 'ノ', 'ハ', 'ヒ', 'フ', 'ヘ', 'ホ', 'マ', 'ミ',
+    'ム', 'メ', 'モ', 'ヤ', 'ユ', 'ヨ', 'ラ', 'リ', 'ル', 'レ', 'ロ', 'ワ', 'ヲ', 'ン',
+];
+
+/// Shortest and longest drops for a screen `travel` cells long: as set, but
+/// never longer than the screen, where they'd just fill whole columns.
+pub fn lengths(settings: &Settings, travel: u16) -> (usize, usize) {
+    let max = settings.max_length.min(travel as usize).max(1);
+    (settings.min_length.min(max), max)
+}
+
+/// A glyph from `CHARS`.
+pub fn random_char(rng: &mut impl Rng) -> char {
+    CHARS[rng.gen_range(0..CHARS.len())]
+}
+
+/// The glyphs for `--charset` and the like: a named part of `CHARS`
+/// (katakana, latin, digits or symbols), or else just the characters given.
+pub fn glyph_set(s: &str) -> Option<Vec<char>> {
+    let part = |keep: fn(&char) -> bool| Some(CHARS.iter().copied().filter(keep).collect());
+    match s.to_lowercase().as_str() {
+        "katakana" => part(|ch| ('\u{30a0}'..='\u{30ff}').contains(ch)),
+        "latin" => part(char::is_ascii_alphabetic),
+        "digits" => part(char::is_ascii_digit),
+        "symbols" => part(char::is_ascii_punctuation),
+        _ => {
+            let mut chars: Vec<char> = s.chars().filter(|ch| !ch.is_whitespace() && !ch.is_control()).collect();
+            chars.dedup();
+            (!chars.is_empty()).then_some(chars)
+        }
+    }
+}
+
+/// A glyph from `set` if there is one, else `CHARS`.
+pub fn pick_char(set: Option<&[char]>, rng: &mut impl Rng) -> char {
+    match set {
+        Some(set) => set[rng.gen_range(0..set.len())],
+        None => random_char(rng),
+    }
+}
+
+/// How a drop's glyphs change as it falls: the shimmer, and the head's
+/// own glyphs and pace.
+#[derive(Clone, Copy)]
+pub struct Churn<'a> {
+    pub shimmer: Option<(f32, usize)>, // Glyphs changed a step on average, and how far from the head
+    pub head_glyphs: Option<&'a [char]>, // What the head is drawn from, None = `CHARS`
+    pub head_mutate: Option<f64>, // Chance the head changes each step, None = with the shimmer
+}
+
+/// How far across the travel axis drops steer clear of an avoided point...
+const AVOID_RADIUS: f32 = 4.0;
+/// ...starting this many cells before they reach it...
+const AVOID_AHEAD: i32 = 8;
+/// ...and until this many after.
+const AVOID_BEHIND: i32 = 2;
+
+/// Cells from the head that still shimmer with `Shimmer::Head`.
+const SHIMMER_HEAD: usize = 3;
+
+/// How heavy `Distribution::HeavyTail`'s tail is, lower being heavier.
+const TAIL: f64 = 1.2;
+
+/// Stands in a trail's glyphs for a dark cell with `--gaps`.
+const GAP: char = '\0';
+
+/// Which way drops travel. Drops track their position along the travel axis
+/// and across it, and the direction maps that pair onto screen cells.
+#[derive(Clone, Copy, PartialEq)]
+pub enum Direction {
+    Down,
+    Up,
+    Left,
+    Right,
+}
+
+impl Direction {
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "down" => Some(Self::Down),
+            "up" => Some(Self::Up),
+            "left" => Some(Self::Left),
+            "right" => Some(Self::Right),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Down => "down",
+            Self::Up => "up",
+            Self::Left => "left",
+            Self::Right => "right",
+        }
+    }
+
+    pub fn reversed(self) -> Self {
+        match self {
+            Self::Down => Self::Up,
+            Self::Up => Self::Down,
+            Self::Left => Self::Right,
+            Self::Right => Self::Left,
+        }
+    }
+
+    pub fn is_vertical(self) -> bool {
+        matches!(self, Self::Down | Self::Up)
+    }
+
+    /// Cells the head moves along the travel axis per step.
+    pub fn step(self) -> i32 {
+        match self {
+            Self::Down | Self::Right => 1,
+            Self::Up | Self::Left => -1,
+        }
+    }
+
+    /// Screen size as (length of the travel axis, length of the cross axis).
+    pub fn extents(self, width: u16, height: u16) -> (u16, u16) {
+        if self.is_vertical() {
+            (height, width)
+        } else {
+            (width, height)
+        }
+    }
+
+    /// Position along and across the travel axis for a screen cell.
+    pub fn axes(self, x: i32, y: i32) -> (i32, i32) {
+        if self.is_vertical() {
+            (y, x)
+        } else {
+            (x, y)
+        }
+    }
+
+    /// Screen cell for a position along and across the travel axis.
+    pub fn cell(self, along: i32, across: i32) -> (i32, i32) {
+        if self.is_vertical() {
+            (across, along)
+        } else {
+            (along, across)
+        }
+    }
+}
+
+/// What happens when a drop catches up with the one ahead of it in its
+/// column, `--collisions`.
+#[derive(Clone, Copy, PartialEq)]
+pub enum Collisions {
+    Pass,  // It runs through, its head drawn over the other's trail
+    Merge, // The two join into one longer drop at the faster speed
+    Block, // It waits behind the other's tail
+}
+
+impl Collisions {
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "pass" => Some(Self::Pass),
+            "merge" => Some(Self::Merge),
+            "block" => Some(Self::Block),
+            _ => None,
+        }
+    }
+}
+
+/// How drop lengths or speeds spread out between their least and most,
+/// `--length-distribution` and `--speed-distribution`.
+#[derive(Clone, Copy, PartialEq)]
+pub enum Distribution {
+    Uniform,   // Any as likely as any other
+    Normal,    // Mostly around the middle, rarely out near the ends
+    HeavyTail, // Mostly near the least, with the odd one far out toward the most
+}
+
+impl Distribution {
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "uniform" | "flat" => Some(Self::Uniform),
+            "normal" | "gaussian" => Some(Self::Normal),
+            "heavy-tail" | "heavy" | "pareto" => Some(Self::HeavyTail),
+            _ => None,
+        }
+    }
+
+    /// A value from `min..=max`.
+    pub fn sample<T, R>(self, min: T, max: T, rng: &mut R) -> T
+    where
+        T: SampleUniform + PartialOrd + Copy + TryInto<usize> + TryFrom<usize>,
+        R: Rng + ?Sized,
+    {
+        let (Ok(low), Ok(high)) = (min.try_into(), max.try_into()) else {
+            return rng.gen_range(min..=max);
+        };
+        if self == Self::Uniform || low >= high {
+            return rng.gen_range(min..=max);
+        }
+        let span = (high - low) as f64;
+        let offset = match self {
+            // Box-Muller, with the ends three deviations out
+            Self::Normal => {
+                let (u, v): (f64, f64) = (rng.gen_range(f64::EPSILON..1.0), rng.gen());
+                let z = (-2.0 * u.ln()).sqrt() * (std::f64::consts::TAU * v).cos();
+                (span / 2.0 + z * span / 6.0).round()
+            }
+            // Pareto from 1, cut off past the most
+            _ => {
+                let cut = 1.0 - (span + 2.0).powf(-TAIL);
+                (1.0 - rng.gen::<f64>() * cut).powf(-1.0 / TAIL).floor() - 1.0
+            }
+        };
+        T::try_from(low + offset.clamp(0.0, span) as usize).unwrap_or(max)
+    }
+
+    /// What `sample` comes to on average, near enough.
+    pub fn mean(self, min: f64, max: f64) -> f64 {
+        let span = (max - min).max(0.0);
+        match self {
+            Self::Uniform | Self::Normal => min + span / 2.0,
+            Self::HeavyTail => {
+                let top = span + 2.0;
+                let pareto = TAIL / (TAIL - 1.0) * (1.0 - top.powf(1.0 - TAIL)) / (1.0 - top.powf(-TAIL));
+                min + (pareto - 1.5).max(0.0)
+            }
+        }
+    }
+}
+
+/// Which of a drop's glyphs flicker to other characters as it falls,
+/// `--shimmer-head` and the `g` key.
+#[derive(Clone, Copy, PartialEq)]
+pub enum Shimmer {
+    Trail, // Anywhere along it
+    Head,  // Only the few cells nearest the head
+    Off,   // None, for a calmer look
+}
+
+impl Shimmer {
+    /// Cells from the head that can change, None for none.
+    pub fn reach(self) -> Option<usize> {
+        match self {
+            Self::Trail => Some(usize::MAX),
+            Self::Head => Some(SHIMMER_HEAD),
+            Self::Off => None,
+        }
+    }
+
+    /// The next in the cycle the key steps through.
+    pub fn next(self) -> Self {
+        match self {
+            Self::Trail => Self::Head,
+            Self::Head => Self::Off,
+            Self::Off => Self::Trail,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Trail => "whole trail",
+            Self::Head => "head only",
+            Self::Off => "off",
+        }
+    }
+}
+
+/// Depth layer a drop belongs to; back drops are stamped first so front ones
+/// always end up on top.
+#[derive(Clone, Copy, PartialEq)]
+pub enum Layer {
+    Back,
+    Front,
+}
+
+impl Layer {
+    /// How much a drop's colors are dimmed to sit at this depth.
+    pub fn brightness(self) -> f32 {
+        match self {
+            Self::Back => 0.45,
+            Self::Front => 1.0,
+        }
+    }
+}
+
+pub struct Drop {
+    pub layer: Layer,
+    along: i32,  // Head position along the travel axis (the row when falling)
+    across: f32, // Fractional position across it, so wind can drift the drop slowly
+    direction: Direction,
+    pub speed: u8,
+    length: usize,
+    chars: Vec<char>,
+    charset: Option<Rc<[char]>>, // What its glyphs are drawn from, None = `CHARS`
+    trail: VecDeque<(i32, i32)>, // Cells covered by the trail, head first
+    drift: f32,                  // How strongly this drop catches the wind
+    heading: f32,                // How far across it moved on its last step
+    boost: f32,                  // Speed gained from gravity, in steps per frame
+    progress: f32,               // Fraction of the way to the next step
+    stretch: usize,              // Extra length the drop may still grow by
+    split_at: Option<i32>,       // Position along the travel axis where it branches
+    splitting: bool,             // Reached `split_at` and waiting for `branch`
+    severed: bool,               // Head stopped at a split; the trail drains away
+    life: Option<u32>,           // Steps until it dissolves, with `--dissolve-chance`
+    dissolving: bool,            // Stopped in mid-air; the trail evaporates up to the head
+}
+
+impl Drop {
+    pub fn new(
+        across: f32,
+        travel: u16,
+        layer: Layer,
+        settings: &Settings,
+        rng: &mut impl Rng,
+    ) -> Self {
+        Self::build(across, travel, layer, settings, rng, vec![], VecDeque::new())
+    }
+
+    /// A new drop in place of this finished one, keeping the memory it
+    /// had for glyphs and trail so spawning allocates nothing.
+    pub fn renew(
+        mut self,
+        across: f32,
+        travel: u16,
+        layer: Layer,
+        settings: &Settings,
+        rng: &mut impl Rng,
+    ) -> Self {
+        let (mut chars, mut trail) = (std::mem::take(&mut self.chars), std::mem::take(&mut self.trail));
+        chars.clear();
+        trail.clear();
+        Self::build(across, travel, layer, settings, rng, chars, trail)
+    }
+
+    fn build(
+        across: f32,
+        travel: u16,
+        layer: Layer,
+        settings: &Settings,
+        rng: &mut impl Rng,
+        mut chars: Vec<char>,
+        mut trail: VecDeque<(i32, i32)>,
+    ) -> Self {
+        let (min_length, max_length) = lengths(settings, travel);
+        let mut length = settings.length_distribution.sample(min_length, max_length, rng);
+        let mut speed = settings.speed_distribution.sample(settings.min_speed, settings.max_speed, rng);
+        if layer == Layer::Back {
+            // Far away: shorter and slower, for parallax
+            length = (length * 3 / 5).max(3).min(max_length);
+            speed = speed.saturating_add(2);
+        }
+        // Start just off the edge the drop travels in from
+        let forward = settings.direction.step() > 0;
+        let along = if forward {
+            rng.gen_range(-30..0)
+        } else {
+            travel as i32 + rng.gen_range(0..30)
+        };
+
+        // Branch somewhere in the middle half of the screen
+        let split_at = rng.gen_bool(settings.branch_chance).then(|| {
+            let depth = (travel as f32 * rng.gen_range(0.25..0.75)) as i32;
+            if forward {
+                depth
+            } else {
+                travel as i32 - depth
+            }
+        });
+
+        // Dissolve somewhere between just on screen and nearly through,
+        // however far off the edge it starts. Without the option the roll
+        // is skipped, so seeded rain stays as it was
+        let dissolves = settings.dissolve_chance > 0.0 && rng.gen_bool(settings.dissolve_chance);
+        let life = dissolves.then(|| {
+            let offscreen = if forward { -along } else { along - travel as i32 };
+            offscreen as u32 + (travel as f32 * rng.gen_range(0.1..0.9)) as u32
+        });
+
+        let charset = match layer {
+            Layer::Back => settings.back_charset.as_ref().or(settings.charset.as_ref()),
+            Layer::Front => settings.charset.as_ref(),
+        };
+        chars.extend((0..length).map(|_| pick_char(charset.map(Rc::as_ref), rng)));
+        if let Some(set) = &settings.head_glyphs {
+            chars[0] = pick_char(Some(set), rng);
+        }
+        // Broken up by a few dark stretches behind the head, like the
+        // films' broken streams. Again no roll without the option
+        if settings.gap_chance > 0.0 && rng.gen_bool(settings.gap_chance) && length > 3 {
+            for _ in 0..rng.gen_range(1..=(length / 6).max(1)) {
+                let start = rng.gen_range(2..length);
+                let end = (start + rng.gen_range(1..=3)).min(length);
+                chars[start..end].fill(GAP);
+            }
+        }
+        trail.reserve(length + 1);
+        Drop {
+            layer,
+            along,
+            across,
+            direction: settings.direction,
+            speed,
+            length,
+            chars,
+            charset: charset.cloned(),
+            trail,
+            drift: rng.gen_range(0.8..1.2),
+            heading: 0.0,
+            boost: 0.0,
+            progress: 0.0,
+            stretch: if length >= 20 { length / 4 } else { 0 },
+            split_at,
+            splitting: false,
+            severed: false,
+            life,
+            dissolving: false,
+        }
+    }
+
+    /// If the drop just reached its split point, stop its head and return two
+    /// shorter children that carry on from the neighbouring cells. With
+    /// `keep_head` both children get the parent's head glyph.
+    pub fn branch(&mut self, keep_head: bool) -> Option<[Drop; 2]> {
+        if !self.splitting {
+            return None;
+        }
+        self.splitting = false;
+        self.severed = true;
+
+        let length = (self.length / 2).max(3);
+        let child = |offset: f32, chars: &[char]| {
+            let mut chars = chars.to_vec();
+            chars.resize(length, chars.last().copied().unwrap_or(' '));
+            // A child whose head would fall in a gap gets the parent's too
+            if keep_head || chars[0] == GAP {
+                chars[0] = self.chars[0];
+            }
+            Drop {
+                layer: self.layer,
+                along: self.along,
+                across: self.across + offset,
+                direction: self.direction,
+                speed: self.speed,
+                length,
+                chars,
+                charset: self.charset.clone(),
+                trail: VecDeque::with_capacity(length + 1),
+                drift: self.drift,
+                heading: self.heading,
+                boost: self.boost,
+                progress: self.progress,
+                stretch: 0,
+                split_at: None,
+                splitting: false,
+                severed: false,
+                life: self.life,
+                dissolving: false,
+            }
+        };
+        let (left, right) = self.chars.split_at(self.length / 2);
+        Some([child(-1.0, left), child(1.0, right)])
+    }
+
+    /// Steps per frame: one step every `speed` frames, plus any gravity boost.
+    fn velocity(&self) -> f32 {
+        (1.0 / self.speed as f32 + self.boost).min(1.0)
+    }
+
+    /// Visible trail cells with their glyphs and colors, head first, less
+    /// any gaps. `paint` colors glyph `i` of a drop `length` long in column
+    /// `x`.
+    pub fn glyphs<'a>(
+        &'a self,
+        width: u16,
+        height: u16,
+        paint: impl Fn(usize, usize, u16, u16) -> Color + 'a,
+    ) -> impl Iterator<Item = (u16, u16, char, Color)> + 'a {
+        // A severed drop has no head left, so skip the bright head colors
+        let offset = if self.severed { 2 } else { 0 };
+        let brightness = self.layer.brightness();
+        self.chars
+            .iter()
+            .zip(&self.trail)
+            .enumerate()
+            .filter(move |(_, (&ch, &(x, y)))| {
+                ch != GAP && x >= 0 && x < width as i32 && y >= 0 && y < height as i32
+            })
+            .map(move |(i, (&ch, &(x, y)))| {
+                let color = paint(i + offset, self.length, x as u16, y as u16);
+                (x as u16, y as u16, ch, dim(color, brightness))
+            })
+    }
+
+    #[cfg(feature = "lua")]
+    pub fn length(&self) -> usize {
+        self.length
+    }
+
+    /// Give a drop that hasn't moved yet a new length and speed.
+    #[cfg(feature = "lua")]
+    pub fn reshape(&mut self, length: usize, speed: u8, rng: &mut impl Rng) {
+        self.length = length.clamp(1, 200);
+        self.speed = speed.max(1);
+        let charset = self.charset.as_deref();
+        self.chars.resize_with(self.length, || pick_char(charset, rng));
+    }
+
+    /// Bend away from the point (`along`, `across`) when it's just ahead of
+    /// or beside the head, harder the closer it is.
+    pub fn avoid(&mut self, along: i32, across: f32, time: f32) {
+        let ahead = (along - self.along) * self.direction.step();
+        let gap = self.across - across;
+        if (-AVOID_BEHIND..AVOID_AHEAD).contains(&ahead) && gap.abs() < AVOID_RADIUS {
+            let push = (AVOID_RADIUS - gap.abs()) / AVOID_RADIUS * 0.5 * time;
+            self.across += if gap < 0.0 { -push } else { push };
+        }
+    }
+
+    /// Move a drop that hasn't appeared yet so its head starts at `along`
+    /// instead of off the edge.
+    pub fn start_at(&mut self, along: i32) {
+        self.along = along;
+    }
+
+    /// Position across the travel axis, e.g. the column when falling.
+    pub fn across(&self) -> f32 {
+        self.across
+    }
+
+    /// The column the head is in, or the row when the rain runs sideways.
+    pub fn column(&self) -> i32 {
+        self.across.round() as i32
+    }
+
+    /// How far the head has come along the travel axis, whichever way that
+    /// runs: bigger is further.
+    pub fn travelled(&self) -> i32 {
+        self.along * self.direction.step()
+    }
+
+    /// Cells from the head to the tail of `ahead`, a drop further along
+    /// the same column: 1 when they touch, 0 or less once they overlap.
+    pub fn gap(&self, ahead: &Drop) -> i32 {
+        let tail = ahead.travelled() - ahead.trail.len().saturating_sub(1) as i32;
+        tail - self.travelled()
+    }
+
+    /// Take in `behind`, which has just run into this drop's tail: its
+    /// trail carries on from the end of this one's, and the two go on as
+    /// one longer drop at the faster speed.
+    pub fn absorb(&mut self, behind: &Drop) {
+        let inside = (1 - behind.gap(self)).max(0) as usize;
+        self.chars.extend(behind.chars.iter().skip(inside));
+        self.trail.extend(behind.trail.iter().skip(inside));
+        self.length = self.chars.len();
+        self.speed = self.speed.min(behind.speed);
+        self.boost = self.boost.max(behind.boost);
+        self.stretch = 0;
+    }
+
+    /// Whether the drop still has its head and isn't splitting, so it can
+    /// run into others and be run into.
+    pub fn is_whole(&self) -> bool {
+        !self.severed && !self.splitting && !self.dissolving
+    }
+
+    /// Where the bright head is, if the drop still has one.
+    pub fn head(&self) -> Option<(i32, i32)> {
+        if self.severed {
+            None
+        } else {
+            self.trail.front().copied()
+        }
+    }
+
+    /// A splash `reach` cells to each side of the head, if it's on the
+    /// last row of a `width` x `height` grid it's heading for (the last
+    /// column when the rain runs sideways).
+    pub fn splash(&self, width: u16, height: u16, reach: u16, rng: &mut impl Rng) -> Option<Splash> {
+        let (travel, _) = self.direction.extents(width, height);
+        let far = if self.direction.step() > 0 { travel as i32 - 1 } else { 0 };
+        let head = self.head().filter(|_| self.along == far)?;
+        Some(Splash::new(head, self.direction, reach, self.charset.as_deref(), rng))
+    }
+
+    /// Advance by `time` frames, a fraction in bullet time, with `churn`
+    /// saying how its glyphs change along the way.
+    pub fn update(
+        &mut self,
+        slope: f32,
+        wind: f32,
+        gravity: f32,
+        time: f32,
+        churn: Churn,
+        rng: &mut impl Rng,
+    ) {
+        self.boost += gravity * time;
+        self.progress += self.velocity() * time;
+        // Tolerate float error so a drop of speed n steps on exactly every nth frame
+        if self.progress < 1.0 - f32::EPSILON * 8.0 {
+            return;
+        }
+        self.progress = (self.progress - 1.0).max(0.0);
+
+        // Out of life: it stops where it is and evaporates
+        if self.life == Some(0) {
+            self.life = None;
+            self.dissolving = true;
+        }
+        if self.severed || self.dissolving {
+            // No head any more, or not going anywhere: eat what's left
+            // from the tail
+            self.trail.pop_back();
+            return;
+        }
+
+        // Long drops stretch a little once they pick up speed
+        if gravity > 0.0 && self.stretch > 0 && self.boost > 0.2 && rng.gen_bool(0.15) {
+            self.chars.push(pick_char(self.charset.as_deref(), rng));
+            self.length += 1;
+            self.stretch -= 1;
+        }
+
+        if let Some(life) = &mut self.life {
+            *life -= 1;
+        }
+        self.along += self.direction.step();
+        self.heading = slope + wind * self.drift;
+        self.across += self.heading;
+        let head = self.direction.cell(self.along, self.across.round() as i32);
+        self.trail.push_front(head);
+        if self.split_at == Some(self.along) {
+            self.split_at = None;
+            self.splitting = true;
+        }
+
+        // Shimmer: up to twice the rate's next whole number of glyphs can
+        // change, each as likely as makes the rate come out on average
+        let Churn { shimmer, head_glyphs: set, head_mutate: mutate } = churn;
+        let first = usize::from(mutate.is_some()); // Leave the head to itself
+        if let Some((rate, reach)) = shimmer.filter(|&(rate, _)| rate > 0.0) {
+            let whole = rate.ceil();
+            let shimmer_count = rng.gen_range(0..=2 * whole as u32);
+            for _ in 0..shimmer_count {
+                if rng.gen_bool((rate / whole) as f64) && first < self.length.min(reach) {
+                    let idx = rng.gen_range(first..self.length.min(reach));
+                    let ch = match idx {
+                        0 => pick_char(set.or(self.charset.as_deref()), rng),
+                        _ => pick_char(self.charset.as_deref(), rng),
+                    };
+                    // A gap stays dark
+                    if self.chars[idx] != GAP {
+                        self.chars[idx] = ch;
+                    }
+                }
+            }
+        }
+        if mutate.is_some_and(|chance| rng.gen_bool(chance)) {
+            self.chars[0] = pick_char(set.or(self.charset.as_deref()), rng);
+        }
+
+        // Drop the tail; the buffer fades the cell it leaves behind
+        if self.trail.len() > self.length {
+            self.trail.pop_back();
+        }
+    }
+
+    pub fn is_done(&self, width: u16, height: u16) -> bool {
+        let (travel, cross) = self.direction.extents(width, height);
+        let vertical = self.direction.is_vertical();
+        // Off one side and drifting further out, not an upwind spawn
+        // that has yet to blow on
+        let side = |past: fn(i32, i32) -> bool| {
+            self.trail.iter().all(|&(x, y)| past(if vertical { x } else { y }, cross as i32))
+        };
+        let blown_away = !self.trail.is_empty()
+            && ((self.heading <= 0.0 && side(|across, _| across < 0))
+                || (self.heading >= 0.0 && side(|across, cross| across >= cross)));
+        let passed = if self.direction.step() > 0 {
+            self.along - self.length as i32 > travel as i32
+        } else {
+            self.along + (self.length as i32) < 0
+        };
+        let drained = (self.severed || self.dissolving) && self.trail.is_empty();
+        passed || blown_away || drained
+    }
+}
+
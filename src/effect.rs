@@ -0,0 +1,112 @@
+use rand::{rngs::SmallRng, Rng};
+
+#[cfg(feature = "plugins")]
+use crate::plugin::PluginEffect;
+use crate::{
+    buffer::PhosphorBuffer,
+    drop::Layer,
+    platform::KeyEvent,
+    rain::MatrixRain,
+    sakura::Sakura,
+    settings::{EffectKind, Settings},
+    snow::Snow,
+};
+
+/// Draw from a Poisson distribution with mean `lambda`.
+pub fn sample_poisson(rng: &mut impl Rng, lambda: f64) -> usize {
+    if lambda <= 0.0 {
+        return 0;
+    }
+    if lambda > 30.0 {
+        // Normal approximation (Box-Muller), exact enough for large rates
+        let u1: f64 = rng.gen_range(f64::EPSILON..1.0);
+        let u2: f64 = rng.gen();
+        let z = (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos();
+        return (lambda + z * lambda.sqrt()).round().max(0.0) as usize;
+    }
+    // Knuth's multiplication method
+    let limit = (-lambda).exp();
+    let mut product: f64 = rng.gen();
+    let mut count = 0;
+    while product > limit {
+        product *= rng.gen::<f64>();
+        count += 1;
+    }
+    count
+}
+
+/// What the weather is doing this frame. Every effect gets the same, so
+/// `--storm` and `--wind` work whatever is falling.
+pub struct Weather {
+    pub wind: f32,      // Columns per row things drift, gusts included
+    pub intensity: f64, // How heavily it's coming down, 1.0 = steady
+    pub time: f32,      // How fast time passes, 1.0 = normal, less in bullet time
+    pub avoid: Option<(u16, u16)>, // Simulation cell things steer around, from the mouse
+}
+
+/// A bright leading point an effect is drawing, such as a drop head, on the
+/// simulation grid. Used by `--glow`, `--qr`, and `--stuck-glyphs`.
+pub struct Head {
+    pub x: u16,
+    pub y: u16,
+    pub layer: Layer,
+}
+
+/// An animation the screensaver can run. Effects simulate on the simulation
+/// grid and light up the shared phosphor buffer; the main loop takes care of
+/// the terminal, weather, overlays, and drawing, so new effects don't have
+/// to touch it.
+pub trait Effect {
+    /// The simulation grid is now `width` x `height`.
+    fn resize(&mut self, width: u16, height: u16);
+
+    /// Advance by one frame.
+    fn update(&mut self, settings: &Settings, weather: &Weather, rng: &mut SmallRng);
+
+    /// Light up this frame's glyphs.
+    fn render(&self, buffer: &mut PhosphorBuffer, settings: &Settings);
+
+    /// Light up the glyphs that sit behind the rest, on the back layer,
+    /// which fades apart from the front one. Most effects are all front.
+    fn render_back(&self, _buffer: &mut PhosphorBuffer, _settings: &Settings) {}
+
+    /// React to a key the main loop offers before its own controls. Returns
+    /// whether the key was used.
+    fn handle_key(&mut self, _key: KeyEvent, _settings: &mut Settings) -> bool {
+        false
+    }
+
+    /// Start `count` new things around cell (`x`, `y`) of the simulation
+    /// grid, e.g. where the mouse was clicked.
+    fn spawn_at(
+        &mut self,
+        _x: u16,
+        _y: u16,
+        _count: usize,
+        _settings: &Settings,
+        _rng: &mut SmallRng,
+    ) {
+    }
+
+    /// Fill `heads` with the bright heads currently on screen, in place of
+    /// what it had, so the same buffer does every tick.
+    fn heads(&self, heads: &mut Vec<Head>) {
+        heads.clear();
+    }
+
+    /// How many things are moving, for the debug overlay.
+    fn population(&self) -> usize;
+}
+
+/// The effect to run for `--effect`.
+pub fn build(settings: &Settings) -> Box<dyn Effect> {
+    #[cfg(feature = "plugins")]
+    if let Some(plugin) = &settings.effect_plugin {
+        return Box::new(PluginEffect::new(plugin.clone()));
+    }
+    match settings.effect {
+        EffectKind::Rain => Box::new(MatrixRain::default()),
+        EffectKind::Snow => Box::new(Snow::default()),
+        EffectKind::Sakura => Box::new(Sakura::default()),
+    }
+}
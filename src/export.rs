@@ -0,0 +1,66 @@
+//! `matrix export`: render simulated frames to numbered PNG files, for
+//! wallpapers, or videos via ffmpeg.
+
+use std::path::PathBuf;
+
+use crate::{cli, matrix::Matrix, raster::Rasterizer};
+
+/// Parse `AxB` as two positive numbers.
+fn pair(text: &str) -> Option<(u32, u32)> {
+    let (a, b) = text.split_once('x')?;
+    let (a, b) = (a.trim().parse().ok()?, b.trim().parse().ok()?);
+    (a > 0 && b > 0).then_some((a, b))
+}
+
+/// Run `matrix export [--frames N] [--out DIR] [--size COLSxROWS]
+/// [--cell WxH] [--font FILE] [OPTIONS]`.
+pub fn run(args: &[String]) -> std::io::Result<()> {
+    let mut frames: u32 = 100;
+    let mut dir = PathBuf::from("frames");
+    let mut size = (80, 24);
+    let mut cell = (10, 20);
+    let mut font = None;
+    let mut options = vec![];
+    let mut i = 0;
+    while i < args.len() {
+        let val = args.get(i + 1);
+        match (args[i].as_str(), val) {
+            ("--frames", Some(val)) => frames = val.parse().unwrap_or(frames),
+            ("--out", Some(val)) => dir = PathBuf::from(val),
+            ("--size", Some(val)) => size = pair(val).unwrap_or(size),
+            ("--cell", Some(val)) => cell = pair(val).unwrap_or(cell),
+            ("--font", Some(val)) => match Rasterizer::load_font(val) {
+                Ok(loaded) => font = Some(loaded),
+                Err(err) => {
+                    eprintln!("Could not load font {}: {}", val, err);
+                    std::process::exit(1);
+                }
+            },
+            _ => {
+                options.push(args[i].clone());
+                i += 1;
+                continue;
+            }
+        }
+        i += 2;
+    }
+
+    let (cols, rows) = (size.0.min(u16::MAX as u32), size.1.min(u16::MAX as u32));
+    let mut matrix = Matrix::with_size(cli::parse(&options), cols as u16, rows as u16);
+    let rasterizer = Rasterizer::new(cell.0, cell.1, font);
+    std::fs::create_dir_all(&dir)?;
+    let digits = frames.to_string().len().max(5);
+    for n in 1..=frames {
+        let image = rasterizer.render(&matrix.render_frame());
+        let path = dir.join(format!("frame_{:0digits$}.png", n, digits = digits));
+        image.save(&path).map_err(std::io::Error::other)?;
+    }
+    println!(
+        "Wrote {} frames to {} (ffmpeg -framerate 30 -i {}/frame_%0{}d.png rain.mp4)",
+        frames,
+        dir.display(),
+        dir.display(),
+        digits
+    );
+    Ok(())
+}
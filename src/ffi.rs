@@ -0,0 +1,135 @@
+//! A C API over the rain, for programs in other languages to run the
+//! simulation and draw the frames however they like:
+//!
+//! ```c
+//! typedef struct Matrix Matrix;
+//!
+//! // Colors are 0xRRGGBB, or MATRIX_DEFAULT_COLOR for the terminal's own
+//! #define MATRIX_DEFAULT_COLOR 0xFFFFFFFF
+//! typedef struct { uint32_t ch, fg, bg; } MatrixFrameCell;
+//!
+//! // NULL if the options, as on the command line, don't make sense
+//! Matrix *matrix_new(uint16_t cols, uint16_t rows, const char *options);
+//! void matrix_resize(Matrix *matrix, uint16_t cols, uint16_t rows);
+//! // Advance a frame; returns the milliseconds to wait before the next
+//! uint32_t matrix_tick(Matrix *matrix);
+//! // Copy up to cap cells, row by row; returns how many the frame has
+//! size_t matrix_frame_cells(const Matrix *matrix, MatrixFrameCell *cells, size_t cap);
+//! void matrix_free(Matrix *matrix);
+//! ```
+
+use std::ffi::{c_char, CStr};
+
+use crate::{
+    cli,
+    console::{ansi_rgb, PALETTE},
+    matrix::Matrix,
+    platform::Color,
+};
+
+/// A color the terminal picks, its default foreground or background.
+const DEFAULT_COLOR: u32 = u32::MAX;
+
+/// A cell of the frame, as `matrix_frame_cells` copies it out.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct MatrixFrameCell {
+    ch: u32, // Unicode scalar value
+    fg: u32, // 0xRRGGBB or `DEFAULT_COLOR`
+    bg: u32,
+}
+
+/// A rain `cols` x `rows` cells big, with `options` as on the command line,
+/// e.g. `"-c rainbow -d 60"`, or the defaults for NULL. Options that would
+/// print and exit, `--help` and `--list-plugins`, make it NULL too.
+///
+/// # Safety
+///
+/// `options` must be NULL or a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn matrix_new(cols: u16, rows: u16, options: *const c_char) -> *mut Matrix {
+    let args: Vec<String> = match options.is_null() {
+        true => vec![],
+        false => CStr::from_ptr(options).to_string_lossy().split_whitespace().map(String::from).collect(),
+    };
+    match cli::try_parse_embedded(&args) {
+        Ok(settings) if settings.help || settings.list_plugins => std::ptr::null_mut(),
+        Ok(settings) => Box::into_raw(Box::new(Matrix::with_size(settings, cols, rows))),
+        Err(_) => std::ptr::null_mut(),
+    }
+}
+
+/// The screen changed size.
+///
+/// # Safety
+///
+/// `matrix` must come from `matrix_new` and not have been freed.
+#[no_mangle]
+pub unsafe extern "C" fn matrix_resize(matrix: *mut Matrix, cols: u16, rows: u16) {
+    if let Some(matrix) = matrix.as_mut() {
+        matrix.resize(cols, rows);
+    }
+}
+
+/// Advance by one frame, returning the milliseconds until the next.
+///
+/// # Safety
+///
+/// `matrix` must come from `matrix_new` and not have been freed.
+#[no_mangle]
+pub unsafe extern "C" fn matrix_tick(matrix: *mut Matrix) -> u32 {
+    let Some(matrix) = matrix.as_mut() else {
+        return 0;
+    };
+    matrix.step();
+    matrix.frame_delay().as_millis() as u32
+}
+
+/// Copy the frame into `cells`, row by row, up to `cap` of them, and
+/// return how many cells the whole frame has, so a caller can ask with a
+/// `cap` of 0 to size its buffer.
+///
+/// # Safety
+///
+/// `matrix` must come from `matrix_new` and not have been freed, and
+/// `cells` must have room for `cap` cells, or be NULL with `cap` 0.
+#[no_mangle]
+pub unsafe extern "C" fn matrix_frame_cells(matrix: *const Matrix, cells: *mut MatrixFrameCell, cap: usize) -> usize {
+    let Some(matrix) = matrix.as_ref() else {
+        return 0;
+    };
+    let frame = matrix.frame();
+    if !cells.is_null() {
+        let out = std::slice::from_raw_parts_mut(cells, cap.min(frame.len()));
+        for (cell, glyph) in out.iter_mut().zip(&frame) {
+            *cell = MatrixFrameCell { ch: glyph.ch as u32, fg: rgb(glyph.fg), bg: rgb(glyph.bg) };
+        }
+    }
+    frame.len()
+}
+
+/// Done with the rain.
+///
+/// # Safety
+///
+/// `matrix` must be NULL or come from `matrix_new`, and not be used again.
+#[no_mangle]
+pub unsafe extern "C" fn matrix_free(matrix: *mut Matrix) {
+    if !matrix.is_null() {
+        drop(Box::from_raw(matrix));
+    }
+}
+
+/// `color` as 0xRRGGBB.
+fn rgb(color: Color) -> u32 {
+    let (r, g, b) = match color {
+        Color::Reset => return DEFAULT_COLOR,
+        Color::Rgb { r, g, b } => (r, g, b),
+        Color::AnsiValue(index) => ansi_rgb(index),
+        named => match PALETTE.iter().find(|&&(c, _)| c == named) {
+            Some(&(_, rgb)) => rgb,
+            None => return DEFAULT_COLOR,
+        },
+    };
+    u32::from_be_bytes([0, r, g, b])
+}
@@ -0,0 +1,126 @@
+//! `--weather <LOCATION>`: the rain follows the real weather outside. The
+//! current conditions come from wttr.in, fetched with `curl` every quarter
+//! of an hour, and set how hard it rains, how fast, which way the wind
+//! blows it and, for drizzle, fog and storms, how it looks.
+
+use std::{
+    process::Command,
+    sync::mpsc::{self, Receiver},
+    thread,
+    time::Duration,
+};
+
+use crate::settings::Settings;
+
+/// How often the weather is fetched again.
+const REFRESH: Duration = Duration::from_secs(15 * 60);
+
+/// How soon to try again after a fetch failed, say while offline.
+const RETRY: Duration = Duration::from_secs(60);
+
+/// What wttr.in is asked for: precipitation, wind and a description,
+/// e.g. `0.4mm|↗12km/h|Light rain`.
+const FORMAT: &str = "%p|%w|%C";
+
+/// The weather outside right now.
+pub struct Conditions {
+    pub rain_mm: f64,        // Precipitation over the last three hours
+    pub wind_kmh: f64,       // Wind speed
+    pub eastward: f64,       // How much of the wind blows east, -1.0 is due west
+    pub description: String, // As wttr.in puts it, e.g. "Light rain"
+}
+
+impl Conditions {
+    /// Parse wttr.in's answer to `FORMAT`.
+    fn parse(s: &str) -> Option<Self> {
+        let mut fields = s.trim().split('|');
+        let (rain, wind, description) = (fields.next()?, fields.next()?, fields.next()?);
+        let rain_mm = rain.trim().trim_end_matches("mm").parse().ok()?;
+        // The arrow points the way the wind blows
+        let wind = wind.trim();
+        let eastward = match wind.chars().next()? {
+            '→' => 1.0,
+            '↗' | '↘' => 0.7,
+            '←' => -1.0,
+            '↖' | '↙' => -0.7,
+            _ => 0.0,
+        };
+        let speed = wind.trim_start_matches(|c: char| !c.is_ascii_digit()).trim_end_matches("km/h");
+        Some(Conditions {
+            rain_mm,
+            wind_kmh: speed.trim().parse().ok()?,
+            eastward,
+            description: description.trim().to_string(),
+        })
+    }
+
+    /// Thunder about, for lightning in the rain.
+    pub fn is_stormy(&self) -> bool {
+        self.description.to_lowercase().contains("thunder")
+    }
+
+    /// How far the colors wash out toward grey: drizzle, mist and fog take
+    /// most of it.
+    pub fn grey(&self) -> f32 {
+        let description = self.description.to_lowercase();
+        if ["drizzle", "mist", "fog", "haze"].iter().any(|word| description.contains(word)) {
+            0.7
+        } else if description.contains("overcast") {
+            0.4
+        } else {
+            0.0
+        }
+    }
+
+    /// Put the weather into `settings`: a dry day still has a thin rain,
+    /// and the harder it rains the denser and faster it gets.
+    pub fn apply(&self, settings: &mut Settings) {
+        settings.density = (0.1 + self.rain_mm * 0.15).min(1.0);
+        (settings.min_speed, settings.max_speed) = match self.rain_mm {
+            mm if mm < 0.5 => (2, 4),
+            mm if mm < 3.0 => (1, 3),
+            _ => (1, 2),
+        };
+        // A strong breeze, 40 km/h, leans it as far as the wind keys go
+        settings.wind = (self.eastward * self.wind_kmh / 40.0).clamp(-1.0, 1.0) as f32;
+    }
+}
+
+/// The weather for a place, fetched in the background.
+pub struct Forecast {
+    updates: Receiver<Conditions>,
+}
+
+impl Forecast {
+    pub fn start(location: &str) -> Self {
+        let url = format!("http://wttr.in/{}?m&format={}", location.trim().replace(' ', "+"), FORMAT);
+        let (tx, updates) = mpsc::channel();
+        thread::spawn(move || loop {
+            let wait = match fetch(&url) {
+                Some(conditions) => {
+                    if tx.send(conditions).is_err() {
+                        return;
+                    }
+                    REFRESH
+                }
+                None => RETRY,
+            };
+            thread::sleep(wait);
+        });
+        Forecast { updates }
+    }
+
+    /// The latest conditions, if they've come in since last time.
+    pub fn latest(&self) -> Option<Conditions> {
+        self.updates.try_iter().last()
+    }
+}
+
+/// The conditions at `url`, None if it couldn't be reached or made sense of.
+fn fetch(url: &str) -> Option<Conditions> {
+    let output = Command::new("curl").args(["-fsS", "--max-time", "15", url]).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Conditions::parse(&String::from_utf8_lossy(&output.stdout))
+}
@@ -0,0 +1,115 @@
+//! `--forever`: the rain run as a child process and started over whenever
+//! it fails, say when the terminal hangs up under it or stops taking its
+//! output, for unattended kiosk terminals. Quitting on purpose still quits.
+
+use std::{
+    env,
+    io::{self, stdout, IsTerminal, Write},
+    process::{Command, ExitStatus},
+    thread,
+    time::Duration,
+};
+#[cfg(unix)]
+use std::{
+    os::unix::process::ExitStatusExt,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+use crossterm::{
+    event::{DisableFocusChange, DisableMouseCapture},
+    queue, terminal,
+};
+
+use crate::{matrix::Exit, settings::Settings, term};
+
+/// How often the rain is looked in on, and SIGTERM checked for.
+const CHECK: Duration = Duration::from_millis(100);
+/// How often to look for the terminal to be back, and the least time
+/// between a run failing and the next starting.
+const RESTART_WAIT: Duration = Duration::from_secs(1);
+
+/// Run the rain with the command line `args`, less `--forever`, again and
+/// again until it ends some way other than failing, then exit as it did.
+/// `settings` are what `args` make.
+pub fn run(args: &[String], settings: &Settings) -> io::Result<()> {
+    // With --while the exit code is the command's, whatever it is
+    let coded = settings.while_cmd.is_none();
+    let args: Vec<&String> = args.iter().filter(|arg| *arg != "--forever").collect();
+    let exe = env::current_exe()?;
+    // A hangup takes the rain with it, but not this
+    #[cfg(unix)]
+    let (terminated, hung_up) = (Arc::new(AtomicBool::new(false)), Arc::new(AtomicBool::new(false)));
+    #[cfg(unix)]
+    {
+        signal_hook::flag::register(signal_hook::consts::SIGTERM, Arc::clone(&terminated))?;
+        signal_hook::flag::register(signal_hook::consts::SIGHUP, Arc::clone(&hung_up))?;
+    }
+
+    loop {
+        let mut child = Command::new(&exe).args(&args).spawn()?;
+        let status = loop {
+            if let Some(status) = child.try_wait()? {
+                break status;
+            }
+            // Passed on, as the rain only hears of a hangup straight from
+            // the terminal when this isn't in the way
+            #[cfg(unix)]
+            for (flag, signal) in [(&terminated, libc::SIGTERM), (&hung_up, libc::SIGHUP)] {
+                if flag.swap(false, Ordering::Relaxed) {
+                    unsafe { libc::kill(child.id() as libc::pid_t, signal) };
+                }
+            }
+            thread::sleep(CHECK);
+        };
+        if !failed(status, coded) {
+            std::process::exit(code(status));
+        }
+
+        restore();
+        let _ = writeln!(io::stderr(), "matrix: the rain failed ({}), starting over", status);
+        loop {
+            thread::sleep(RESTART_WAIT);
+            #[cfg(unix)]
+            if terminated.load(Ordering::Relaxed) {
+                std::process::exit(128 + libc::SIGTERM);
+            }
+            if stdout().is_terminal() && terminal::size().is_ok_and(|(cols, rows)| cols > 0 && rows > 0) {
+                break;
+            }
+        }
+    }
+}
+
+/// Whether the rain failed in a way starting over can mend: its terminal
+/// or other I/O failed while it ran, or the terminal hung up or closed the
+/// pipe on it. Errors setting up would only fail again. Unless `coded`,
+/// the code is a `--while` command's and says nothing about the rain.
+fn failed(status: ExitStatus, coded: bool) -> bool {
+    #[cfg(unix)]
+    if let Some(signal) = status.signal() {
+        return matches!(signal, libc::SIGHUP | libc::SIGPIPE);
+    }
+    coded && status.code() == Some(Exit::IO_ERROR)
+}
+
+/// The code to exit with for the rain ending with `status`, the shell's
+/// 128 plus the signal for one that was killed.
+fn code(status: ExitStatus) -> i32 {
+    #[cfg(unix)]
+    if let Some(signal) = status.signal() {
+        return 128 + signal;
+    }
+    status.code().unwrap_or(1)
+}
+
+/// Put the terminal back after a rain that couldn't, as well as can be:
+/// it may well not be there, so nothing here can fail.
+fn restore() {
+    let mut out = vec![];
+    let _ = queue!(out, DisableMouseCapture, DisableFocusChange);
+    let _ = term::leave(&mut out);
+    let _ = stdout().write_all(&out).and_then(|_| stdout().flush());
+}
@@ -0,0 +1,56 @@
+use std::time::Duration;
+
+use crate::platform::Instant;
+
+/// How much each new frame moves the averages, so the readout is steady
+/// enough to read.
+const SMOOTHING: f64 = 0.1;
+
+/// Smoothed frame rate and per-frame work time for the `--show-fps`
+/// overlay.
+#[derive(Default)]
+pub struct FpsMeter {
+    last_frame: Option<Instant>,
+    begun: Option<Instant>,
+    fps: f64,
+    work_ms: f64, // Time spent updating and drawing a frame
+}
+
+impl FpsMeter {
+    /// A frame is starting.
+    pub fn begin(&mut self) {
+        let now = Instant::now();
+        if let Some(last) = self.last_frame {
+            let secs = now.duration_since(last).as_secs_f64();
+            if secs > 0.0 {
+                self.fps = smooth(self.fps, 1.0 / secs);
+            }
+        }
+        self.last_frame = Some(now);
+        self.begun = Some(now);
+    }
+
+    /// The frame started by `begin` has been drawn.
+    pub fn end(&mut self) {
+        if let Some(begun) = self.begun.take() {
+            let work: Duration = Instant::now().duration_since(begun);
+            self.work_ms = smooth(self.work_ms, work.as_secs_f64() * 1000.0);
+        }
+    }
+
+    pub fn fps(&self) -> f64 {
+        self.fps
+    }
+
+    pub fn work_ms(&self) -> f64 {
+        self.work_ms
+    }
+}
+
+fn smooth(average: f64, sample: f64) -> f64 {
+    if average == 0.0 {
+        sample
+    } else {
+        average + (sample - average) * SMOOTHING
+    }
+}
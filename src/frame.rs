@@ -0,0 +1,105 @@
+use std::io::Write;
+
+use crate::{
+    platform::{set_bg, set_fg, set_style, Color},
+    render::{Glyph, Style},
+};
+
+/// One finished frame: what every terminal cell should show, with no
+/// terminal involved, so the rain can be embedded, tested, or serialized.
+#[derive(Clone, PartialEq)]
+pub struct Frame {
+    width: u16,
+    height: u16,
+    glyphs: Vec<Glyph>, // Row-major, `width * height` cells
+}
+
+impl Frame {
+    pub(crate) fn new(width: u16, height: u16, glyphs: Vec<Glyph>) -> Self {
+        debug_assert_eq!(glyphs.len(), width as usize * height as usize);
+        Frame {
+            width,
+            height,
+            glyphs,
+        }
+    }
+
+    pub fn width(&self) -> u16 {
+        self.width
+    }
+
+    pub fn height(&self) -> u16 {
+        self.height
+    }
+
+    /// Character and foreground color of cell (`x`, `y`).
+    pub fn cell(&self, x: u16, y: u16) -> Option<(char, Color)> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        let glyph = self.glyphs[y as usize * self.width as usize + x as usize];
+        Some((glyph.ch, glyph.fg))
+    }
+
+    /// Every cell's character and foreground color, row by row.
+    pub fn cells(&self) -> impl Iterator<Item = (char, Color)> + '_ {
+        self.glyphs.iter().map(|glyph| (glyph.ch, glyph.fg))
+    }
+
+    pub(crate) fn glyphs(&self) -> &[Glyph] {
+        &self.glyphs
+    }
+
+    /// Replace cell (`x`, `y`), if it's on the frame.
+    pub(crate) fn set(&mut self, x: u16, y: u16, glyph: Glyph) {
+        if x < self.width && y < self.height {
+            self.glyphs[y as usize * self.width as usize + x as usize] = glyph;
+        }
+    }
+
+    /// The frame as plain text without any colors, one line per row.
+    pub fn to_plain_string(&self) -> String {
+        let mut text = String::with_capacity(self.glyphs.len() + self.height as usize);
+        for row in self.glyphs.chunks(self.width.max(1) as usize) {
+            text.extend(row.iter().map(|glyph| glyph.ch));
+            text.push('\n');
+        }
+        text
+    }
+
+    /// The frame as text with ANSI color codes, rows separated by `\r\n`
+    /// so it prints the same in raw and cooked terminals. Colors and styles
+    /// are reset at the end.
+    pub fn to_ansi_string(&self) -> String {
+        let mut out = Vec::with_capacity(self.glyphs.len() * 4);
+        // Colors the output is currently set to, to skip redundant changes
+        let mut fg = None;
+        let mut bg = None;
+        let mut style = Style::Plain;
+        for (idx, glyph) in self.glyphs.iter().enumerate() {
+            if idx > 0 && idx % self.width as usize == 0 {
+                out.extend_from_slice(b"\r\n");
+            }
+            // Writing to a Vec can't fail
+            if bg != Some(glyph.bg) {
+                let _ = set_bg(&mut out, glyph.bg);
+                bg = Some(glyph.bg);
+            }
+            if glyph.ch != ' ' && fg != Some(glyph.fg) {
+                let _ = set_fg(&mut out, glyph.fg);
+                fg = Some(glyph.fg);
+            }
+            if style != glyph.style {
+                let _ = set_style(&mut out, glyph.style);
+                style = glyph.style;
+            }
+            let _ = write!(out, "{}", glyph.ch);
+        }
+        let _ = set_fg(&mut out, Color::Reset);
+        let _ = set_bg(&mut out, Color::Reset);
+        if style != Style::Plain {
+            let _ = set_style(&mut out, Style::Plain);
+        }
+        String::from_utf8(out).unwrap_or_default()
+    }
+}
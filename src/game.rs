@@ -0,0 +1,145 @@
+use rand::Rng;
+
+use crate::{color::{ColorScheme, Sweep}, platform::Color, render::Glyph};
+
+/// What typing starts the game with.
+pub const TRIGGER: &str = "neo";
+
+/// Words that fall, picked at random.
+const WORDS: [&str; 24] = [
+    "matrix", "neo", "trinity", "morpheus", "oracle", "zion", "agent", "smith", "nebuchadnezzar",
+    "construct", "sentinel", "rabbit", "spoon", "pill", "choice", "code", "operator", "exit",
+    "keymaker", "merovingian", "glitch", "loop", "signal", "wake",
+];
+
+/// Most words on screen at once.
+const MAX_WORDS: usize = 5;
+
+/// Rows per second words start out falling at; each point scored adds a
+/// little.
+const BASE_SPEED: f32 = 1.5;
+const SPEED_PER_POINT: f32 = 0.02;
+
+/// A new word starts about this often, in seconds.
+const SPAWN_EVERY: f32 = 2.5;
+
+struct Word {
+    text: &'static str,
+    x: u16,
+    y: f32, // Row of the first letter; the rest stack below it
+}
+
+/// Hidden typing game: words fall down the screen as drops, and typing one
+/// before its last letter reaches the bottom scores its length. The word
+/// being typed lights up as it's matched.
+pub struct Game {
+    words: Vec<Word>,
+    typed: String,
+    score: usize,
+    missed: usize,
+    until_spawn: f32, // Seconds until the next word
+}
+
+impl Game {
+    pub fn new() -> Self {
+        Game {
+            words: vec![],
+            typed: String::new(),
+            score: 0,
+            missed: 0,
+            until_spawn: 0.0,
+        }
+    }
+
+    pub fn score(&self) -> usize {
+        self.score
+    }
+
+    /// Advance by `secs`, on a screen `cols` x `rows` cells big: move the
+    /// words down, lose those that fell off, and maybe start another.
+    pub fn tick(&mut self, cols: u16, rows: u16, secs: f32, rng: &mut impl Rng) {
+        let speed = BASE_SPEED + self.score as f32 * SPEED_PER_POINT;
+        for word in &mut self.words {
+            word.y += speed * secs;
+        }
+        let before = self.words.len();
+        self.words.retain(|word| word.y as usize + word.text.len() <= rows as usize);
+        if self.words.len() < before {
+            self.missed += before - self.words.len();
+            // What was typed may have been for a word that's gone
+            self.typed.clear();
+        }
+
+        self.until_spawn -= secs;
+        if self.until_spawn > 0.0 || self.words.len() >= MAX_WORDS || cols == 0 {
+            return;
+        }
+        self.until_spawn = SPAWN_EVERY * rng.gen_range(0.6..1.4);
+        let text = WORDS[rng.gen_range(0..WORDS.len())];
+        if text.len() > rows as usize {
+            return;
+        }
+        // Keep clear of the columns words already fill
+        for _ in 0..10 {
+            let x = rng.gen_range(0..cols);
+            if self.words.iter().all(|word| word.x.abs_diff(x) > 1) {
+                self.words.push(Word { text, x, y: 0.0 });
+                break;
+            }
+        }
+    }
+
+    /// Take a typed letter. Typing that matches no word starts over.
+    pub fn type_char(&mut self, ch: char) {
+        self.typed.push(ch.to_ascii_lowercase());
+        if !self.words.iter().any(|word| word.text.starts_with(&self.typed)) {
+            self.typed.clear();
+            return;
+        }
+        // The lowest word typed in full goes first
+        let done = self
+            .words
+            .iter()
+            .enumerate()
+            .filter(|(_, word)| word.text == self.typed)
+            .max_by(|(_, a), (_, b)| a.y.total_cmp(&b.y))
+            .map(|(idx, _)| idx);
+        if let Some(idx) = done {
+            self.score += self.words.remove(idx).text.len();
+            self.typed.clear();
+        }
+    }
+
+    pub fn backspace(&mut self) {
+        self.typed.pop();
+    }
+
+    /// Draw the words over `frame`, `cols` wide and `rows` high, and the
+    /// score along the top.
+    pub fn apply(&self, frame: &mut [Glyph], cols: u16, rows: u16, scheme: ColorScheme) {
+        let mut put = |x: u16, y: u16, glyph: Glyph| {
+            if x < cols && y < rows {
+                frame[y as usize * cols as usize + x as usize] = glyph;
+            }
+        };
+        for word in &self.words {
+            let matched = if word.text.starts_with(&self.typed) {
+                self.typed.len()
+            } else {
+                0
+            };
+            for (i, ch) in word.text.chars().enumerate() {
+                let color = if i < matched {
+                    Color::White
+                } else {
+                    scheme.get_colors(1, 2, word.x, Sweep::Horizontal)
+                };
+                put(word.x, word.y as u16 + i as u16, Glyph::new(ch, color));
+            }
+        }
+        let text = format!(" score {}  missed {}  (Esc to stop) ", self.score, self.missed);
+        for (x, ch) in text.chars().enumerate() {
+            put(x as u16, 0, Glyph::new(ch, Color::White));
+        }
+    }
+}
@@ -0,0 +1,111 @@
+use rand::{rngs::SmallRng, Rng, SeedableRng};
+
+use crate::{
+    color::{dim, ColorScheme, Sweep},
+    drop::random_char,
+    platform::Color,
+    render::Glyph,
+};
+
+enum Corruption {
+    Shift(i32), // Columns the band slides sideways, wrapping around
+    Invert,
+    Noise,
+}
+
+struct Band {
+    top: u16,
+    height: u16,
+    corruption: Corruption,
+    frames_left: u8,
+    noise: u64, // Seed for this frame's noise, so drawing needs no RNG of its own
+}
+
+/// Occasional `--glitch` corruption: a random horizontal band shifts,
+/// inverts, or fills with noise for a few frames before snapping back.
+pub struct Glitch {
+    per_minute: f64,
+    band: Option<Band>,
+}
+
+impl Glitch {
+    pub fn new(per_minute: f64) -> Self {
+        Glitch {
+            per_minute,
+            band: None,
+        }
+    }
+
+    /// Advance by a frame that lasts `frame_ms`, maybe starting a new glitch.
+    pub fn tick(&mut self, rows: u16, frame_ms: u64, rng: &mut impl Rng) {
+        if let Some(band) = self.band.as_mut() {
+            band.noise = rng.gen();
+            band.frames_left = band.frames_left.saturating_sub(1);
+            if band.frames_left == 0 {
+                self.band = None;
+            }
+            return;
+        }
+
+        let chance = self.per_minute * frame_ms.max(1) as f64 / 60_000.0;
+        if rows == 0 || !rng.gen_bool(chance.clamp(0.0, 1.0)) {
+            return;
+        }
+        let height = rng.gen_range(1..=(rows / 6).max(1));
+        let corruption = match rng.gen_range(0..3) {
+            0 => {
+                let shift = rng.gen_range(2..12);
+                Corruption::Shift(if rng.gen_bool(0.5) { shift } else { -shift })
+            }
+            1 => Corruption::Invert,
+            _ => Corruption::Noise,
+        };
+        self.band = Some(Band {
+            top: rng.gen_range(0..rows),
+            height,
+            corruption,
+            frames_left: rng.gen_range(2..8),
+            noise: rng.gen(),
+        });
+    }
+
+    /// Corrupt the active band, if any, in a row-major `cols`-wide frame.
+    pub fn apply(&self, frame: &mut [Glyph], cols: u16, color_scheme: ColorScheme) {
+        let Some(band) = self.band.as_ref().filter(|_| cols > 0) else {
+            return;
+        };
+        let mut rng = SmallRng::seed_from_u64(band.noise);
+        let cols = cols as usize;
+        for row in frame.chunks_mut(cols).skip(band.top as usize).take(band.height as usize) {
+            match band.corruption {
+                Corruption::Shift(shift) if shift > 0 => row.rotate_right(shift as usize % cols),
+                Corruption::Shift(shift) => row.rotate_left(shift.unsigned_abs() as usize % cols),
+                Corruption::Invert => {
+                    for (x, glyph) in row.iter_mut().enumerate() {
+                        // Blank cells light up too so the whole band reads as a bar
+                        let bg = if glyph.ch == ' ' {
+                            dim(color_scheme.get_colors(10, 10, x as u16, Sweep::Horizontal), 0.5)
+                        } else {
+                            glyph.fg
+                        };
+                        *glyph = Glyph {
+                            ch: glyph.ch,
+                            fg: Color::Black,
+                            bg,
+                            style: glyph.style,
+                        };
+                    }
+                }
+                Corruption::Noise => {
+                    for (x, glyph) in row.iter_mut().enumerate() {
+                        if rng.gen_bool(0.6) {
+                            let ch = random_char(&mut rng);
+                            let color = color_scheme.get_colors(rng.gen_range(0..10), 10, x as u16, Sweep::Horizontal);
+                            *glyph = Glyph::new(ch, dim(color, rng.gen_range(0.4..1.0)));
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,221 @@
+use std::time::Duration;
+
+use rand::Rng;
+
+use crate::{
+    color::Sweep,
+    overlay::{Canvas, Overlay, Z_PANEL},
+    platform::Instant,
+    render::Glyph,
+    settings::Rect,
+};
+
+/// Panels open at most this many at a time...
+const MAX_PANELS: usize = 2;
+/// ...a while apart...
+const GAP: (Duration, Duration) = (Duration::from_secs(2), Duration::from_secs(6));
+/// ...and stay up for a few seconds.
+const LIFE: (Duration, Duration) = (Duration::from_secs(3), Duration::from_secs(7));
+
+/// How often the code and hex dumps scroll up a line.
+const SCROLL: Duration = Duration::from_millis(120);
+
+/// How fast the access banner blinks, on and off.
+const BLINK: Duration = Duration::from_millis(300);
+
+/// What gets "hacked": scrolls past in the code panels, over and over.
+const CODE: &[&str] = &[
+    "int main(int argc, char **argv) {",
+    "    struct sockaddr_in target;",
+    "    target.sin_port = htons(31337);",
+    "    if (connect(fd, &target, len) < 0)",
+    "        bypass_firewall(fd, PROXY_CHAIN);",
+    "    uint8_t *key = brute_force(hash, 0x7f);",
+    "    for (i = 0; i < MAINFRAME_NODES; i++) {",
+    "        inject(payload, node[i], key);",
+    "        trace_route(node[i]) ^= 0xdeadbeef;",
+    "    }",
+    "    decrypt(&vault, key, AES_256_GCM);",
+    "    mov eax, [ebp+8]",
+    "    xor ebx, ebx",
+    "    jmp 0x0804a01c",
+    "    download(\"/sys/zion/coords.dat\");",
+    "    wipe_logs(TRACE_ALL);",
+    "    return ACCESS_GRANTED;",
+    "}",
+];
+
+/// What a panel shows.
+#[derive(Clone, Copy, PartialEq)]
+enum Kind {
+    Code,
+    Hex,
+    Access(bool), // Granted, or denied
+}
+
+struct Panel {
+    kind: Kind,
+    area: Rect,
+    opened: Instant,
+    life: Duration,
+    seed: u32, // Where the code starts, and the bytes of the hex dump
+}
+
+/// `--hollywood`: the rain with a movie hacker at work, brief panels of
+/// scrolling code, hex dumps and ACCESS GRANTED flashing up over it and
+/// going again.
+pub struct Hollywood {
+    panels: Vec<Panel>,
+    next: Instant, // When the next panel opens
+}
+
+impl Hollywood {
+    pub fn new() -> Self {
+        Hollywood {
+            panels: vec![],
+            next: Instant::now() + GAP.0,
+        }
+    }
+
+    /// Close the panels that have had their time and maybe open another
+    /// on a `cols` x `rows` screen.
+    pub fn tick(&mut self, cols: u16, rows: u16, rng: &mut impl Rng) {
+        let now = Instant::now();
+        self.panels.retain(|panel| now.duration_since(panel.opened) < panel.life);
+        if now < self.next || self.panels.len() >= MAX_PANELS || cols < 24 || rows < 7 {
+            return;
+        }
+        self.next = now + rng.gen_range(GAP.0..GAP.1);
+
+        let kind = match rng.gen_range(0..5) {
+            0 | 1 => Kind::Code,
+            2 | 3 => Kind::Hex,
+            _ => Kind::Access(rng.gen_bool(0.7)),
+        };
+        let (width, height) = match kind {
+            Kind::Access(_) => (22, 3),
+            Kind::Code => (rng.gen_range(30..=46), rng.gen_range(6..=12)),
+            Kind::Hex => (81, rng.gen_range(5..=10)),
+        };
+        let (width, height) = (width.min(cols), height.min(rows));
+        let area = if let Kind::Access(_) = kind {
+            Rect {
+                x: (cols - width) / 2,
+                y: (rows - height) / 2,
+                width,
+                height,
+            }
+        } else {
+            Rect {
+                x: rng.gen_range(0..=cols - width),
+                y: rng.gen_range(0..=rows - height),
+                width,
+                height,
+            }
+        };
+        // Only one banner at a time, and panels don't pile on each other
+        let overlaps = |other: &Panel| {
+            let (a, b) = (area, other.area);
+            a.x < b.x + b.width && b.x < a.x + a.width && a.y < b.y + b.height && b.y < a.y + a.height
+        };
+        if self.panels.iter().any(overlaps) {
+            return;
+        }
+        let life = match kind {
+            Kind::Access(_) => Duration::from_secs(2),
+            _ => rng.gen_range(LIFE.0..LIFE.1),
+        };
+        self.panels.push(Panel {
+            kind,
+            area,
+            opened: now,
+            life,
+            seed: rng.gen(),
+        });
+    }
+}
+
+impl Overlay for Hollywood {
+    fn z(&self) -> u8 {
+        Z_PANEL
+    }
+
+    fn draw(&self, canvas: &mut Canvas) {
+        // The rain's colors, a touch brighter for the titles
+        let text = canvas.scheme.get_colors(3, 10, 0, Sweep::Horizontal);
+        let title = canvas.scheme.get_colors(1, 10, 0, Sweep::Horizontal);
+        for panel in &self.panels {
+            let elapsed = panel.opened.elapsed();
+            let Rect { x, y, width, height } = panel.area;
+            let inner = width.saturating_sub(4) as usize;
+            let lines: Vec<String> = match panel.kind {
+                Kind::Access(granted) => {
+                    let on = (elapsed.as_millis() / BLINK.as_millis()).is_multiple_of(2);
+                    let word = if granted { "ACCESS GRANTED" } else { "ACCESS DENIED" };
+                    vec![if on { word.to_string() } else { String::new() }]
+                }
+                Kind::Code | Kind::Hex => {
+                    // Scroll up from an empty panel, a line at a time
+                    let shown = (elapsed.as_millis() / SCROLL.as_millis()) as usize + 1;
+                    let visible = height.saturating_sub(2) as usize;
+                    (shown.saturating_sub(visible)..shown)
+                        .map(|line| match panel.kind {
+                            Kind::Code => CODE[(panel.seed as usize + line) % CODE.len()].to_string(),
+                            _ => hex_line(panel.seed, line),
+                        })
+                        .collect()
+                }
+            };
+
+            // The lines first, since the first to take a cell keeps it
+            let centered = matches!(panel.kind, Kind::Access(_));
+            for (dy, line) in lines.iter().enumerate() {
+                let line: String = line.chars().take(inner).collect();
+                let len = line.chars().count() as u16;
+                let left = if centered { x + (width - len) / 2 } else { x + 2 };
+                let color = if centered { title } else { text };
+                canvas.text(left, y + 1 + dy as u16, &line, color);
+            }
+
+            // Then a box round them, blanked inside so the rain doesn't show
+            // through
+            for dy in 0..height {
+                for dx in 0..width {
+                    let edge = |d: u16, size: u16| d == 0 || d + 1 == size;
+                    let ch = match (edge(dx, width), edge(dy, height)) {
+                        (true, true) => match (dx == 0, dy == 0) {
+                            (true, true) => '┌',
+                            (false, true) => '┐',
+                            (true, false) => '└',
+                            (false, false) => '┘',
+                        },
+                        (false, true) => '─',
+                        (true, false) => '│',
+                        (false, false) => ' ',
+                    };
+                    canvas.put(x + dx, y + dy, Glyph::new(ch, text));
+                }
+            }
+        }
+    }
+}
+
+/// Line `n` of a made-up hex dump: the address, 16 bytes and them as text.
+fn hex_line(seed: u32, n: usize) -> String {
+    let bytes: Vec<u8> = (0..16).map(|i| byte(seed, n as u32 * 16 + i)).collect();
+    let hex: Vec<String> = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+    let text: String = bytes
+        .iter()
+        .map(|&b| if b.is_ascii_graphic() { b as char } else { '.' })
+        .collect();
+    format!("{:08x}  {}  |{}|", n * 16, hex.join(" "), text)
+}
+
+/// A byte that's always the same for the same seed and offset.
+fn byte(seed: u32, offset: u32) -> u8 {
+    let mut n = seed ^ offset.wrapping_mul(0x9e37_79b1);
+    n ^= n >> 15;
+    n = n.wrapping_mul(0x2c1b_3c6d);
+    n ^= n >> 12;
+    n as u8
+}
@@ -0,0 +1,139 @@
+//! `--serve-http`: the rain as a WebSocket stream of ANSI frames, plus a
+//! small built-in page that plays it, for dashboards and status pages.
+
+use std::{
+    io::{ErrorKind, Read, Write},
+    net::{TcpListener, TcpStream},
+    thread,
+    time::Duration,
+};
+use tungstenite::{Error, Message};
+
+use crate::{cli, matrix::Matrix, pacer::Pacer};
+
+/// Page served to plain HTTP requests. It connects back to the same address
+/// over WebSocket, tells the server its size as `COLSxROWS`, and writes
+/// every frame it gets to an xterm.js terminal.
+const PAGE: &str = r#"<!DOCTYPE html>
+<html>
+<head>
+  <meta charset="utf-8">
+  <title>Matrix Rain</title>
+  <link rel="stylesheet" href="https://cdn.jsdelivr.net/npm/@xterm/xterm@5.5.0/css/xterm.css">
+  <style>html, body, #terminal { margin: 0; height: 100%; background: #000; }</style>
+</head>
+<body>
+  <div id="terminal"></div>
+  <script src="https://cdn.jsdelivr.net/npm/@xterm/xterm@5.5.0/lib/xterm.js"></script>
+  <script src="https://cdn.jsdelivr.net/npm/@xterm/addon-fit@0.10.0/lib/addon-fit.js"></script>
+  <script>
+    const term = new Terminal({ cursorBlink: false, disableStdin: true });
+    const fit = new FitAddon.FitAddon();
+    term.loadAddon(fit);
+    term.open(document.getElementById("terminal"));
+    fit.fit();
+
+    const socket = new WebSocket(`ws://${location.host}/`);
+    const sendSize = () => socket.send(`${term.cols}x${term.rows}`);
+    socket.onopen = sendSize;
+    socket.onmessage = (event) => term.write(event.data);
+    term.onResize(() => socket.readyState === WebSocket.OPEN && sendSize());
+    window.addEventListener("resize", () => fit.fit());
+  </script>
+</body>
+</html>
+"#;
+
+/// Size used until the page tells us its own.
+const DEFAULT_SIZE: (u16, u16) = (80, 24);
+
+/// Whether a request is asking to upgrade to a WebSocket, judged from its
+/// headers without consuming them.
+fn wants_websocket(stream: &TcpStream) -> bool {
+    let mut buf = [0; 4096];
+    let n = stream.peek(&mut buf).unwrap_or(0);
+    String::from_utf8_lossy(&buf[..n])
+        .to_ascii_lowercase()
+        .contains("upgrade: websocket")
+}
+
+fn serve_page(mut stream: TcpStream) -> std::io::Result<()> {
+    // The request itself doesn't matter: every path gets the page
+    let mut buf = [0; 4096];
+    let _ = stream.read(&mut buf)?;
+    write!(
+        stream,
+        "HTTP/1.1 200 OK\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        PAGE.len(),
+        PAGE
+    )
+}
+
+/// Stream rain to one WebSocket client until it goes away.
+fn serve_socket(stream: TcpStream, options: &[String]) -> std::io::Result<()> {
+    stream.set_nodelay(true)?;
+    let mut socket = tungstenite::accept(stream).map_err(std::io::Error::other)?;
+    // Reads give up almost at once so they never hold up a frame
+    socket
+        .get_ref()
+        .set_read_timeout(Some(Duration::from_millis(1)))?;
+
+    let (cols, rows) = DEFAULT_SIZE;
+    let mut matrix = Matrix::with_size(cli::parse(options), cols, rows);
+    // Hide the cursor and start from a clean screen
+    let mut out = b"\x1b[?25l\x1b[2J".to_vec();
+    let mut pacer = Pacer::new();
+    loop {
+        loop {
+            match socket.read() {
+                Ok(Message::Text(text)) => {
+                    let size = text.split_once('x').and_then(|(cols, rows)| {
+                        Some((cols.trim().parse().ok()?, rows.trim().parse().ok()?))
+                    });
+                    if let Some((cols, rows)) = size.filter(|&(cols, rows)| cols > 0 && rows > 0) {
+                        matrix.resize(cols, rows);
+                        out.extend_from_slice(b"\x1b[2J");
+                    }
+                }
+                Ok(Message::Close(_)) => return Ok(()),
+                Ok(_) => {}
+                Err(Error::Io(err))
+                    if matches!(err.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) =>
+                {
+                    break
+                }
+                // Anything else means the client is gone
+                Err(_) => return Ok(()),
+            }
+        }
+        for _ in 1..pacer.due(matrix.frame_delay()) {
+            matrix.step();
+        }
+        matrix.draw(&mut out)?;
+        let frame = Message::Text(String::from_utf8_lossy(&out).into_owned());
+        socket.send(frame).map_err(std::io::Error::other)?;
+        out.clear();
+        pacer.wait();
+    }
+}
+
+/// Serve the page and the WebSocket stream on `addr` forever. Every client
+/// gets its own rain made from `options`.
+pub fn serve(addr: &str, options: &[String]) -> std::io::Result<()> {
+    let listener = TcpListener::bind(addr)?;
+    println!("Serving the rain on http://{} (Ctrl+C to stop)", addr);
+    for stream in listener.incoming() {
+        let Ok(stream) = stream else {
+            continue;
+        };
+        let options = options.to_vec();
+        thread::spawn(move || {
+            if wants_websocket(&stream) {
+                let _ = serve_socket(stream, &options);
+            } else {
+                let _ = serve_page(stream);
+            }
+        });
+    }
+    Ok(())
+}
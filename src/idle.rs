@@ -0,0 +1,255 @@
+//! `matrix idle`: run a shell, or any other command, in a pseudo-terminal,
+//! passing everything through untouched, and cover it with the rain once
+//! the keyboard has been idle for a while, as a screensaver would. The next
+//! key brings the shell back.
+
+use std::{
+    fs::File,
+    io::{self, Read, Write},
+    os::{
+        fd::{AsRawFd, FromRawFd, OwnedFd},
+        unix::process::CommandExt,
+    },
+    process::{Command, Stdio},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use crossterm::{
+    execute,
+    terminal::{self, EnterAlternateScreen, LeaveAlternateScreen},
+};
+
+use crate::{cli, matrix::Matrix};
+
+/// Idle time before the rain comes on, without `--after`.
+const DEFAULT_AFTER: Duration = Duration::from_secs(300);
+
+/// How often, in milliseconds, the wait for a key stops to check on the
+/// command and the window size.
+const TICK_MS: i32 = 200;
+
+/// Output the command writes while covered is kept, up to this many bytes
+/// (the newest), and shown once the rain goes away.
+const MAX_HELD: usize = 1 << 20;
+
+/// Where the command's output goes: straight to the terminal, or held back
+/// while the rain covers it.
+#[derive(Default)]
+struct Output {
+    covered: bool,
+    held: Vec<u8>,
+}
+
+/// Parse a duration such as `90`, `90s`, `5m` or `1h`; plain numbers are
+/// seconds.
+fn parse_duration(s: &str) -> Option<Duration> {
+    let (number, unit) = match s.find(|ch: char| ch.is_ascii_alphabetic()) {
+        Some(i) => s.split_at(i),
+        None => (s, "s"),
+    };
+    let number: f64 = number.parse().ok()?;
+    let scale = match unit {
+        "s" => 1.0,
+        "m" => 60.0,
+        "h" => 3600.0,
+        _ => return None,
+    };
+    (number.is_finite() && number > 0.0).then(|| Duration::from_secs_f64(number * scale))
+}
+
+fn winsize(cols: u16, rows: u16) -> libc::winsize {
+    libc::winsize {
+        ws_row: rows,
+        ws_col: cols,
+        ws_xpixel: 0,
+        ws_ypixel: 0,
+    }
+}
+
+/// A new pseudo-terminal `cols` x `rows` big: its master and slave ends.
+fn open_pty(cols: u16, rows: u16) -> io::Result<(OwnedFd, OwnedFd)> {
+    let (mut master, mut slave) = (0, 0);
+    let mut size = winsize(cols, rows);
+    let opened = unsafe {
+        libc::openpty(
+            &mut master,
+            &mut slave,
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+            std::ptr::addr_of_mut!(size),
+        )
+    };
+    if opened != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(unsafe { (OwnedFd::from_raw_fd(master), OwnedFd::from_raw_fd(slave)) })
+}
+
+/// Tell the pseudo-terminal, and so the command, that the window changed.
+fn resize(master: &OwnedFd, cols: u16, rows: u16) {
+    let size = winsize(cols, rows);
+    unsafe { libc::ioctl(master.as_raw_fd(), libc::TIOCSWINSZ, &size) };
+}
+
+/// Run `matrix idle [--after DURATION] [OPTIONS] [-- COMMAND...]`: run
+/// `COMMAND`, or `$SHELL` without one, and rain with `OPTIONS` after
+/// `DURATION` (default 5m) without a key. Exits with the command's code.
+pub fn run(args: &[String]) -> io::Result<()> {
+    let mut after = DEFAULT_AFTER;
+    let mut options = vec![];
+    let mut command = vec![];
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--after" => {
+                let value = args.get(i + 1).map(String::as_str).unwrap_or("");
+                let Some(duration) = parse_duration(value) else {
+                    eprintln!("matrix idle: --after needs a duration such as 90s, 5m or 1h");
+                    std::process::exit(2);
+                };
+                after = duration;
+                i += 1;
+            }
+            "--" => {
+                command = args[i + 1..].to_vec();
+                break;
+            }
+            _ => options.push(args[i].clone()),
+        }
+        i += 1;
+    }
+    if command.is_empty() {
+        command.push(std::env::var("SHELL").unwrap_or_else(|_| "/bin/sh".to_string()));
+    }
+    // Fail now rather than when the rain first comes on
+    cli::parse(&options);
+
+    let (cols, rows) = terminal::size().unwrap_or((80, 24));
+    let (master, slave) = open_pty(cols, rows)?;
+    let mut child = unsafe {
+        Command::new(&command[0])
+            .args(&command[1..])
+            .stdin(Stdio::from(slave.try_clone()?))
+            .stdout(Stdio::from(slave.try_clone()?))
+            .stderr(Stdio::from(slave))
+            // Its own session, with the pseudo-terminal as its terminal, so
+            // job control and Ctrl+C work inside it
+            .pre_exec(|| {
+                if libc::setsid() < 0 || libc::ioctl(0, libc::TIOCSCTTY as _, 0) < 0 {
+                    return Err(io::Error::last_os_error());
+                }
+                Ok(())
+            })
+            .spawn()?
+    };
+
+    terminal::enable_raw_mode()?;
+    let output = Arc::new(Mutex::new(Output::default()));
+    let mut from_command = File::from(master.try_clone()?);
+    let shared = Arc::clone(&output);
+    thread::spawn(move || {
+        let mut buf = [0; 4096];
+        while let Ok(n @ 1..) = from_command.read(&mut buf) {
+            let mut output = shared.lock().unwrap();
+            if output.covered {
+                output.held.extend_from_slice(&buf[..n]);
+                let excess = output.held.len().saturating_sub(MAX_HELD);
+                output.held.drain(..excess);
+            } else {
+                let mut stdout = io::stdout();
+                let _ = stdout.write_all(&buf[..n]).and_then(|_| stdout.flush());
+            }
+        }
+    });
+    let exited = Arc::new(AtomicBool::new(false));
+    let done = Arc::clone(&exited);
+    let pid = child.id();
+    thread::spawn(move || {
+        // Waits for the command without reaping it, which `try_wait` below
+        // still does
+        let mut info: libc::siginfo_t = unsafe { std::mem::zeroed() };
+        let flags = libc::WEXITED | libc::WNOWAIT;
+        if unsafe { libc::waitid(libc::P_PID, pid, &mut info, flags) } == 0 {
+            done.store(true, Ordering::Relaxed);
+        }
+    });
+
+    let mut to_command = File::from(master.try_clone()?);
+    let mut size = (cols, rows);
+    let mut last_key = Instant::now();
+    let mut buf = [0u8; 4096];
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        if let Ok(now) = terminal::size() {
+            if now != size {
+                size = now;
+                resize(&master, size.0, size.1);
+            }
+        }
+        let mut stdin = libc::pollfd {
+            fd: 0,
+            events: libc::POLLIN,
+            revents: 0,
+        };
+        if unsafe { libc::poll(&mut stdin, 1, TICK_MS) } > 0 {
+            let n = unsafe { libc::read(0, buf.as_mut_ptr().cast(), buf.len()) };
+            if n > 0 {
+                to_command.write_all(&buf[..n as usize])?;
+                last_key = Instant::now();
+            }
+        } else if last_key.elapsed() >= after {
+            cover(&output, &options, &master, &exited)?;
+            last_key = Instant::now();
+        }
+    };
+
+    terminal::disable_raw_mode()?;
+    std::process::exit(status.code().unwrap_or(1))
+}
+
+/// Rain over the command until a key, or until it's `exited`, then put
+/// back what it showed and whatever it wrote meanwhile.
+fn cover(
+    output: &Mutex<Output>,
+    options: &[String],
+    master: &OwnedFd,
+    exited: &Arc<AtomicBool>,
+) -> io::Result<()> {
+    let mut settings = cli::parse(options);
+    settings.exit_on_any_key = true;
+    let mut stdout = io::stdout();
+    {
+        let mut output = output.lock().unwrap();
+        output.covered = true;
+        // The alternate screen keeps the command's screen to go back to
+        execute!(stdout, EnterAlternateScreen)?;
+    }
+    let mut matrix = Matrix::new(settings);
+    matrix.stop_on(Arc::clone(exited));
+    let rained = matrix.run();
+
+    let mut output = output.lock().unwrap();
+    execute!(stdout, LeaveAlternateScreen)?;
+    stdout.write_all(&output.held)?;
+    stdout.flush()?;
+    output.held.clear();
+    output.covered = false;
+    // The rain hands the terminal back out of raw mode
+    terminal::enable_raw_mode()?;
+    // Full-screen programs such as editors were on the alternate screen
+    // too; a resize signal makes them redraw
+    unsafe {
+        let group = libc::tcgetpgrp(master.as_raw_fd());
+        if group > 0 {
+            libc::kill(-group, libc::SIGWINCH);
+        }
+    }
+    rained.map(|_| ())
+}
@@ -0,0 +1,95 @@
+use std::time::Duration;
+
+use crate::{color::{ColorScheme, Sweep}, platform::Instant, render::Glyph};
+
+/// What's typed out, one line at a time, as in the film.
+const LINES: [&str; 4] = [
+    "Wake up, Neo...",
+    "The Matrix has you...",
+    "Follow the white rabbit.",
+    "Knock, knock, Neo.",
+];
+
+/// Time to type a character, give or take...
+const KEYSTROKE: Duration = Duration::from_millis(70);
+/// ...with a longer pause after punctuation, as if thinking...
+const PAUSE: Duration = Duration::from_millis(350);
+/// ...then how long the line stays up before it's wiped...
+const HOLD: Duration = Duration::from_millis(1800);
+/// ...and the dark between lines.
+const BLANK: Duration = Duration::from_millis(400);
+
+/// How fast the cursor blinks, on and off, while it waits.
+const BLINK: Duration = Duration::from_millis(500);
+
+/// `--intro`: before the rain starts, a cursor on a black screen types out
+/// the film's opening lines one by one, then the rain crashes in.
+pub struct Intro {
+    started: Instant,
+}
+
+impl Intro {
+    pub fn new() -> Self {
+        Intro {
+            started: Instant::now(),
+        }
+    }
+
+    /// The screen right now, `cols` x `rows`, None once the intro's over.
+    pub fn frame(&self, cols: u16, rows: u16, scheme: ColorScheme) -> Option<Vec<Glyph>> {
+        let mut frame = vec![Glyph::BLANK; cols as usize * rows as usize];
+        let color = scheme.get_colors(1, 10, 0, Sweep::Horizontal);
+        let mut put = |x: u16, ch: char| {
+            // Top left, where the film has it, a cell in from the edges
+            let (x, y) = (x + 1, 1.min(rows.saturating_sub(1)));
+            if x < cols {
+                frame[y as usize * cols as usize + x as usize] = Glyph::new(ch, color);
+            }
+        };
+
+        let mut elapsed = self.started.elapsed();
+        for line in LINES {
+            let chars: Vec<char> = line.chars().collect();
+            let typing: Duration = (0..chars.len()).map(|i| keystroke(&chars, i)).sum();
+            if elapsed >= typing + HOLD + BLANK {
+                elapsed -= typing + HOLD + BLANK;
+                continue;
+            }
+
+            // Typing, a keystroke at a time, with the cursor just after
+            let mut typed = 0;
+            let mut time = Duration::ZERO;
+            while typed < chars.len() && time + keystroke(&chars, typed) <= elapsed {
+                time += keystroke(&chars, typed);
+                typed += 1;
+            }
+            if elapsed >= typing + HOLD {
+                return Some(frame);
+            }
+            for (x, &ch) in chars[..typed].iter().enumerate() {
+                put(x as u16, ch);
+            }
+            // Solid while typing, blinking once it's done
+            let waiting = elapsed >= typing;
+            let on = !waiting || ((elapsed - typing).as_millis() / BLINK.as_millis()).is_multiple_of(2);
+            if on {
+                put(typed as u16, '█');
+            }
+            return Some(frame);
+        }
+        None
+    }
+}
+
+/// How long character `i` of `chars` takes to type: never quite even, and
+/// longer after a comma or a full stop.
+fn keystroke(chars: &[char], i: usize) -> Duration {
+    let after_punctuation = i > 0 && matches!(chars[i - 1], ',' | '.') && chars[i] != '.';
+    let jitter = (i as u32).wrapping_mul(2_654_435_761) >> 27; // 0 to 31
+    let stroke = KEYSTROKE + Duration::from_millis(jitter as u64 * 2);
+    if after_punctuation {
+        stroke + PAUSE
+    } else {
+        stroke
+    }
+}
@@ -0,0 +1,123 @@
+//! Commands run around the rain: `--while` keeps it going until one
+//! finishes, `--on-exit` runs one once the terminal's been handed back.
+
+use std::{
+    io::{self, Read, Write},
+    process::{Child, Command, Stdio},
+    sync::{Arc, Mutex},
+    thread::{self, JoinHandle},
+};
+
+/// `cmd` run by the platform's shell, so pipes and quoting work as typed.
+fn shell(cmd: &str) -> Command {
+    #[cfg(windows)]
+    let (program, flag) = ("cmd", "/C");
+    #[cfg(not(windows))]
+    let (program, flag) = ("sh", "-c");
+    let mut command = Command::new(program);
+    command.args([flag, cmd]);
+    command
+}
+
+/// Run `cmd` to the end with the terminal to itself.
+pub fn run(cmd: &str) -> io::Result<()> {
+    shell(cmd).status().map(|_| ())
+}
+
+/// Start `cmd` and leave it to it, with nowhere to print to so it can't
+/// scribble over the screen.
+pub fn start(cmd: &str) -> io::Result<()> {
+    shell(cmd)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map(|_| ())
+}
+
+/// A command running behind the rain. What it prints is held back until
+/// the end so it doesn't scribble over the screen.
+pub struct Job {
+    child: Child,
+    stdout: Pipe,
+    stderr: Pipe,
+    killed: bool,
+}
+
+impl Job {
+    pub fn spawn(cmd: &str) -> io::Result<Job> {
+        let mut child = shell(cmd)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+        Ok(Job {
+            stdout: Pipe::collect(child.stdout.take()),
+            stderr: Pipe::collect(child.stderr.take()),
+            child,
+            killed: false,
+        })
+    }
+
+    /// Whether the command has finished.
+    pub fn done(&mut self) -> io::Result<bool> {
+        Ok(self.child.try_wait()?.is_some())
+    }
+
+    /// Stop the command if it's still running.
+    pub fn kill(&mut self) -> io::Result<()> {
+        if self.child.try_wait()?.is_none() {
+            self.child.kill()?;
+            self.killed = true;
+        }
+        Ok(())
+    }
+
+    /// Wait for the command to finish, print what it printed, and return
+    /// the code it exited with; 1 if a signal ended it.
+    pub fn finish(mut self) -> io::Result<i32> {
+        let status = self.child.wait()?;
+        // Anything the killed command started may still hold its pipes
+        // open, so take what's arrived rather than wait for the end
+        let wait = !self.killed;
+        io::stdout().write_all(&self.stdout.take(wait))?;
+        io::stderr().write_all(&self.stderr.take(wait))?;
+        Ok(status.code().unwrap_or(1))
+    }
+}
+
+/// Output from one of the command's pipes, read on its own thread so a
+/// chatty command never blocks on a full pipe.
+struct Pipe {
+    output: Arc<Mutex<Vec<u8>>>,
+    reader: JoinHandle<()>,
+}
+
+impl Pipe {
+    fn collect(pipe: Option<impl Read + Send + 'static>) -> Pipe {
+        let output = Arc::new(Mutex::new(vec![]));
+        let shared = Arc::clone(&output);
+        let reader = thread::spawn(move || {
+            let Some(mut pipe) = pipe else {
+                return;
+            };
+            let mut chunk = [0; 4096];
+            // Whatever arrived before a read error is still worth showing
+            while let Ok(len @ 1..) = pipe.read(&mut chunk) {
+                if let Ok(mut output) = shared.lock() {
+                    output.extend_from_slice(&chunk[..len]);
+                }
+            }
+        });
+        Pipe { output, reader }
+    }
+
+    /// What's been read, after waiting for the end of it if `wait`.
+    fn take(self, wait: bool) -> Vec<u8> {
+        if wait {
+            let _ = self.reader.join();
+        }
+        let mut output = self.output.lock().unwrap_or_else(|err| err.into_inner());
+        std::mem::take(&mut *output)
+    }
+}
@@ -0,0 +1,344 @@
+//! Which keys do what at runtime. The config file's `[keys]` table remaps
+//! them, naming an action and the key or keys for it, e.g.
+//!
+//! ```toml
+//! [keys]
+//! quit = ["q", "esc"]
+//! speed_up = "k"
+//! speed_down = "j"
+//! ```
+
+use crate::platform::{KeyCode, KeyEvent, KeyModifiers};
+
+/// Something a runtime key does.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Action {
+    SpeedUp,
+    SpeedDown,
+    DensityUp,
+    DensityDown,
+    LengthUp,
+    LengthDown,
+    Green,
+    Blue,
+    Red,
+    Purple,
+    Cyan,
+    Rainbow,
+    WindLeft,
+    WindRight,
+    BrightnessDown,
+    BrightnessUp,
+    GhostingDown,
+    GhostingUp,
+    Reverse,
+    Shimmer,
+    Debug,
+    Fps,
+    BulletTime,
+    Pause,
+    Step,
+    StepTen,
+    NextPreset,
+    PrevPreset,
+    Reset,
+    Help,
+    Screenshot,
+    ScreenshotPlain,
+    SaveConfig,
+    Copy,
+    CopyAnsi,
+    Quit,
+}
+
+impl Action {
+    /// Whether holding the key down keeps doing it, e.g. holding Up keeps
+    /// speeding up, rather than doing it once.
+    pub fn repeats(self) -> bool {
+        matches!(
+            self,
+            Action::SpeedUp
+                | Action::SpeedDown
+                | Action::DensityUp
+                | Action::DensityDown
+                | Action::LengthUp
+                | Action::LengthDown
+                | Action::WindLeft
+                | Action::WindRight
+                | Action::BrightnessDown
+                | Action::BrightnessUp
+                | Action::GhostingDown
+                | Action::GhostingUp
+                | Action::Step
+                | Action::StepTen
+        )
+    }
+}
+
+/// Every action, by the name the config file uses for it.
+const ACTIONS: [(&str, Action); 36] = [
+    ("speed_up", Action::SpeedUp),
+    ("speed_down", Action::SpeedDown),
+    ("density_up", Action::DensityUp),
+    ("density_down", Action::DensityDown),
+    ("length_up", Action::LengthUp),
+    ("length_down", Action::LengthDown),
+    ("green", Action::Green),
+    ("blue", Action::Blue),
+    ("red", Action::Red),
+    ("purple", Action::Purple),
+    ("cyan", Action::Cyan),
+    ("rainbow", Action::Rainbow),
+    ("wind_left", Action::WindLeft),
+    ("wind_right", Action::WindRight),
+    ("brightness_down", Action::BrightnessDown),
+    ("brightness_up", Action::BrightnessUp),
+    ("ghosting_down", Action::GhostingDown),
+    ("ghosting_up", Action::GhostingUp),
+    ("reverse", Action::Reverse),
+    ("shimmer", Action::Shimmer),
+    ("debug", Action::Debug),
+    ("fps", Action::Fps),
+    ("bullet_time", Action::BulletTime),
+    ("pause", Action::Pause),
+    ("step", Action::Step),
+    ("step_ten", Action::StepTen),
+    ("next_preset", Action::NextPreset),
+    ("prev_preset", Action::PrevPreset),
+    ("reset", Action::Reset),
+    ("help", Action::Help),
+    ("screenshot", Action::Screenshot),
+    ("screenshot_plain", Action::ScreenshotPlain),
+    ("save_config", Action::SaveConfig),
+    ("copy", Action::Copy),
+    ("copy_ansi", Action::CopyAnsi),
+    ("quit", Action::Quit),
+];
+
+/// The keys each action starts out on.
+const DEFAULTS: [(Action, &[&str]); 36] = [
+    (Action::SpeedUp, &["up"]),
+    (Action::SpeedDown, &["down"]),
+    (Action::DensityUp, &["right"]),
+    (Action::DensityDown, &["left"]),
+    (Action::LengthUp, &["+", "="]),
+    (Action::LengthDown, &["-"]),
+    (Action::Green, &["1"]),
+    (Action::Blue, &["2"]),
+    (Action::Red, &["3"]),
+    (Action::Purple, &["4"]),
+    (Action::Cyan, &["5"]),
+    (Action::Rainbow, &["6"]),
+    (Action::WindLeft, &["w"]),
+    (Action::WindRight, &["W"]),
+    (Action::BrightnessDown, &["{"]),
+    (Action::BrightnessUp, &["}"]),
+    (Action::GhostingDown, &["("]),
+    (Action::GhostingUp, &[")"]),
+    (Action::Reverse, &["u"]),
+    (Action::Shimmer, &["g"]),
+    (Action::Debug, &["d"]),
+    (Action::Fps, &["f"]),
+    (Action::BulletTime, &["b"]),
+    (Action::Pause, &["p"]),
+    (Action::Step, &["."]),
+    (Action::StepTen, &[","]),
+    (Action::NextPreset, &["tab", "]"]),
+    (Action::PrevPreset, &["shift+tab", "["]),
+    (Action::Reset, &["r"]),
+    (Action::Help, &["?"]),
+    (Action::Screenshot, &["s"]),
+    (Action::ScreenshotPlain, &["S"]),
+    (Action::SaveConfig, &["ctrl+s"]),
+    (Action::Copy, &["y"]),
+    (Action::CopyAnsi, &["Y"]),
+    (Action::Quit, &["q", "esc", "enter", "space", "ctrl+d", "ctrl+z"]),
+];
+
+/// A key as bound, with the modifiers that have to be held for it. Shift
+/// is left out: it's already in the character, `W` rather than `w`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Binding {
+    code: KeyCode,
+    ctrl: bool,
+    alt: bool,
+}
+
+impl Binding {
+    /// Parse a key name such as `q`, `esc`, `up`, `space` or `ctrl+s`.
+    fn from_str(s: &str) -> Option<Self> {
+        let (mut ctrl, mut alt) = (false, false);
+        let mut rest = s;
+        loop {
+            let lower = rest.to_lowercase();
+            if lower.starts_with("ctrl+") && rest.len() > 5 {
+                ctrl = true;
+                rest = &rest[5..];
+            } else if lower.starts_with("alt+") && rest.len() > 4 {
+                alt = true;
+                rest = &rest[4..];
+            } else {
+                break;
+            }
+        }
+        let code = match rest.to_lowercase().as_str() {
+            "up" => KeyCode::Up,
+            "down" => KeyCode::Down,
+            "left" => KeyCode::Left,
+            "right" => KeyCode::Right,
+            "enter" | "return" => KeyCode::Enter,
+            "esc" | "escape" => KeyCode::Esc,
+            "backspace" => KeyCode::Backspace,
+            "tab" => KeyCode::Tab,
+            "shift+tab" | "backtab" => KeyCode::BackTab,
+            "space" => KeyCode::Char(' '),
+            _ => {
+                let mut chars = rest.chars();
+                match (chars.next(), chars.next()) {
+                    // Control keys arrive lowercase
+                    (Some(ch), None) if ctrl => KeyCode::Char(ch.to_ascii_lowercase()),
+                    (Some(ch), None) => KeyCode::Char(ch),
+                    _ => return None,
+                }
+            }
+        };
+        Some(Binding { code, ctrl, alt })
+    }
+
+    fn matches(self, key: KeyEvent) -> bool {
+        self.code == key.code
+            && self.ctrl == key.modifiers.contains(KeyModifiers::CONTROL)
+            && self.alt == key.modifiers.contains(KeyModifiers::ALT)
+    }
+
+    /// The key as the help shows it, e.g. `↑` or `Ctrl+S`.
+    fn name(self) -> String {
+        let name = match self.code {
+            KeyCode::Up => "↑".to_string(),
+            KeyCode::Down => "↓".to_string(),
+            KeyCode::Left => "←".to_string(),
+            KeyCode::Right => "→".to_string(),
+            KeyCode::Enter => "Enter".to_string(),
+            KeyCode::Esc => "Esc".to_string(),
+            KeyCode::Backspace => "Backspace".to_string(),
+            KeyCode::Tab => "Tab".to_string(),
+            KeyCode::BackTab => "Shift+Tab".to_string(),
+            KeyCode::Char(' ') => "Space".to_string(),
+            KeyCode::Char(ch) if self.ctrl => ch.to_ascii_uppercase().to_string(),
+            KeyCode::Char(ch) => ch.to_string(),
+            // The browser's stand-in has no other keys
+            #[allow(unreachable_patterns)]
+            code => format!("{:?}", code),
+        };
+        let mut prefix = String::new();
+        if self.ctrl {
+            prefix += "Ctrl+";
+        }
+        if self.alt {
+            prefix += "Alt+";
+        }
+        prefix + &name
+    }
+}
+
+/// The runtime keys and what each does.
+#[derive(Clone)]
+pub struct Keymap {
+    bindings: Vec<(Binding, Action)>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        let mut bindings = vec![];
+        for (action, keys) in DEFAULTS {
+            for key in keys {
+                let binding = Binding::from_str(key).expect("default keys parse");
+                bindings.push((binding, action));
+            }
+        }
+        Keymap { bindings }
+    }
+}
+
+impl Keymap {
+    /// What `key` does, if anything.
+    pub fn action(&self, key: KeyEvent) -> Option<Action> {
+        self.bindings
+            .iter()
+            .find(|(binding, _)| binding.matches(key))
+            .map(|&(_, action)| action)
+    }
+
+    /// Put the action called `name` on `keys` instead of its own. A key
+    /// that did something else before now does only this.
+    pub fn bind(&mut self, name: &str, keys: &[String]) -> Result<(), String> {
+        let action = ACTIONS
+            .iter()
+            .find(|(action, _)| *action == name)
+            .map(|&(_, action)| action)
+            .ok_or_else(|| format!("no action called {}", name))?;
+        let keys = keys
+            .iter()
+            .map(|key| Binding::from_str(key).ok_or_else(|| format!("{}: unknown key {}", name, key)))
+            .collect::<Result<Vec<_>, _>>()?;
+        self.bindings.retain(|(_, bound)| *bound != action);
+        self.add(action, &keys);
+        Ok(())
+    }
+
+    /// Put `action` on `keys` as well as the keys it's on already, taking
+    /// them from whatever they did before.
+    fn add(&mut self, action: Action, keys: &[Binding]) {
+        self.bindings.retain(|(binding, _)| !keys.contains(binding));
+        self.bindings.extend(keys.iter().map(|&binding| (binding, action)));
+    }
+
+    /// `--vim`: hjkl for speed and density alongside the arrows, and Space
+    /// pauses rather than quits.
+    pub fn vim(&mut self) {
+        for (key, action) in [
+            ("k", Action::SpeedUp),
+            ("j", Action::SpeedDown),
+            ("l", Action::DensityUp),
+            ("h", Action::DensityDown),
+            ("space", Action::Pause),
+        ] {
+            let binding = Binding::from_str(key).expect("vim keys parse");
+            self.add(action, &[binding]);
+        }
+    }
+
+    /// The keys for `actions`, as the help shows them, e.g. `↑/↓`.
+    pub fn describe(&self, actions: &[Action]) -> String {
+        let names: Vec<String> = actions
+            .iter()
+            .flat_map(|&action| {
+                self.bindings
+                    .iter()
+                    .filter(move |(_, bound)| *bound == action)
+                    .map(|(binding, _)| binding.name())
+            })
+            .collect();
+        if names.is_empty() {
+            "(none)".to_string()
+        } else {
+            names.join("/")
+        }
+    }
+
+    /// Whether `actions` are all still on just their default keys.
+    pub fn is_default(&self, actions: &[Action]) -> bool {
+        let default = Keymap::default();
+        actions.iter().all(|&action| {
+            let keys = |keymap: &Keymap| -> Vec<Binding> {
+                keymap
+                    .bindings
+                    .iter()
+                    .filter(|(_, bound)| *bound == action)
+                    .map(|&(binding, _)| binding)
+                    .collect()
+            };
+            keys(self) == keys(&default)
+        })
+    }
+}
@@ -0,0 +1,215 @@
+use std::time::Duration;
+
+use crate::{
+    color::Sweep,
+    drop::CHARS,
+    overlay::{Canvas, Overlay, Z_REVEAL},
+    platform::{Color, Instant, KeyCode, KeyEvent, KeyModifiers},
+    render::Glyph,
+};
+
+/// How long a message takes to decode out of the rain...
+const DECODE: Duration = Duration::from_millis(1500);
+/// ...stays up, plus a little more for every character...
+const HOLD: Duration = Duration::from_secs(3);
+const HOLD_PER_CHAR: Duration = Duration::from_millis(100);
+/// ...takes to dissolve back into it...
+const DISSOLVE: Duration = Duration::from_millis(1500);
+/// ...and how long the rain runs alone before the next.
+const GAP: Duration = Duration::from_secs(2);
+
+/// Share of the cells scrambling at once while a message comes and goes.
+const SCRAMBLE: f32 = 0.3;
+
+/// Capitals, digits and some punctuation in 3x5 blocks, for messages big
+/// enough to read across a room. Lowercase comes out as capitals.
+const FONT: [(char, [&str; 5]); 50] = [
+    ('A', [".#.", "#.#", "###", "#.#", "#.#"]),
+    ('B', ["##.", "#.#", "##.", "#.#", "##."]),
+    ('C', [".##", "#..", "#..", "#..", ".##"]),
+    ('D', ["##.", "#.#", "#.#", "#.#", "##."]),
+    ('E', ["###", "#..", "##.", "#..", "###"]),
+    ('F', ["###", "#..", "##.", "#..", "#.."]),
+    ('G', [".##", "#..", "#.#", "#.#", ".##"]),
+    ('H', ["#.#", "#.#", "###", "#.#", "#.#"]),
+    ('I', ["###", ".#.", ".#.", ".#.", "###"]),
+    ('J', ["..#", "..#", "..#", "#.#", ".#."]),
+    ('K', ["#.#", "#.#", "##.", "#.#", "#.#"]),
+    ('L', ["#..", "#..", "#..", "#..", "###"]),
+    ('M', ["#.#", "###", "###", "#.#", "#.#"]),
+    ('N', ["##.", "#.#", "#.#", "#.#", "#.#"]),
+    ('O', [".#.", "#.#", "#.#", "#.#", ".#."]),
+    ('P', ["##.", "#.#", "##.", "#..", "#.."]),
+    ('Q', [".#.", "#.#", "#.#", "##.", ".##"]),
+    ('R', ["##.", "#.#", "##.", "#.#", "#.#"]),
+    ('S', [".##", "#..", ".#.", "..#", "##."]),
+    ('T', ["###", ".#.", ".#.", ".#.", ".#."]),
+    ('U', ["#.#", "#.#", "#.#", "#.#", "###"]),
+    ('V', ["#.#", "#.#", "#.#", "#.#", ".#."]),
+    ('W', ["#.#", "#.#", "###", "###", "#.#"]),
+    ('X', ["#.#", "#.#", ".#.", "#.#", "#.#"]),
+    ('Y', ["#.#", "#.#", ".#.", ".#.", ".#."]),
+    ('Z', ["###", "..#", ".#.", "#..", "###"]),
+    ('0', ["###", "#.#", "#.#", "#.#", "###"]),
+    ('1', [".#.", "##.", ".#.", ".#.", "###"]),
+    ('2', ["##.", "..#", ".#.", "#..", "###"]),
+    ('3', ["##.", "..#", ".#.", "..#", "##."]),
+    ('4', ["#.#", "#.#", "###", "..#", "..#"]),
+    ('5', ["###", "#..", "##.", "..#", "##."]),
+    ('6', [".##", "#..", "###", "#.#", "###"]),
+    ('7', ["###", "..#", ".#.", ".#.", ".#."]),
+    ('8', ["###", "#.#", "###", "#.#", "###"]),
+    ('9', ["###", "#.#", "###", "..#", "##."]),
+    (' ', ["...", "...", "...", "...", "..."]),
+    ('.', ["...", "...", "...", "...", ".#."]),
+    (',', ["...", "...", "...", ".#.", "#.."]),
+    ('!', [".#.", ".#.", ".#.", "...", ".#."]),
+    ('?', ["##.", "..#", ".#.", "...", ".#."]),
+    ('-', ["...", "...", "###", "...", "..."]),
+    ('+', ["...", ".#.", "###", ".#.", "..."]),
+    (':', ["...", ".#.", "...", ".#.", "..."]),
+    ('\'', [".#.", ".#.", "...", "...", "..."]),
+    ('"', ["#.#", "#.#", "...", "...", "..."]),
+    ('/', ["..#", "..#", ".#.", "#..", "#.."]),
+    ('(', [".#.", "#..", "#..", "#..", ".#."]),
+    (')', [".#.", "..#", "..#", "..#", ".#."]),
+    ('&', [".#.", "#.#", ".#.", "#.#", ".##"]),
+];
+
+/// Whether the 3x5 block for `ch`, or `?` without one, is filled at `x`,
+/// `y`. The fourth column is the gap between letters.
+pub fn pixel(ch: char, x: usize, y: usize) -> bool {
+    let block = |ch: char| FONT.iter().find(|(c, _)| *c == ch).map(|(_, rows)| rows);
+    let rows = block(ch).or_else(|| block('?')).expect("? is in the font");
+    rows[y].as_bytes().get(x) == Some(&b'#')
+}
+
+/// Whether `key` is Ctrl+Alt+Q, the one key `--kiosk-lock` leaves working.
+pub fn is_chord(key: KeyEvent) -> bool {
+    matches!(key.code, KeyCode::Char('q') | KeyCode::Char('Q'))
+        && key.modifiers.contains(KeyModifiers::CONTROL)
+        && key.modifiers.contains(KeyModifiers::ALT)
+}
+
+/// A number in 0..1 that's always the same for the same inputs.
+pub fn noise(x: u16, y: u16, salt: u32) -> f32 {
+    let mut n = (x as u32).wrapping_mul(0x9e37_79b1) ^ (y as u32).wrapping_mul(0x85eb_ca77) ^ salt;
+    n ^= n >> 15;
+    n = n.wrapping_mul(0x2c1b_3c6d);
+    n ^= n >> 12;
+    (n & 0xffff) as f32 / 65536.0
+}
+
+/// `--kiosk`: the lines of a text file, one after another for ever, each
+/// decoding out of the rain as a big centered message, holding, then
+/// dissolving back into it.
+pub struct Kiosk {
+    lines: Vec<String>,
+    line: usize,
+    shown: Instant, // When the current line started decoding
+}
+
+impl Kiosk {
+    /// Read the file's lines, leaving out blank ones.
+    pub fn load(path: &str) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path).map_err(|err| err.to_string())?;
+        let lines: Vec<String> = text
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(str::to_string)
+            .collect();
+        if lines.is_empty() {
+            return Err("it has no lines to show".to_string());
+        }
+        Ok(Kiosk {
+            lines,
+            line: 0,
+            shown: Instant::now(),
+        })
+    }
+
+    fn hold(&self) -> Duration {
+        HOLD + HOLD_PER_CHAR * self.lines[self.line].chars().count() as u32
+    }
+
+    /// Move on to the next line once this one's gone.
+    pub fn tick(&mut self) {
+        if self.shown.elapsed() >= DECODE + self.hold() + DISSOLVE + GAP {
+            self.line = (self.line + 1) % self.lines.len();
+            self.shown = Instant::now();
+        }
+    }
+}
+
+impl Overlay for Kiosk {
+    fn z(&self) -> u8 {
+        Z_REVEAL
+    }
+
+    /// Set the current line into the middle of the screen, in big blocks if
+    /// it fits and as plain text if not, each cell in its own time.
+    fn draw(&self, canvas: &mut Canvas) {
+        let (cols, rows) = (canvas.cols, canvas.rows);
+        let elapsed = self.shown.elapsed();
+        let hold = self.hold();
+        // How far through coming (rising) or going (falling) the line is
+        let progress = if elapsed < DECODE {
+            elapsed.as_secs_f32() / DECODE.as_secs_f32()
+        } else if elapsed < DECODE + hold {
+            1.0
+        } else if elapsed < DECODE + hold + DISSOLVE {
+            1.0 - (elapsed - DECODE - hold).as_secs_f32() / DISSOLVE.as_secs_f32()
+        } else {
+            return;
+        };
+        // Scale so the last cells are still in time to settle
+        let progress = progress * (1.0 + SCRAMBLE);
+
+        let chars: Vec<char> = self.lines[self.line].chars().collect();
+        let big_width = (chars.len() * 4).saturating_sub(1);
+        let big = big_width + 4 <= cols as usize && rows >= 7;
+        let (width, height) = if big {
+            (big_width as u16, 5)
+        } else {
+            ((chars.len() as u16).min(cols.saturating_sub(4)), 1)
+        };
+        // A cell of margin all round keeps the rain off the message
+        let (box_width, box_height) = ((width + 4).min(cols), (height + 2).min(rows));
+        let (left, top) = ((cols - box_width) / 2, (rows - box_height) / 2);
+
+        let salt = self.line as u32 * 7919;
+        let tumble = (elapsed.as_millis() / 90) as u32;
+        for y in 0..box_height {
+            for x in 0..box_width {
+                let threshold = noise(x, y, salt);
+                if threshold >= progress {
+                    continue;
+                }
+                let (cx, cy) = (x as i32 - 2, y as i32 - 1);
+                let inside = cx >= 0 && cy >= 0 && cx < width as i32 && cy < height as i32;
+                let lit = inside && {
+                    let (cx, cy) = (cx as usize, cy as usize);
+                    if big {
+                        let ch = chars.get(cx / 4).copied().unwrap_or(' ');
+                        pixel(ch.to_ascii_uppercase(), cx % 4, cy)
+                    } else {
+                        chars.get(cx).is_some_and(|ch| *ch != ' ')
+                    }
+                };
+                let (gx, gy) = (left + x, top + y);
+                let glyph = if threshold + SCRAMBLE >= progress {
+                    let ch = CHARS[(noise(x, y, salt ^ tumble) * CHARS.len() as f32) as usize];
+                    Glyph::new(ch, canvas.scheme.get_colors(1, 4, gx, Sweep::Horizontal))
+                } else if lit && big {
+                    Glyph::new('█', Color::White)
+                } else if lit {
+                    Glyph::new(chars[(x - 2) as usize], Color::White)
+                } else {
+                    Glyph::BLANK
+                };
+                canvas.put(gx, gy, glyph);
+            }
+        }
+    }
+}
@@ -0,0 +1,116 @@
+//! The Matrix digital rain, as a terminal screensaver and as a library for
+//! embedding the rain in other programs.
+
+mod alert;
+mod backdrop;
+pub mod banner;
+#[cfg(all(feature = "battery", not(target_arch = "wasm32")))]
+mod battery;
+pub mod bench;
+mod buffer;
+mod burnin;
+#[cfg(not(target_arch = "wasm32"))]
+mod chooser;
+pub mod cli;
+#[cfg(feature = "clipboard")]
+mod clipboard;
+mod color;
+mod compositor;
+#[cfg(windows)]
+mod conhost;
+mod console;
+mod countdown;
+mod daylight;
+mod demo;
+mod dither;
+#[cfg(not(target_arch = "wasm32"))]
+mod config;
+mod drop;
+mod effect;
+#[cfg(feature = "export")]
+pub mod export;
+#[cfg(all(feature = "ffi", not(target_arch = "wasm32")))]
+mod ffi;
+#[cfg(not(target_arch = "wasm32"))]
+mod forecast;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod forever;
+mod fps;
+mod frame;
+mod game;
+mod glitch;
+mod hollywood;
+#[cfg(feature = "http")]
+pub mod http;
+#[cfg(unix)]
+pub mod idle;
+#[cfg(not(target_arch = "wasm32"))]
+mod intro;
+#[cfg(not(target_arch = "wasm32"))]
+mod job;
+mod keymap;
+mod kiosk;
+mod lightning;
+#[cfg(target_os = "linux")]
+pub mod logind;
+#[cfg(feature = "lua")]
+mod lua;
+mod mask;
+mod matrix;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod motd;
+mod night;
+pub mod overlay;
+#[cfg(not(target_arch = "wasm32"))]
+mod pacer;
+#[cfg(feature = "image")]
+mod picture;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod play;
+#[cfg(not(target_arch = "wasm32"))]
+mod profile;
+mod platform;
+#[cfg(feature = "plugins")]
+mod plugin;
+mod qr;
+mod rain;
+#[cfg(feature = "export")]
+mod raster;
+#[cfg(not(target_arch = "wasm32"))]
+mod record;
+mod render;
+mod rotate;
+mod sakura;
+mod schedule;
+pub mod schemes;
+mod screen;
+mod script;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod serve;
+mod settings;
+pub mod simulate;
+mod snow;
+mod splash;
+#[cfg(all(feature = "sound", not(target_arch = "wasm32")))]
+mod sound;
+mod storm;
+mod stuck;
+#[cfg(not(target_arch = "wasm32"))]
+mod sync;
+#[cfg(not(target_arch = "wasm32"))]
+mod term;
+#[cfg(all(feature = "watch", not(target_arch = "wasm32")))]
+mod watch;
+mod watermark;
+#[cfg(target_arch = "wasm32")]
+pub mod web;
+#[cfg(feature = "ratatui")]
+pub mod widget;
+#[cfg(feature = "zellij")]
+pub mod zellij;
+
+pub use frame::Frame;
+#[cfg(not(target_arch = "wasm32"))]
+pub use matrix::Exit;
+pub use matrix::Matrix;
+pub use settings::Settings;
@@ -0,0 +1,72 @@
+use rand::Rng;
+
+use crate::{color::brighten, platform::Color, render::Glyph};
+
+/// Below this the flash has faded out completely.
+const CUTOFF: f32 = 0.05;
+
+/// Rare `--lightning` flashes that wash every visible glyph toward white,
+/// either across the whole screen or down a band of columns, then fade.
+pub struct Lightning {
+    per_minute: f64,
+    flash: f32,                  // How far glyphs are pushed toward white right now
+    columns: Option<(u16, u16)>, // Start and width of a column strike, None = whole screen
+}
+
+impl Lightning {
+    pub fn new(per_minute: f64) -> Self {
+        Lightning {
+            per_minute,
+            flash: 0.0,
+            columns: None,
+        }
+    }
+
+    /// Flash the whole screen now, due or not.
+    pub fn strike(&mut self) {
+        self.flash = 1.0;
+        self.columns = None;
+    }
+
+    /// Advance by a frame that lasts `frame_ms`, maybe striking again.
+    /// Heavier weather (`intensity` above 1.0) strikes more often.
+    pub fn tick(&mut self, cols: u16, frame_ms: u64, intensity: f64, rng: &mut impl Rng) {
+        self.flash *= 0.75;
+        if self.flash < CUTOFF {
+            self.flash = 0.0;
+        }
+
+        let chance = self.per_minute * intensity * frame_ms.max(1) as f64 / 60_000.0;
+        if cols == 0 || !rng.gen_bool(chance.clamp(0.0, 1.0)) {
+            return;
+        }
+        self.flash = rng.gen_range(0.6..1.0);
+        self.columns = if rng.gen_bool(0.4) {
+            let width = rng.gen_range((cols / 8).max(1)..=(cols / 3).max(1));
+            Some((rng.gen_range(0..cols), width))
+        } else {
+            None
+        };
+    }
+
+    /// Brighten the lit cells of a row-major `cols`-wide frame.
+    pub fn apply(&self, frame: &mut [Glyph], cols: u16) {
+        if self.flash == 0.0 || cols == 0 {
+            return;
+        }
+        for (idx, glyph) in frame.iter_mut().enumerate() {
+            let x = (idx % cols as usize) as u16;
+            if let Some((start, width)) = self.columns {
+                if x < start || x >= start.saturating_add(width) {
+                    continue;
+                }
+            }
+            if glyph.ch != ' ' {
+                glyph.fg = brighten(glyph.fg, self.flash);
+            }
+            if glyph.bg != Color::Reset {
+                glyph.bg = brighten(glyph.bg, self.flash);
+            }
+        }
+    }
+}
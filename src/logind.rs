@@ -0,0 +1,122 @@
+//! `--on-lock`: rain while the desktop session is locked, following
+//! systemd-logind's Lock and Unlock signals on the system bus, as
+//! `gdbus monitor` reports them.
+
+use std::{
+    io::{self, BufRead, BufReader},
+    process::{Child, Command, Stdio},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread,
+    time::Duration,
+};
+
+use crate::{
+    cli,
+    matrix::{Exit, Matrix},
+    platform::{KeyCode, KeyModifiers},
+    settings::Settings,
+};
+
+/// How often the wait for a lock checks on it and on `gdbus`.
+const TICK: Duration = Duration::from_millis(200);
+
+/// The bus path of logind's session `id`, escaped the way logind does:
+/// anything but letters and digits, and a leading digit, as `_xx` hex.
+fn session_path(id: &str) -> String {
+    let mut path = "/org/freedesktop/login1/session/".to_string();
+    for (i, byte) in id.bytes().enumerate() {
+        if byte.is_ascii_alphabetic() || (byte.is_ascii_digit() && i > 0) {
+            path.push(byte as char);
+        } else {
+            path += &format!("_{:02x}", byte);
+        }
+    }
+    path
+}
+
+/// Start `gdbus` watching this session, or every session outside of one,
+/// and keep `unlocked` up to date from what it sees.
+fn monitor(unlocked: &Arc<AtomicBool>) -> io::Result<Child> {
+    let mut command = Command::new("gdbus");
+    command.args(["monitor", "--system", "--dest", "org.freedesktop.login1"]);
+    if let Some(id) = std::env::var("XDG_SESSION_ID").ok().filter(|id| !id.is_empty()) {
+        command.args(["--object-path", &session_path(&id)]);
+    }
+    let mut child = command
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|err| io::Error::new(err.kind(), format!("--on-lock needs gdbus: {}", err)))?;
+    let stdout = child.stdout.take().expect("piped stdout");
+    let unlocked = Arc::clone(unlocked);
+    thread::spawn(move || {
+        // e.g. `/org/freedesktop/login1/session/_32: org.freedesktop.login1.Session.Lock ()`
+        for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+            if line.contains("org.freedesktop.login1.Session.Lock ") {
+                unlocked.store(false, Ordering::Relaxed);
+            } else if line.contains("org.freedesktop.login1.Session.Unlock ") {
+                unlocked.store(true, Ordering::Relaxed);
+            }
+        }
+    });
+    Ok(child)
+}
+
+/// Wait until the session is `locked`, or not. False if `gdbus` went away
+/// or SIGTERM came meanwhile.
+fn wait(
+    unlocked: &AtomicBool,
+    locked: bool,
+    gdbus: &mut Child,
+    terminated: &AtomicBool,
+) -> io::Result<bool> {
+    while unlocked.load(Ordering::Relaxed) == locked {
+        if terminated.load(Ordering::Relaxed) || gdbus.try_wait()?.is_some() {
+            return Ok(false);
+        }
+        thread::sleep(TICK);
+    }
+    Ok(true)
+}
+
+/// Rain with `settings` each time the session locks, until it unlocks or
+/// a key is pressed, and wait for the next lock in between. Ctrl+C, or
+/// SIGTERM, ends it.
+pub fn run(settings: Settings) -> io::Result<()> {
+    let terminated = Arc::new(AtomicBool::new(false));
+    signal_hook::flag::register(signal_hook::consts::SIGTERM, Arc::clone(&terminated))?;
+    let unlocked = Arc::new(AtomicBool::new(true));
+    let mut gdbus = monitor(&unlocked)?;
+    println!("Matrix Rain - waiting for the session to lock (Ctrl+C to stop)");
+
+    let mut settings = Some(settings);
+    let code = loop {
+        if !wait(&unlocked, true, &mut gdbus, &terminated)? {
+            break if terminated.load(Ordering::Relaxed) { 143 } else { 1 };
+        }
+        // Fresh settings each time, the first already parsed
+        let mut matrix = Matrix::new(settings.take().unwrap_or_else(cli::parse_args));
+        matrix.stop_on(Arc::clone(&unlocked));
+        match matrix.run()? {
+            Exit::Key(key)
+                if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) =>
+            {
+                break 0;
+            }
+            Exit::Terminated => break 143,
+            _ => {}
+        }
+        // A key while still locked waits out this lock
+        if !wait(&unlocked, false, &mut gdbus, &terminated)? {
+            break if terminated.load(Ordering::Relaxed) { 143 } else { 1 };
+        }
+    };
+    let _ = gdbus.kill();
+    if code == 1 {
+        eprintln!("matrix: --on-lock: gdbus monitor stopped; is logind running?");
+    }
+    std::process::exit(code)
+}
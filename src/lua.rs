@@ -0,0 +1,124 @@
+use crossterm::style::Color;
+use mlua::{Function, Lua, Table, Value};
+use rand::Rng;
+use std::time::{Duration, Instant};
+
+use crate::{
+    color::ColorScheme,
+    drop::{Drop, Layer},
+    settings::Settings,
+};
+
+/// A `--lua` script. Any of these globals it defines are called as the rain
+/// runs; errors inside a hook are ignored so a typo can't take the
+/// screensaver down.
+///
+/// ```lua
+/// -- Called for every new drop; change `length` or `speed`, or return
+/// -- false to skip it. `x` is across the rain, `layer` "front" or "back".
+/// function on_spawn(drop) drop.length = drop.length * 2 end
+///
+/// -- Color for glyph `i` of a drop `length` long in column `x`, `t` seconds
+/// -- in: return r, g, b, or nothing to use the color scheme.
+/// function color(i, length, x, t) return 0, 255 - i * 8, 128 end
+///
+/// -- Called every frame; return a table to change settings, with the same
+/// -- keys as a `--script` cue (color, density, speed, length, wind).
+/// function on_frame(t) return { wind = math.sin(t / 10) * 5 } end
+/// ```
+pub struct LuaHooks {
+    lua: Lua,
+    started: Instant,
+}
+
+impl LuaHooks {
+    /// Run a script file so it can define its hooks.
+    pub fn load(path: &str) -> Result<Self, String> {
+        let source = std::fs::read_to_string(path).map_err(|err| err.to_string())?;
+        let lua = Lua::new();
+        lua.load(source)
+            .set_name(path)
+            .exec()
+            .map_err(|err| err.to_string())?;
+        Ok(LuaHooks {
+            lua,
+            started: Instant::now(),
+        })
+    }
+
+    /// Seconds since the script was loaded, the `t` every hook gets.
+    fn time(&self) -> f64 {
+        self.started.elapsed().as_secs_f64()
+    }
+
+    fn hook(&self, name: &str) -> Option<Function<'_>> {
+        self.lua.globals().get(name).ok()
+    }
+
+    /// Let `on_spawn` reshape a new drop. Returns whether it should spawn.
+    pub fn on_spawn(&self, across: f32, drop: &mut Drop, rng: &mut impl Rng) -> bool {
+        let Some(hook) = self.hook("on_spawn") else {
+            return true;
+        };
+        let Ok(table) = self.lua.create_table() else {
+            return true;
+        };
+        let layer = match drop.layer {
+            Layer::Back => "back",
+            Layer::Front => "front",
+        };
+        let _ = table.set("x", across);
+        let _ = table.set("length", drop.length());
+        let _ = table.set("speed", drop.speed);
+        let _ = table.set("layer", layer);
+        if let Ok(Value::Boolean(false)) = hook.call::<_, Value>(table.clone()) {
+            return false;
+        }
+        let length = table.get("length").unwrap_or(drop.length());
+        let speed = table.get("speed").unwrap_or(drop.speed);
+        drop.reshape(length, speed, rng);
+        true
+    }
+
+    /// The script's color for a glyph, if it has a `color` hook and it
+    /// returned one.
+    pub fn color(&self, i: usize, length: usize, x: u16) -> Option<Color> {
+        let hook = self.hook("color")?;
+        let (r, g, b) = hook
+            .call::<_, (Option<f64>, Option<f64>, Option<f64>)>((i, length, x, self.time()))
+            .ok()?;
+        let channel = |v: f64| v.clamp(0.0, 255.0) as u8;
+        Some(Color::Rgb {
+            r: channel(r?),
+            g: channel(g?),
+            b: channel(b?),
+        })
+    }
+
+    /// Call `on_frame` and apply any settings it hands back.
+    pub fn on_frame(&self, settings: &mut Settings) {
+        let Some(hook) = self.hook("on_frame") else {
+            return;
+        };
+        let Ok(Some(changes)) = hook.call::<_, Option<Table>>(self.time()) else {
+            return;
+        };
+        if let Ok(scheme) = changes.get::<_, String>("color") {
+            if let Some(scheme) = ColorScheme::from_str(&scheme) {
+                settings.color_scheme = scheme;
+            }
+        }
+        if let Ok(pct) = changes.get::<_, f64>("density") {
+            settings.density = (pct / 100.0).clamp(0.01, 1.0);
+        }
+        if let Ok(ms) = changes.get::<_, u64>("speed") {
+            settings.frame_delay = Duration::from_millis(ms.clamp(5, 1000));
+        }
+        if let Ok(length) = changes.get::<_, usize>("length") {
+            settings.max_length = length.max(settings.min_length);
+        }
+        if let Ok(wind) = changes.get::<_, f32>("wind") {
+            settings.wind = (wind / 10.0).clamp(-1.0, 1.0);
+        }
+    }
+}
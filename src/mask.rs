@@ -0,0 +1,108 @@
+/// Characters of a text mask from dark to bright, as ASCII art uses them.
+const RAMP: &str = " .:-=+*#%@";
+
+/// `--mask`: a picture of where the rain is heavy and where it's sparse.
+/// Each lane drops start down, a column or a row as the rain runs, is as
+/// likely to get them as the picture is bright along it, stretched to the
+/// screen.
+///
+/// The picture is a PGM image (`P2` or `P5`) or plain text, where `0`-`9`
+/// and ` .:-=+*#%@` step from dark to bright and anything else is bright.
+pub struct Mask {
+    width: usize,
+    height: usize,
+    values: Vec<f32>, // Brightness 0-1, row by row
+}
+
+impl Mask {
+    pub fn load(path: &str) -> Result<Self, String> {
+        let bytes = std::fs::read(path).map_err(|err| format!("could not read mask {}: {}", path, err))?;
+        let mask = match bytes.get(..2) {
+            Some(b"P2") | Some(b"P5") => Self::pgm(&bytes),
+            _ => Self::text(&String::from_utf8_lossy(&bytes)),
+        }
+        .map_err(|err| format!("invalid mask {}: {}", path, err))?;
+        if mask.values.iter().all(|&value| value == 0.0) {
+            return Err(format!("mask {} is dark all over, so no rain would fall", path));
+        }
+        Ok(mask)
+    }
+
+    fn text(text: &str) -> Result<Self, String> {
+        let lines: Vec<&str> = text.lines().collect();
+        let width = lines.iter().map(|line| line.chars().count()).max().unwrap_or(0);
+        if width == 0 {
+            return Err("it's empty".to_string());
+        }
+        let mut values = vec![0.0; width * lines.len()];
+        for (y, line) in lines.iter().enumerate() {
+            for (x, ch) in line.chars().enumerate() {
+                values[y * width + x] = match (ch.to_digit(10), RAMP.find(ch)) {
+                    (Some(digit), _) => digit as f32 / 9.0,
+                    (_, Some(step)) => step as f32 / (RAMP.len() - 1) as f32,
+                    _ => 1.0,
+                };
+            }
+        }
+        Ok(Mask { width, height: lines.len(), values })
+    }
+
+    /// A greyscale PGM, in either its text (`P2`) or binary (`P5`) form.
+    fn pgm(bytes: &[u8]) -> Result<Self, String> {
+        // The header is the magic number, width, height and maximum value,
+        // split by whitespace and # comments
+        let mut header = [0; 3];
+        let mut at = 2;
+        for field in &mut header {
+            loop {
+                match bytes.get(at) {
+                    Some(b'#') => at += bytes[at..].iter().position(|&b| b == b'\n').unwrap_or(bytes.len() - at),
+                    Some(b) if b.is_ascii_whitespace() => at += 1,
+                    _ => break,
+                }
+            }
+            let digits = bytes[at..].iter().take_while(|b| b.is_ascii_digit()).count();
+            *field = std::str::from_utf8(&bytes[at..at + digits])
+                .ok()
+                .and_then(|n| n.parse().ok())
+                .ok_or("truncated PGM header")?;
+            at += digits;
+        }
+        let [width, height, max] = header;
+        if width == 0 || height == 0 || max == 0 || max > 65535 {
+            return Err("bad PGM size or maximum value".to_string());
+        }
+        let samples: Vec<u32> = if &bytes[..2] == b"P2" {
+            let text = String::from_utf8_lossy(&bytes[at..]);
+            text.split_ascii_whitespace().map_while(|n| n.parse().ok()).collect()
+        } else {
+            // One whitespace byte, then a byte per sample, or two big-endian
+            // ones past 255
+            let data = bytes.get(at + 1..).unwrap_or_default();
+            match max {
+                ..=255 => data.iter().map(|&b| b as u32).collect(),
+                _ => data.chunks_exact(2).map(|pair| u16::from_be_bytes([pair[0], pair[1]]) as u32).collect(),
+            }
+        };
+        if samples.len() < width * height {
+            return Err(format!("{} of {}x{} samples", samples.len(), width, height));
+        }
+        let values = samples[..width * height].iter().map(|&n| n.min(max as u32) as f32 / max as f32).collect();
+        Ok(Mask { width, height, values })
+    }
+
+    /// How likely a drop is to start down lane `across` of `lanes`: the
+    /// mask's average brightness down its columns for `vertical` rain, or
+    /// along its rows for sideways rain.
+    pub fn chance(&self, across: f32, lanes: u16, vertical: bool) -> f64 {
+        let (size, length) = if vertical { (self.width, self.height) } else { (self.height, self.width) };
+        let lane = (across.max(0.0) as usize * size / lanes.max(1) as usize).min(size - 1);
+        let sum: f32 = (0..length)
+            .map(|i| match vertical {
+                true => self.values[i * self.width + lane],
+                false => self.values[lane * self.width + i],
+            })
+            .sum();
+        (sum / length as f32).min(1.0) as f64
+    }
+}
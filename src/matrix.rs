@@ -0,0 +1,2014 @@
+#[cfg(not(target_arch = "wasm32"))]
+use crossterm::{
+    event::{
+        poll, read, DisableFocusChange, DisableMouseCapture, EnableFocusChange, EnableMouseCapture,
+        Event, KeyEventKind, KeyboardEnhancementFlags, MouseEventKind, PopKeyboardEnhancementFlags,
+        PushKeyboardEnhancementFlags,
+    },
+    execute, queue,
+    terminal::{self, Clear, ClearType},
+};
+use rand::{rngs::SmallRng, Rng, SeedableRng};
+use std::{
+    collections::hash_map::DefaultHasher,
+    hash::{Hash, Hasher},
+    io::Write,
+    time::Duration,
+};
+#[cfg(unix)]
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+#[cfg(not(target_arch = "wasm32"))]
+use crate::{
+    chooser::Chooser,
+    config,
+    forecast::{Conditions, Forecast},
+    intro::Intro,
+    job::{self, Job},
+    pacer::Pacer,
+    platform::counts,
+    profile::{FrameTimes, Profiler},
+    record::Recorder,
+    sync::{Message, SyncClient, SyncServer},
+    term,
+};
+#[cfg(all(feature = "battery", not(target_arch = "wasm32")))]
+use crate::battery::BatteryWatch;
+#[cfg(feature = "clipboard")]
+use crate::clipboard::Clipboard;
+#[cfg(all(feature = "sound", not(target_arch = "wasm32")))]
+use crate::sound::Sound;
+#[cfg(all(feature = "watch", not(target_arch = "wasm32")))]
+use crate::watch::ConfigWatch;
+use crate::{
+    backdrop::Backdrop,
+    burnin,
+    cli::{self, Preset},
+    color::{desaturate, dim, gamma_dim, lightness, ColorScheme},
+    compositor::Compositor,
+    console,
+    countdown::Countdown,
+    daylight::Daylight,
+    dither::{self, ColorDepth},
+    drop::{Direction, Layer},
+    demo::Demo,
+    effect::{self, Effect, Head, Weather},
+    fps::FpsMeter,
+    frame::Frame,
+    game::{self, Game},
+    glitch::Glitch,
+    keymap::Action,
+    hollywood::Hollywood,
+    kiosk::{self, Kiosk},
+    lightning::Lightning,
+    night,
+    overlay::{self, Help, Notice, Osd, Overlay, StatusBar, TooSmall},
+    platform::{is_interrupt, Color, Instant, KeyCode, KeyEvent, KeyModifiers},
+    qr::Qr,
+    render::{glow, spotlight, Glyph, Style},
+    rotate::Rotation,
+    screen::Screen,
+    schedule::Schedule,
+    script::Timeline,
+    settings::{CursorEffect, Settings, Tuning},
+    storm::Storm,
+    stuck::Stuck,
+    watermark::Watermark,
+};
+
+/// Why the rain stopped, which decides the code the program exits with.
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Exit {
+    Key(KeyEvent), // One of the exit keys, or any key with --exit-on-any-key
+    Mouse,         // The mouse moved, with --exit-on-mouse
+    Timeout,       // --timeout ran out
+    Terminated,    // SIGTERM
+    Finished(i32), // The --while command exited with this code
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Exit {
+    /// The code for `run` failing once the terminal's been taken over, say
+    /// when it went away, as told apart from errors setting up.
+    pub const IO_ERROR: i32 = 74;
+
+    pub fn code(self) -> i32 {
+        match self {
+            Exit::Key(_) => 0,
+            Exit::Mouse => 3,
+            Exit::Timeout => 124,
+            Exit::Terminated => 143,
+            Exit::Finished(code) => code,
+        }
+    }
+
+    /// The name of the key that ended the rain, e.g. `q` or `Ctrl+C`.
+    pub fn key_name(self) -> Option<String> {
+        let Exit::Key(key) = self else {
+            return None;
+        };
+        let name = match key.code {
+            KeyCode::Char(' ') => "Space".to_string(),
+            KeyCode::Char(ch) if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                ch.to_ascii_uppercase().to_string()
+            }
+            KeyCode::Char(ch) => ch.to_string(),
+            code => format!("{:?}", code),
+        };
+        let mut prefix = String::new();
+        if key.modifiers.contains(KeyModifiers::CONTROL) {
+            prefix += "Ctrl+";
+        }
+        if key.modifiers.contains(KeyModifiers::ALT) {
+            prefix += "Alt+";
+        }
+        Some(prefix + &name)
+    }
+}
+
+/// Slowest frame time in `--low-power` mode, 10 fps.
+#[cfg(not(target_arch = "wasm32"))]
+const LOW_POWER_DELAY: Duration = Duration::from_millis(100);
+
+/// Columns around the mouse pointer `--cursor-effect spotlight` lights up.
+const SPOTLIGHT_RADIUS: f32 = 8.0;
+
+/// Overlays that stay up all run, the FPS counter, debug line and status
+/// bar, step a cell around this small square of offsets...
+const SHIFTS: [(u16, u16); 4] = [(0, 0), (1, 0), (1, 1), (0, 1)];
+/// ...one step this often, so they never burn into the screen.
+const SHIFT_EVERY: Duration = Duration::from_secs(120);
+
+/// Smallest terminal the rain is drawn in; anything smaller just says so.
+const MIN_COLS: u16 = 10;
+const MIN_ROWS: u16 = 3;
+
+/// Drops a mouse click starts; dragging starts one per cell.
+pub(crate) const MOUSE_BURST: usize = 6;
+
+/// How often a hidden screen wakes to check on --timeout, --while and
+/// SIGTERM.
+#[cfg(not(target_arch = "wasm32"))]
+const HIDDEN_WAKE: Duration = Duration::from_millis(250);
+
+/// Slowest frame time while running on battery, 15 fps.
+#[cfg(all(feature = "battery", not(target_arch = "wasm32")))]
+const BATTERY_DELAY: Duration = Duration::from_millis(66);
+
+/// Bullet time slows the rain to this fraction of normal speed...
+const BULLET_SPEED: f32 = 0.2;
+/// ...easing in and out over this many seconds...
+const BULLET_EASE: f32 = 0.6;
+/// ...and holding for this many in between.
+const BULLET_HOLD: f32 = 3.0;
+
+/// The slowest `--ramp` runs the clock, right at the start and the end.
+const RAMP_FLOOR: f32 = 0.05;
+
+/// How long the rain takes to fade from one color scheme into the next.
+const CROSSFADE: Duration = Duration::from_secs(1);
+
+/// Chance each cell along the edge the rain comes in from starts a scheduled
+/// burst...
+const BURST_CHANCE: f64 = 0.6;
+/// ...and how many drops each of those starts.
+const BURST_DROPS: usize = 3;
+
+/// How long the rain takes to materialize at startup...
+const APPEAR: Duration = Duration::from_secs(1);
+/// ...and to dissolve to black on the way out.
+const DISSOLVE: Duration = Duration::from_millis(600);
+
+/// With `--transparent`, glyphs dimmer than this are cleared rather than
+/// drawn nearly black.
+const TRANSPARENT_CUTOFF: f32 = 0.12;
+
+/// A half-typed `--lock` passphrase is forgotten after this long without a key.
+const LOCK_IDLE: Duration = Duration::from_secs(5);
+
+/// Frame rates the Up/Down keys step through.
+const FPS_STEPS: [f64; 13] = [
+    5.0, 10.0, 15.0, 20.0, 24.0, 30.0, 40.0, 50.0, 60.0, 75.0, 90.0, 120.0, 144.0,
+];
+
+/// Trail persistence the `(`/`)` keys step through, from instant clear to
+/// long after-images.
+const PERSISTENCE_STEPS: [f32; 8] = [0.0, 0.5, 0.7, 0.8, 0.85, 0.9, 0.94, 0.97];
+
+/// The whole screensaver: an effect, the overlays on top of it, and the
+/// terminal it draws to.
+pub struct Matrix {
+    effect: Box<dyn Effect>,
+    width: u16,  // Simulation grid size, which is finer than the terminal
+    height: u16, // in the sub-cell render modes
+    cols: u16,   // Terminal size
+    rows: u16,
+    settings: Settings,
+    tuning: Tuning, // What `r` resets the settings to
+    presets: Vec<Preset>,
+    preset: Option<usize>, // Last preset picked with Tab
+    storm: Option<Storm>,
+    intensity: f64,
+    gust: f32,
+    backdrop: Option<Backdrop>,
+    glitch: Option<Glitch>,
+    lightning: Option<Lightning>,
+    grey: f32, // How far `--weather` has washed the colors out toward grey
+    stuck: Option<Stuck>,
+    qr: Option<Qr>,
+    timeline: Option<Timeline>,
+    schedule: Option<Schedule>,
+    daylight: Option<Daylight>,
+    demo: Option<Demo>,
+    rotation: Option<Rotation>,
+    kiosk: Option<Kiosk>,
+    countdown: Option<Countdown>,
+    alarm: bool, // The countdown just got to zero, for `run` to raise --alert
+    watermark: Option<Watermark>,
+    hollywood: Option<Hollywood>,
+    overlays: Vec<Box<dyn Overlay>>, // Added with `add_overlay`
+    layers: Compositor, // The rain, back and front
+    heads: Vec<Head>,   // The effect's heads as of the last tick, reused each time
+    painted: ColorScheme, // The scheme the rain was last painted in, to see it change
+    fading: Option<(ColorScheme, u64)>, // The scheme being faded from, and since which tick
+    appearing: Option<Instant>,  // When the rain began fading in at startup
+    dissolving: Option<Instant>, // When it began dissolving on the way out
+    screen: Screen,
+    #[cfg(feature = "clipboard")]
+    clipboard: Clipboard,
+    notice: Option<(String, Instant)>, // Confirmation shown along the bottom until then
+    // Every random choice comes from here, so --seed can replay a run. SmallRng's
+    // sequence for a seed depends on the word size, so only on the same platform
+    rng: SmallRng,
+    ticks: u64, // Frames simulated, since the start or the last `restart`
+    fps: FpsMeter,
+    started: Instant, // For the time running on the status bar
+    osd: Option<(String, u64)>, // Value a key just changed, and at which frame
+    frames: u64, // Frames stepped, paused or not, to time the key notes by
+    help: bool, // Showing the `?` overlay, with the animation held still
+    bullet: Option<f32>, // Seconds into bullet time, if it's running
+    owed: f64, // Simulation steps due but not yet run, with --sim-rate
+    ends: Option<Instant>, // When --timeout will end the run, for --ramp to wind down toward
+    paused: bool, // Frozen with `p`: still drawn, but not updated
+    typed: Option<(String, Instant)>, // What's been typed of the --lock passphrase, and when
+    pointer: Option<(u16, u16)>, // Terminal cell the mouse was last seen over
+    recent: String, // The last few letters typed, to spot the game's trigger
+    game: Option<Game>,
+    #[cfg(not(target_arch = "wasm32"))]
+    hidden: bool, // The terminal lost focus, so nothing is updated or drawn
+    #[cfg(not(target_arch = "wasm32"))]
+    on_screen: bool, // `run` has taken the terminal over, so errors now are its
+    #[cfg(unix)]
+    stop: Option<Arc<AtomicBool>>, // Set from elsewhere when `run` should end
+    #[cfg(all(feature = "sound", not(target_arch = "wasm32")))]
+    sound: Option<Sound>, // What --sound plays, once `run` has started it
+}
+
+impl Matrix {
+    /// A screensaver filling the terminal.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn new(settings: Settings) -> Self {
+        let (cols, rows) = terminal_size(&settings).unwrap_or((80, 24));
+        Self::with_size(settings, cols, rows)
+    }
+
+    /// A rain `cols` x `rows` cells big, for drawing somewhere other than the
+    /// whole terminal.
+    pub fn with_size(mut settings: Settings, cols: u16, rows: u16) -> Self {
+        if settings.vim {
+            settings.keymap.vim();
+        }
+        let rng = match settings.seed {
+            Some(seed) => SmallRng::seed_from_u64(seed),
+            None => SmallRng::from_entropy(),
+        };
+        let mut matrix = Matrix {
+            effect: effect::build(&settings),
+            width: 0,
+            height: 0,
+            cols: 0,
+            rows: 0,
+            storm: settings.storm_period.map(Storm::new),
+            backdrop: None,
+            glitch: (settings.glitch > 0.0).then(|| Glitch::new(settings.glitch)),
+            lightning: (settings.lightning > 0.0).then(|| Lightning::new(settings.lightning)),
+            grey: 0.0,
+            stuck: (settings.stuck_glyphs > 0.0).then(|| Stuck::new(settings.stuck_glyphs)),
+            qr: settings.qr.as_deref().and_then(Qr::new),
+            timeline: settings.script.take(),
+            schedule: settings.schedule.take(),
+            daylight: settings.time_of_day.take(),
+            demo: settings.demo.map(|secs| Demo::new(secs, &settings)),
+            rotation: settings.rotate.map(Rotation::new),
+            kiosk: settings.kiosk.take(),
+            countdown: settings.countdown.take(),
+            alarm: false,
+            watermark: settings.watermark.as_deref().map(Watermark::new),
+            hollywood: settings.hollywood.then(Hollywood::new),
+            overlays: vec![],
+            tuning: settings.tuning(),
+            presets: cli::all_presets(&settings.presets),
+            preset: None,
+            painted: settings.color_scheme,
+            fading: None,
+            appearing: None,
+            dissolving: None,
+            settings,
+            intensity: 1.0,
+            gust: 0.0,
+            layers: Compositor::new(0, 0),
+            heads: vec![],
+            screen: Screen::new(0, 0),
+            #[cfg(feature = "clipboard")]
+            clipboard: Clipboard::default(),
+            notice: None,
+            rng,
+            ticks: 0,
+            fps: FpsMeter::default(),
+            started: Instant::now(),
+            osd: None,
+            frames: 0,
+            help: false,
+            bullet: None,
+            owed: 0.0,
+            ends: None,
+            paused: false,
+            typed: None,
+            pointer: None,
+            recent: String::new(),
+            game: None,
+            #[cfg(not(target_arch = "wasm32"))]
+            hidden: false,
+            #[cfg(not(target_arch = "wasm32"))]
+            on_screen: false,
+            #[cfg(unix)]
+            stop: None,
+            #[cfg(all(feature = "sound", not(target_arch = "wasm32")))]
+            sound: None,
+        };
+        matrix.resize(cols, rows);
+        matrix
+    }
+
+    /// Keep `overlay` drawn over the rain from now on.
+    pub fn add_overlay(&mut self, overlay: Box<dyn Overlay>) {
+        self.overlays.push(overlay);
+    }
+
+    /// Size in terminal cells.
+    pub fn size(&self) -> (u16, u16) {
+        (self.cols, self.rows)
+    }
+
+    pub fn resize(&mut self, cols: u16, rows: u16) {
+        let (sx, sy) = self.settings.render_mode.scale();
+        self.cols = cols;
+        self.rows = rows;
+        let rows = self.rain_rows();
+        self.width = cols.saturating_mul(sx);
+        self.height = rows.saturating_mul(sy);
+        self.layers.resize(self.width, self.height);
+        self.screen.resize(cols, self.rows);
+        if self.settings.backdrop || self.settings.inverse {
+            self.backdrop = Some(Backdrop::new(cols, rows, &mut self.rng));
+        }
+        if let Some(qr) = self.qr.as_mut() {
+            qr.place(cols, rows);
+        }
+        self.effect.resize(self.width, self.height);
+        self.effect.heads(&mut self.heads);
+        #[cfg(feature = "image")]
+        if let Some(picture) = self.settings.picture.as_mut() {
+            picture.fit(cols, rows);
+        }
+    }
+
+    /// Rows the rain falls through: all of them, less one for the status bar.
+    fn rain_rows(&self) -> u16 {
+        if self.settings.status_bar {
+            self.rows.saturating_sub(1)
+        } else {
+            self.rows
+        }
+    }
+
+    /// Columns per row the wind pushes drops right now, gusts included.
+    fn wind(&self) -> f32 {
+        self.settings.wind + self.gust
+    }
+
+    fn update_wind(&mut self) {
+        let share = self.tick_share();
+        self.gust *= 0.97f32.powf(share);
+        if self.settings.wind != 0.0 && self.rng.gen_bool(0.005 * share as f64) {
+            self.gust = self.settings.wind * self.rng.gen_range(0.5..1.5);
+        }
+    }
+
+    /// How fast the effect's clock runs this frame. Bullet time eases down
+    /// to a fifth of normal speed, holds, and eases back, all in simulated
+    /// time so it pauses along with the animation.
+    fn time_scale(&mut self) -> f32 {
+        let Some(elapsed) = self.bullet else {
+            return 1.0;
+        };
+        let elapsed = elapsed + self.tick_delay().as_secs_f32();
+        let (ease, hold, total) = (BULLET_EASE, BULLET_HOLD, BULLET_EASE * 2.0 + BULLET_HOLD);
+        if elapsed >= total {
+            self.bullet = None;
+            return 1.0;
+        }
+        self.bullet = Some(elapsed);
+        let depth = if elapsed < ease {
+            elapsed / ease
+        } else if elapsed < ease + hold {
+            1.0
+        } else {
+            (total - elapsed) / ease
+        };
+        // Smoothstep, so speed changes without a jolt at either end
+        let depth = depth * depth * (3.0 - 2.0 * depth);
+        1.0 - (1.0 - BULLET_SPEED) * depth
+    }
+
+    /// How far up to speed `--ramp` has the effect's clock: building from
+    /// a crawl over the first seconds simulated, and back down over the
+    /// last before the timeout. Spawning follows the clock, so it's sparse
+    /// to begin with too.
+    fn ramp(&self) -> f32 {
+        let Some(ramp) = self.settings.ramp else {
+            return 1.0;
+        };
+        let since = self.ticks as f32 * self.tick_delay().as_secs_f32();
+        let until = self
+            .ends
+            .map_or(f32::INFINITY, |ends| ends.saturating_duration_since(Instant::now()).as_secs_f32());
+        let level = (since.min(until) / ramp.as_secs_f32()).clamp(0.0, 1.0);
+        // Smoothstep, and never quite standing still
+        let level = level * level * (3.0 - 2.0 * level);
+        RAMP_FLOOR + (1.0 - RAMP_FLOOR) * level
+    }
+
+    /// Advance the simulation by one frame and light up the buffer, unless
+    /// paused, in which case the buffer is left as it is.
+    pub fn step(&mut self) {
+        self.fps.begin();
+        self.frames += 1;
+        if !self.paused && !self.help {
+            // With --sim-rate, however many ticks fit in a frame, carrying
+            // the odd part over to the next
+            self.owed += self.frame_delay().as_secs_f64() / self.tick_delay().as_secs_f64();
+            while self.owed > 1.0 - 1e-9 {
+                self.owed -= 1.0;
+                self.tick();
+            }
+        }
+    }
+
+    /// Simulated time a tick covers: a frame's, or less with `--sim-rate`.
+    fn tick_delay(&self) -> Duration {
+        match self.settings.sim_rate {
+            Some(hz) => Duration::from_secs_f64(1.0 / hz).min(self.frame_delay()),
+            None => self.frame_delay(),
+        }
+    }
+
+    /// Share of a frame a tick covers, which is all of it without `--sim-rate`.
+    fn tick_share(&self) -> f32 {
+        (self.tick_delay().as_secs_f64() / self.frame_delay().as_secs_f64().max(f64::EPSILON)) as f32
+    }
+
+    fn tick(&mut self) {
+        self.ticks += 1;
+        if let Some(timeline) = self.timeline.as_mut() {
+            timeline.tick(&mut self.settings);
+        }
+        if self.schedule.as_mut().is_some_and(|schedule| schedule.tick(&mut self.settings)) {
+            self.burst();
+        }
+        if let Some(daylight) = self.daylight.as_mut() {
+            daylight.tick(&mut self.settings);
+        }
+        if let Some(kiosk) = self.kiosk.as_mut() {
+            kiosk.tick();
+        }
+        if let Some(countdown) = self.countdown.as_mut() {
+            self.alarm |= countdown.just_over();
+            if countdown.tick() {
+                self.lightning.get_or_insert_with(|| Lightning::new(0.0)).strike();
+            }
+        }
+        let rows = self.rain_rows();
+        if let Some(hollywood) = self.hollywood.as_mut() {
+            hollywood.tick(self.cols, rows, &mut self.rng);
+        }
+        if let Some(demo) = self.demo.as_mut() {
+            if demo.tick(&mut self.settings, &mut self.rng) {
+                self.effect = effect::build(&self.settings);
+                self.resize(self.cols, self.rows);
+            }
+        }
+        if let Some(rotation) = self.rotation.as_mut() {
+            if rotation.tick(&mut self.settings) {
+                self.effect = effect::build(&self.settings);
+                self.resize(self.cols, self.rows);
+            }
+        }
+        #[cfg(feature = "lua")]
+        if let Some(lua) = self.settings.lua.take() {
+            lua.on_frame(&mut self.settings);
+            self.settings.lua = Some(lua);
+        }
+        if let Some(storm) = self.storm.as_mut() {
+            self.intensity = storm.intensity(&mut self.rng);
+        }
+
+        self.update_wind();
+        let (sx, sy) = self.settings.render_mode.scale();
+        let avoid = match self.settings.cursor_effect {
+            Some(CursorEffect::Avoid) => self.pointer.map(|(x, y)| (x * sx, y * sy)),
+            _ => None,
+        };
+        let weather = Weather {
+            wind: self.wind(),
+            intensity: self.intensity,
+            time: self.time_scale() * self.ramp() * self.tick_share(),
+            avoid,
+        };
+        self.effect.update(&self.settings, &weather, &mut self.rng);
+        self.effect.heads(&mut self.heads);
+        #[cfg(all(feature = "sound", not(target_arch = "wasm32")))]
+        if let Some(sound) = self.sound.as_mut() {
+            sound.hear(&self.heads, self.settings.direction, self.width, self.height);
+        }
+
+        if let Some(qr) = self.qr.as_mut() {
+            let (sx, sy) = self.settings.render_mode.scale();
+            for head in &self.heads {
+                qr.lock(head.x / sx, head.y / sy);
+            }
+        }
+
+        if let Some(backdrop) = self.backdrop.as_mut() {
+            backdrop.twinkle(&mut self.rng);
+        }
+        let frame_ms = self.tick_delay().as_millis() as u64;
+        let rows = self.rain_rows();
+        if let Some(glitch) = self.glitch.as_mut() {
+            glitch.tick(rows, frame_ms, &mut self.rng);
+        }
+        if let Some(lightning) = self.lightning.as_mut() {
+            lightning.tick(
+                self.cols,
+                frame_ms,
+                self.intensity,
+                &mut self.rng,
+            );
+        }
+
+        if let Some(game) = self.game.as_mut() {
+            game.tick(self.cols, rows, frame_ms as f32 / 1000.0, &mut self.rng);
+        }
+
+        // Fade into a new color scheme however it came, rather than snapping
+        if self.settings.color_scheme != self.painted {
+            self.fading = Some((self.painted, self.ticks));
+            self.painted = self.settings.color_scheme;
+        }
+        // In simulated time, like bullet time, so it holds while paused and
+        // a seeded run fades the same however fast it's drawn
+        self.settings.crossfade = self.fading.and_then(|(from, since)| {
+            let faded = self.ticks.saturating_sub(since) as f32 * self.tick_delay().as_secs_f32();
+            let amount = faded / CROSSFADE.as_secs_f32();
+            (amount < 1.0).then_some((from, amount))
+        });
+        if self.settings.crossfade.is_none() {
+            self.fading = None;
+        }
+
+        self.layers.decay(self.settings.persistence.powf(self.tick_share()));
+        self.effect.render_back(self.layers.layer_mut(Layer::Back), &self.settings);
+        self.effect.render(self.layers.layer_mut(Layer::Front), &self.settings);
+        if let Some(stuck) = self.stuck.as_mut() {
+            let scheme = self.settings.color_scheme;
+            stuck.tick(
+                &self.heads,
+                &self.layers.flatten(),
+                scheme,
+                frame_ms,
+                &mut self.rng,
+            );
+            stuck.stamp(self.layers.layer_mut(Layer::Front));
+        }
+    }
+
+    /// Simulate until the slowest, longest drops could have crossed the
+    /// screen, so the next frame looks like the rain has been running a while.
+    pub fn warm_up(&mut self) {
+        for _ in 0..self.warm_up_frames() {
+            self.step();
+        }
+    }
+
+    fn warm_up_frames(&self) -> usize {
+        let (travel, _) = self.settings.direction.extents(self.width, self.height);
+        (travel as usize + self.settings.max_length) * self.settings.max_speed as usize
+    }
+
+    /// Start the rain over from `seed`, warmed up, so that everything
+    /// started over from the same seed at the same size keeps in step.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn restart(&mut self, seed: u64) {
+        self.rng = SmallRng::seed_from_u64(seed);
+        self.effect = effect::build(&self.settings);
+        self.layers = Compositor::new(0, 0);
+        self.resize(self.cols, self.rows);
+        let settings = &self.settings;
+        self.glitch = (settings.glitch > 0.0).then(|| Glitch::new(settings.glitch));
+        self.lightning = (settings.lightning > 0.0).then(|| Lightning::new(settings.lightning));
+        self.stuck = (settings.stuck_glyphs > 0.0).then(|| Stuck::new(settings.stuck_glyphs));
+        self.intensity = 1.0;
+        self.gust = 0.0;
+        self.bullet = None;
+        // Even while paused, which a client might be
+        for _ in 0..self.warm_up_frames() {
+            self.tick();
+        }
+        self.ticks = 0;
+    }
+
+    /// Advance by one frame and return how the screen should look, without
+    /// touching the terminal.
+    pub fn render_frame(&mut self) -> Frame {
+        self.step();
+        Frame::new(self.cols, self.rows, self.frame())
+    }
+
+    /// The screen as it should look right now, row-major.
+    pub(crate) fn frame(&self) -> Vec<Glyph> {
+        let scheme = self.settings.color_scheme;
+        let background = |x, y| match &self.backdrop {
+            Some(backdrop) => {
+                let (sx, sy) = self.settings.render_mode.scale();
+                let scheme = self.settings.scheme_at(x * sx, y * sy, self.width, self.height);
+                let sweep = self.settings.rainbow_axis.at(y * sy);
+                let (ch, color) = backdrop.glyph(x, y, scheme, sweep);
+                Glyph::new(ch, color)
+            }
+            None => Glyph::BLANK,
+        };
+        let mode = self.settings.render_mode;
+        let mut frame = match self.backdrop.as_ref().filter(|_| self.settings.inverse) {
+            // The same field, but lit, at the rain's resolution
+            Some(backdrop) => self.layers.compose_inverse(mode, self.cols, self.rain_rows(), |x, y| {
+                let (sx, sy) = mode.scale();
+                let scheme = self.settings.scheme_at(x, y, self.width, self.height);
+                let sweep = self.settings.rainbow_axis.at(y);
+                (backdrop.char_at(x / sx, y / sy), scheme.get_colors(5, 10, x, sweep))
+            }),
+            None => self.layers.compose(mode, self.cols, self.rain_rows(), background),
+        };
+        if self.settings.head_style != Style::Plain {
+            let (sx, sy) = mode.scale();
+            let (width, height) = (self.width, self.height);
+            for head in &self.heads {
+                let twin = self
+                    .settings
+                    .mirror
+                    .map(|mirror| mirror.reflect(head.x, head.y, width, height));
+                for (x, y) in std::iter::once((head.x, head.y)).chain(twin) {
+                    let idx = (y / sy) as usize * self.cols as usize + (x / sx) as usize;
+                    if let Some(glyph) = frame.get_mut(idx).filter(|glyph| glyph.ch != ' ') {
+                        glyph.style = self.settings.head_style;
+                    }
+                }
+            }
+        }
+        if self.settings.glow && !self.settings.transparent {
+            // Heads come front layer first: the first bloom to reach a cell
+            // keeps it
+            let (sx, sy) = self.settings.render_mode.scale();
+            let (width, height) = (self.width, self.height);
+            for head in &self.heads {
+                let twin = self
+                    .settings
+                    .mirror
+                    .map(|mirror| mirror.reflect(head.x, head.y, width, height));
+                for (x, y) in std::iter::once((head.x, head.y)).chain(twin) {
+                    let scheme = self.settings.scheme_at(x, y, width, height);
+                    let color = scheme.get_colors(0, 1, x / sx, self.settings.rainbow_axis.at(y));
+                    let color = dim(color, head.layer.brightness());
+                    glow(&mut frame, self.cols, self.rain_rows(), x / sx, y / sy, color);
+                }
+            }
+        }
+        if self.settings.cursor_effect == Some(CursorEffect::Spotlight) {
+            if let Some((x, y)) = self.pointer {
+                spotlight(&mut frame, self.cols, self.rain_rows(), x, y, SPOTLIGHT_RADIUS);
+            }
+        }
+        #[cfg(feature = "image")]
+        if let Some(picture) = &self.settings.picture {
+            picture.tint(&mut frame);
+        }
+        if let Some(qr) = &self.qr {
+            for (idx, glyph) in frame.iter_mut().enumerate() {
+                let (x, y) = ((idx % self.cols as usize) as u16, (idx / self.cols as usize) as u16);
+                if let Some(code) = qr.glyph(x, y, scheme) {
+                    *glyph = code;
+                }
+            }
+        }
+        if let Some(lightning) = &self.lightning {
+            lightning.apply(&mut frame, self.cols);
+        }
+        if let Some(glitch) = &self.glitch {
+            glitch.apply(&mut frame, self.cols, scheme);
+        }
+        if let Some(game) = &self.game {
+            game.apply(&mut frame, self.cols, self.rain_rows(), scheme);
+        }
+        if let Some(fade) = self.demo.as_ref().map(Demo::fade).filter(|&fade| fade < 1.0) {
+            for glyph in frame.iter_mut() {
+                glyph.fg = dim(glyph.fg, fade);
+                glyph.bg = dim(glyph.bg, fade);
+            }
+        }
+        // The old effect cleared away above a bright line on its way down
+        if let Some(wipe) = self.rotation.as_ref().and_then(Rotation::wipe) {
+            let line = (wipe * self.rain_rows() as f32) as usize;
+            let cols = self.cols as usize;
+            for (y, row) in frame.chunks_mut(cols).enumerate().take(line + 1) {
+                for (x, glyph) in row.iter_mut().enumerate() {
+                    *glyph = if y == line {
+                        let sweep = self.settings.rainbow_axis.at(y as u16);
+                        Glyph::new('━', scheme.get_colors(0, 10, x as u16, sweep))
+                    } else {
+                        Glyph::BLANK
+                    };
+                }
+            }
+        }
+        // Holes go last so nothing, not even a glitch, bleeds into them
+        for hole in &self.settings.holes {
+            for (idx, glyph) in frame.iter_mut().enumerate() {
+                let (x, y) = ((idx % self.cols as usize) as u16, (idx / self.cols as usize) as u16);
+                if hole.contains(x, y) {
+                    *glyph = Glyph::BLANK;
+                }
+            }
+        }
+        // The status bar's row, which the rain doesn't reach
+        frame.resize(self.cols as usize * self.rows as usize, Glyph::BLANK);
+        if self.grey > 0.0 {
+            for glyph in frame.iter_mut() {
+                glyph.fg = desaturate(glyph.fg, self.grey);
+            }
+        }
+        self.compose(&mut frame);
+        if let Some(level) = self.transition() {
+            for (idx, glyph) in frame.iter_mut().enumerate() {
+                let (x, y) = ((idx % self.cols as usize) as u16, (idx / self.cols as usize) as u16);
+                // Every cell in its own time, rather than the screen as one
+                let level = (level * 1.5 - kiosk::noise(x, y, 0) * 0.5).clamp(0.0, 1.0);
+                *glyph = match level {
+                    0.0 => Glyph::BLANK,
+                    _ => Glyph {
+                        fg: dim(glyph.fg, level),
+                        bg: dim(glyph.bg, level),
+                        ..*glyph
+                    },
+                };
+            }
+        }
+        if self.settings.night.is_some_and(|night| night.now()) {
+            night::apply(&mut frame, self.settings.night_warm);
+        }
+        if self.settings.brightness < 1.0 {
+            for glyph in frame.iter_mut() {
+                glyph.fg = gamma_dim(glyph.fg, self.settings.brightness);
+                glyph.bg = gamma_dim(glyph.bg, self.settings.brightness);
+            }
+        }
+        if self.settings.oled {
+            burnin::apply(&mut frame, self.cols, self.started.elapsed());
+        }
+        // On a background other than black, near-black glyphs would show
+        // as dark smudges, so they give way to the terminal's own
+        if self.settings.transparent {
+            for glyph in frame.iter_mut() {
+                if lightness(glyph.bg) < TRANSPARENT_CUTOFF {
+                    glyph.bg = Color::Reset;
+                }
+                if glyph.bg == Color::Reset && lightness(glyph.fg) < TRANSPARENT_CUTOFF {
+                    *glyph = Glyph::BLANK;
+                }
+            }
+        }
+        if self.settings.linux_console {
+            dither::apply(&mut frame, self.cols, ColorDepth::Ansi16);
+            console::apply(&mut frame);
+        } else {
+            dither::apply(&mut frame, self.cols, self.settings.colors);
+        }
+        frame
+    }
+
+    /// How far into view the screen is, 0.0 to 1.0, while it's fading in at
+    /// startup or dissolving on the way out; None the rest of the time.
+    fn transition(&self) -> Option<f32> {
+        if let Some(since) = self.dissolving {
+            return Some(1.0 - (since.elapsed().as_secs_f32() / DISSOLVE.as_secs_f32()).min(1.0));
+        }
+        let level = self.appearing?.elapsed().as_secs_f32() / APPEAR.as_secs_f32();
+        (level < 1.0).then_some(level)
+    }
+
+    /// Let everything on screen decay to black, the rain still falling, before
+    /// the terminal's put back.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn dissolve(
+        &mut self,
+        stdout: &mut impl Write,
+        out: &mut Vec<u8>,
+        recorder: &mut Option<Recorder>,
+    ) -> std::io::Result<()> {
+        self.dissolving = Some(Instant::now());
+        out.clear();
+        loop {
+            self.step();
+            self.paint(out)?;
+            stdout.write_all(out)?;
+            stdout.flush()?;
+            if let Some(recorder) = recorder {
+                recorder.output(out)?;
+            }
+            out.clear();
+            if self.transition() == Some(0.0) {
+                return Ok(());
+            }
+            std::thread::sleep(self.frame_delay().min(Duration::from_millis(30)));
+        }
+    }
+
+    /// What the terminal's title says while the rain runs.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn title(&self) -> String {
+        format!(
+            "matrix-rain — {} — {:.0}fps",
+            self.settings.color_scheme.name(),
+            self.fps_target()
+        )
+    }
+
+    /// How far the overlays that stay up are nudged in from their corner
+    /// right now.
+    fn shift(&self) -> (u16, u16) {
+        let step = self.started.elapsed().as_secs() / SHIFT_EVERY.as_secs();
+        let (dx, dy) = SHIFTS[step as usize % SHIFTS.len()];
+        (dx.min(self.cols.saturating_sub(1)), dy.min(self.rows.saturating_sub(1)))
+    }
+
+    /// Put whichever overlays are showing over `frame`.
+    fn compose(&self, frame: &mut [Glyph]) {
+        let drops = self.effect.population();
+        let status = self.settings.status_bar.then(|| StatusBar {
+            scheme: self.settings.color_scheme.name(),
+            fps: self.fps.fps(),
+            density: self.settings.density,
+            drops,
+            running: self.started.elapsed(),
+            dx: self.shift().0,
+        });
+        let fps = self.settings.show_fps.then(|| overlay::Fps {
+            fps: self.fps.fps(),
+            work_ms: self.fps.work_ms(),
+            drops,
+            shift: self.shift(),
+        });
+        let debug = self.settings.show_debug.then(|| overlay::Debug {
+            drops,
+            max_drops: self.settings.max_drops,
+            storm: self.storm.as_ref().map(|_| self.intensity),
+            wind: self.wind(),
+            shift: self.shift(),
+        });
+        let message = self
+            .timeline
+            .as_ref()
+            .and_then(Timeline::message)
+            .or_else(|| self.schedule.as_ref().and_then(Schedule::message))
+            .map(overlay::Message);
+        let notice = self
+            .notice
+            .as_ref()
+            .filter(|(_, until)| Instant::now() < *until)
+            .map(|(text, _)| Notice(text));
+        let osd = self.osd.as_ref().map(|(text, shown)| Osd {
+            text,
+            age: self.frame_delay() * self.frames.saturating_sub(*shown) as u32,
+        });
+        let help = self.help.then(|| Help {
+            lines: self.help_lines(),
+        });
+
+        let mut overlays: Vec<&dyn Overlay> = vec![];
+        if let Some(kiosk) = &self.kiosk {
+            overlays.push(kiosk);
+        }
+        if let Some(countdown) = &self.countdown {
+            overlays.push(countdown);
+        }
+        if let Some(watermark) = &self.watermark {
+            overlays.push(watermark);
+        }
+        if let Some(hollywood) = &self.hollywood {
+            overlays.push(hollywood);
+        }
+        overlays.extend(status.as_ref().map(|overlay| overlay as &dyn Overlay));
+        overlays.extend(fps.as_ref().map(|overlay| overlay as &dyn Overlay));
+        overlays.extend(debug.as_ref().map(|overlay| overlay as &dyn Overlay));
+        overlays.extend(message.as_ref().map(|overlay| overlay as &dyn Overlay));
+        overlays.extend(notice.as_ref().map(|overlay| overlay as &dyn Overlay));
+        overlays.extend(osd.as_ref().map(|overlay| overlay as &dyn Overlay));
+        overlays.extend(help.as_ref().map(|overlay| overlay as &dyn Overlay));
+        overlays.extend(self.overlays.iter().map(Box::as_ref));
+        let scheme = self.settings.color_scheme;
+        overlay::compose(frame, self.cols, self.rows, scheme, &mut overlays);
+    }
+
+    /// What the `?` box says: the keys, and what the settings they change
+    /// are at.
+    fn help_lines(&self) -> Vec<String> {
+        let s = &self.settings;
+        let mut lines = vec!["Keys".to_string()];
+        for (keys, action) in cli::controls(&self.settings.keymap) {
+            lines.push(format!("  {:<11} {}", keys, action));
+        }
+        lines.push(String::new());
+        lines.push("Settings".to_string());
+        lines.push(format!(
+            "  {:.0} fps, density {:.0}%, length {}, {} colors, wind {:+.1}",
+            self.fps_target(),
+            s.density * 100.0,
+            s.max_length,
+            s.color_scheme.name(),
+            s.wind
+        ));
+        lines.push(String::new());
+        lines.push("Press any key to close".to_string());
+        lines
+    }
+
+    /// Frames per second the rain is paced at.
+    pub fn fps_target(&self) -> f64 {
+        1.0 / self.frame_delay().as_secs_f64().max(f64::EPSILON)
+    }
+
+    /// Step the frame rate up or down, for Up/Down and the mouse wheel.
+    fn speed_up(&mut self, up: bool) {
+        // Allow for rounding in the delay when looking for the next step
+        let target = self.fps_target();
+        if up {
+            self.set_fps(FPS_STEPS.iter().find(|&&fps| fps > target + 0.5));
+        } else {
+            self.set_fps(FPS_STEPS.iter().rev().find(|&&fps| fps < target - 0.5));
+        }
+    }
+
+    /// Step how long trails linger after a drop passes, for `(`/`)`.
+    fn ghost(&mut self, up: bool) {
+        let now = self.settings.persistence;
+        let next = if up {
+            PERSISTENCE_STEPS.iter().find(|&&step| step > now + 0.005)
+        } else {
+            PERSISTENCE_STEPS.iter().rev().find(|&&step| step < now - 0.005)
+        };
+        if let Some(&persistence) = next {
+            self.settings.persistence = persistence;
+        }
+        self.show_osd(match self.settings.persistence {
+            0.0 => "Ghosting: off".to_string(),
+            persistence => format!("Ghosting: {:.0}%", persistence * 100.0),
+        });
+    }
+
+    /// Step the density up or down, for Right/Left and Shift+wheel.
+    fn thicken(&mut self, up: bool) {
+        if up {
+            self.settings.density = (self.settings.density + 0.05).min(1.0);
+            self.settings.spawns_per_frame = (self.settings.spawns_per_frame + 1).min(20);
+        } else {
+            self.settings.density = (self.settings.density - 0.05).max(0.05);
+            self.settings.spawns_per_frame =
+                self.settings.spawns_per_frame.saturating_sub(1).max(1);
+        }
+        self.show_osd(format!("Density: {:.0}%", self.settings.density * 100.0));
+    }
+
+    /// Switch to a built-in color scheme, which replaces a plugin's colors,
+    /// and name it.
+    fn set_scheme(&mut self, scheme: ColorScheme) {
+        #[cfg(feature = "plugins")]
+        {
+            self.settings.color_plugin = None;
+        }
+        self.settings.color_scheme = scheme;
+        self.show_osd(format!("Colors: {}", scheme.name()));
+    }
+
+    /// Switch to `fps`, if there is one, and say so.
+    fn set_fps(&mut self, fps: Option<&f64>) {
+        if let Some(&fps) = fps {
+            self.settings.frame_delay = Duration::from_secs_f64(1.0 / fps);
+        }
+        self.show_osd(format!("Speed: {:.0} fps", self.fps_target()));
+    }
+
+    /// Switch to the preset `offset` places on from the current one.
+    fn cycle_preset(&mut self, offset: usize) {
+        let count = self.presets.len();
+        let next = match self.preset {
+            Some(preset) => (preset + offset) % count,
+            None if offset == 1 => 0,
+            None => count - 1,
+        };
+        self.preset = Some(next);
+        let (name, options) = &self.presets[next];
+        // A config-file preset can be wrong; say so rather than exit mid-run
+        match cli::try_parse(options) {
+            Ok(settings) => {
+                let name = format!("Preset: {}", name);
+                self.settings.retune(&settings.tuning());
+                self.show_osd(name);
+            }
+            Err(err) => {
+                let text = format!("Preset {}: {}", name, err);
+                self.show_osd(text);
+            }
+        }
+    }
+
+    /// Show the value a key just changed in the bottom right corner, fading
+    /// out over a second's worth of frames, so a seeded run shows it the same.
+    fn show_osd(&mut self, text: String) {
+        self.osd = Some((text, self.frames));
+    }
+
+    /// Take `key` towards the `--lock` passphrase, false once it's been
+    /// typed and Enter pressed. Every other key is ignored.
+    fn unlock(&mut self, phrase: &str, key: KeyEvent) -> bool {
+        let mut typed = match self.typed.take() {
+            Some((typed, at)) if at.elapsed() < LOCK_IDLE => typed,
+            _ => String::new(),
+        };
+        match key.code {
+            KeyCode::Enter if typed == phrase => return false,
+            KeyCode::Enter => {
+                self.notify("Wrong passphrase".to_string());
+                return true;
+            }
+            KeyCode::Backspace => {
+                typed.pop();
+            }
+            KeyCode::Esc => typed.clear(),
+            KeyCode::Char(ch)
+                if !key.modifiers.contains(KeyModifiers::CONTROL)
+                    && !key.modifiers.contains(KeyModifiers::ALT) =>
+            {
+                typed.push(ch)
+            }
+            _ => {}
+        }
+        // Show how much has been typed, never what
+        self.notify(format!("Locked - passphrase: {}_", "*".repeat(typed.chars().count())));
+        self.typed = Some((typed, Instant::now()));
+        true
+    }
+
+    /// Play `--intro` before the rain, then have the rain crash in down
+    /// every other column. A quit key during it is the exit, any other key
+    /// skips to the rain.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn intro(
+        &mut self,
+        stdout: &mut impl Write,
+        out: &mut Vec<u8>,
+        recorder: &mut Option<Recorder>,
+    ) -> std::io::Result<Option<Exit>> {
+        let intro = Intro::new();
+        let listening = self.settings.reserve_bottom == 0;
+        loop {
+            if !listening {
+                std::thread::sleep(Duration::from_millis(20));
+            } else if poll(Duration::from_millis(20))? {
+                match read()? {
+                    Event::Key(key) if key.kind == KeyEventKind::Release => {}
+                    Event::Key(key) if is_interrupt(key) || self.settings.keymap.action(key) == Some(Action::Quit) => {
+                        return Ok(Some(Exit::Key(key)));
+                    }
+                    Event::Key(_) => break,
+                    _ => {}
+                }
+            }
+            if let Ok((w, h)) = terminal_size(&self.settings) {
+                if (w, h) != (self.cols, self.rows) {
+                    self.resize(w, h);
+                }
+            }
+            let Some(frame) = intro.frame(self.cols, self.rows, self.settings.color_scheme) else {
+                break;
+            };
+            self.show(out, &frame)?;
+            stdout.write_all(out)?;
+            stdout.flush()?;
+            if let Some(recorder) = recorder {
+                recorder.output(out)?;
+            }
+            out.clear();
+        }
+        for x in (0..self.width).step_by(2) {
+            self.effect.spawn_at(x, 0, 1, &self.settings, &mut self.rng);
+        }
+        Ok(None)
+    }
+
+    /// `--chooser`: every scheme raining in a tile of its own until one's
+    /// picked, then the rain in that one. Returns how the run ended if it
+    /// was quit instead.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn choose(
+        &mut self,
+        stdout: &mut impl Write,
+        out: &mut Vec<u8>,
+        recorder: &mut Option<Recorder>,
+    ) -> std::io::Result<Option<Exit>> {
+        let mut chooser = Chooser::new(&self.settings, self.cols, self.rows);
+        let mut pacer = Pacer::new();
+        loop {
+            while let Some(wait) = pacer.remaining() {
+                if !poll(wait)? {
+                    continue;
+                }
+                match read()? {
+                    Event::Key(key) if key.kind == KeyEventKind::Release => {}
+                    Event::Key(key) if is_interrupt(key) => return Ok(Some(Exit::Key(key))),
+                    Event::Key(key) => match key.code {
+                        KeyCode::Left | KeyCode::Char('h') => chooser.go(-1, 0),
+                        KeyCode::Right | KeyCode::Char('l') => chooser.go(1, 0),
+                        KeyCode::Up | KeyCode::Char('k') => chooser.go(0, -1),
+                        KeyCode::Down | KeyCode::Char('j') => chooser.go(0, 1),
+                        KeyCode::Enter | KeyCode::Char(' ') => {
+                            let scheme = chooser.scheme();
+                            self.settings.color_scheme = scheme;
+                            // What it starts with, so no fade, and what `r` goes back to
+                            self.painted = scheme;
+                            self.tuning = self.settings.tuning();
+                            return Ok(None);
+                        }
+                        KeyCode::Esc | KeyCode::Char('q') => return Ok(Some(Exit::Key(key))),
+                        _ => {}
+                    },
+                    _ => {}
+                }
+            }
+            pacer.due(self.frame_delay());
+            if let Ok((w, h)) = terminal_size(&self.settings) {
+                if (w, h) != (self.cols, self.rows) {
+                    self.resize(w, h);
+                    chooser.resize(w, h);
+                }
+            }
+            chooser.step();
+            self.show(out, &chooser.frame())?;
+            stdout.write_all(out)?;
+            stdout.flush()?;
+            if let Some(recorder) = recorder {
+                recorder.output(out)?;
+            }
+            out.clear();
+        }
+    }
+
+    /// Rain the way it is outside, as `--weather` found it.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn follow_weather(&mut self, conditions: &Conditions) {
+        conditions.apply(&mut self.settings);
+        self.grey = conditions.grey();
+        // Thunder brings lightning, and takes any it brought when it goes
+        if conditions.is_stormy() && !self.settings.reduced_motion {
+            self.lightning.get_or_insert_with(|| Lightning::new(4.0));
+        } else if self.settings.lightning == 0.0 {
+            self.lightning = None;
+        }
+        self.notify(format!(
+            "Weather: {}, {:.1} mm, wind {:.0} km/h",
+            conditions.description, conditions.rain_mm, conditions.wind_kmh
+        ));
+    }
+
+    /// Take on the config file as `--watch` just reloaded it, or say what's
+    /// wrong with it and carry on as before.
+    #[cfg(all(feature = "watch", not(target_arch = "wasm32")))]
+    fn follow_config(&mut self, reloaded: Result<Settings, String>) {
+        match reloaded {
+            Ok(fresh) => {
+                self.settings.restyle(fresh);
+                self.tuning = self.settings.tuning();
+                self.presets = cli::all_presets(&self.settings.presets);
+                self.preset = None;
+                self.notify("Config reloaded".to_string());
+            }
+            Err(err) => self.notify(format!("Config: {}", err)),
+        }
+    }
+
+    /// Show `text` along the bottom of the screen for a couple of seconds.
+    fn notify(&mut self, text: String) {
+        self.notice = Some((text, Instant::now() + Duration::from_secs(2)));
+    }
+
+    /// Save the screen to `~/matrix-<timestamp>.ans`, or `.txt` without
+    /// colors, and say where it went.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn screenshot(&mut self, ansi: bool) {
+        let frame = Frame::new(self.cols, self.rows, self.frame());
+        let (contents, ext) = if ansi {
+            (frame.to_ansi_string() + "\r\n", "ans")
+        } else {
+            (frame.to_plain_string(), "txt")
+        };
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |since| since.as_secs());
+        let dir = std::env::var_os("HOME").map_or_else(Default::default, std::path::PathBuf::from);
+        let path = dir.join(format!("matrix-{}.{}", timestamp, ext));
+        match std::fs::write(&path, contents) {
+            Ok(()) => self.notify(format!("Saved {}", path.display())),
+            Err(err) => self.notify(format!("Could not save screenshot: {}", err)),
+        }
+    }
+
+    /// Pass `key` to the typing game. Returns false for keys the game
+    /// leaves to the usual controls.
+    fn play(&mut self, key: KeyEvent) -> bool {
+        let plain = !key.modifiers.contains(KeyModifiers::CONTROL)
+            && !key.modifiers.contains(KeyModifiers::ALT);
+        let Some(game) = self.game.as_mut() else {
+            return false;
+        };
+        match key.code {
+            KeyCode::Esc => {
+                let score = game.score();
+                self.game = None;
+                self.notify(format!("Game over - score {}", score));
+            }
+            KeyCode::Backspace => game.backspace(),
+            KeyCode::Char(ch) if plain => game.type_char(ch),
+            // So a stray Enter or Space doesn't end the rain mid-game
+            KeyCode::Enter => {}
+            _ => return false,
+        }
+        true
+    }
+
+    /// Start a drop for `--reactive` from the edge the rain comes in from,
+    /// in a column picked by the key so each key has its own.
+    fn react(&mut self, key: KeyEvent) {
+        let direction = self.settings.direction;
+        let (travel, cross) = direction.extents(self.width, self.height);
+        if travel == 0 || cross == 0 {
+            return;
+        }
+        let mut hasher = DefaultHasher::new();
+        key.code.hash(&mut hasher);
+        let across = (hasher.finish() % cross as u64) as i32;
+        let along = if direction.step() > 0 { 0 } else { travel as i32 - 1 };
+        let (x, y) = direction.cell(along, across);
+        self.effect.spawn_at(x as u16, y as u16, 1, &self.settings, &mut self.rng);
+    }
+
+    /// Start `count` drops at terminal cell (`col`, `row`).
+    pub(crate) fn spawn_at(&mut self, col: u16, row: u16, count: usize) {
+        if col >= self.cols || row >= self.rain_rows() {
+            return;
+        }
+        let (sx, sy) = self.settings.render_mode.scale();
+        self.effect.spawn_at(col * sx, row * sy, count, &self.settings, &mut self.rng);
+    }
+
+    /// A wave of drops in from the whole of the edge the rain comes from,
+    /// for `--at ... burst`.
+    fn burst(&mut self) {
+        let (cols, rows) = (self.cols, self.rain_rows());
+        let edge: Vec<(u16, u16)> = match self.settings.direction {
+            Direction::Down => (0..cols).map(|x| (x, 0)).collect(),
+            Direction::Up => (0..cols).map(|x| (x, rows.saturating_sub(1))).collect(),
+            Direction::Right => (0..rows).map(|y| (0, y)).collect(),
+            Direction::Left => (0..rows).map(|y| (cols.saturating_sub(1), y)).collect(),
+        };
+        for (x, y) in edge {
+            if self.rng.gen_bool(BURST_CHANCE) {
+                self.spawn_at(x, y, BURST_DROPS);
+            }
+        }
+    }
+
+    /// React to a terminal event. Returns why to quit, if it's time to.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn handle_event(&mut self, event: Event) -> Option<Exit> {
+        if let Event::Mouse(mouse) = event {
+            self.pointer = Some((mouse.column, mouse.row));
+        }
+        match event {
+            Event::Key(key) if !self.handle_key(key) => return Some(Exit::Key(key)),
+            Event::Mouse(_) if self.settings.kiosk_lock => {}
+            // Like --grace for keys, so a nudge while launching doesn't count
+            Event::Mouse(_)
+                if self.settings.exit_on_mouse && self.started.elapsed() >= self.settings.grace =>
+            {
+                return Some(Exit::Mouse)
+            }
+            // A locked screen ignores the mouse as it does keys
+            Event::Mouse(mouse) if self.settings.mouse && self.settings.lock.is_none() => {
+                let shift = mouse.modifiers.contains(KeyModifiers::SHIFT);
+                match mouse.kind {
+                    MouseEventKind::Down(_) => self.spawn_at(mouse.column, mouse.row, MOUSE_BURST),
+                    MouseEventKind::Drag(_) => self.spawn_at(mouse.column, mouse.row, 1),
+                    MouseEventKind::ScrollUp if shift => self.thicken(true),
+                    MouseEventKind::ScrollDown if shift => self.thicken(false),
+                    MouseEventKind::ScrollUp => self.speed_up(true),
+                    MouseEventKind::ScrollDown => self.speed_up(false),
+                    _ => {}
+                }
+            }
+            Event::FocusLost => self.hidden = true,
+            Event::FocusGained => self.hidden = false,
+            _ => {}
+        }
+        None
+    }
+
+    /// React to a key press. Returns false for the exit keys.
+    pub fn handle_key(&mut self, key: KeyEvent) -> bool {
+        // Windows, and terminals speaking the kitty keyboard protocol,
+        // report releases too; a key counts once, when it goes down, and
+        // held keys repeat only where holding them makes sense
+        #[cfg(not(target_arch = "wasm32"))]
+        if !counts(key, self.settings.keymap.action(key).is_some_and(Action::repeats)) {
+            return true;
+        }
+        // A locked-down kiosk ignores every key but the one that closes it
+        if self.settings.kiosk_lock {
+            return !kiosk::is_chord(key);
+        }
+        // The key that launched us may still be arriving
+        if self.started.elapsed() < self.settings.grace && !is_interrupt(key) {
+            return true;
+        }
+        if is_interrupt(key) {
+            return !self.settings.ctrl_c;
+        }
+        if let Some(phrase) = self.settings.lock.clone() {
+            return self.unlock(&phrase, key);
+        }
+        if self.settings.exit_on_any_key {
+            return false;
+        }
+        if self.settings.reactive && self.settings.keymap.action(key) != Some(Action::Quit) {
+            self.react(key);
+        }
+        // Any key just closes the help overlay
+        if self.help {
+            self.help = false;
+            return true;
+        }
+        if self.game.is_some() {
+            if self.play(key) {
+                return true;
+            }
+        } else if let KeyCode::Char(ch) = key.code {
+            self.recent.push(ch);
+            while self.recent.len() > game::TRIGGER.len() {
+                self.recent.remove(0);
+            }
+            if self.recent == game::TRIGGER {
+                self.recent.clear();
+                self.game = Some(Game::new());
+                self.notify("Type the falling words".to_string());
+                return true;
+            }
+        }
+        match self.settings.keymap.action(key) {
+            Some(Action::Quit) => return false,
+            // Keys the running effect uses itself
+            _ if self.effect.handle_key(key, &mut self.settings) => {}
+            // Speed controls
+            Some(Action::SpeedUp) => self.speed_up(true),
+            Some(Action::SpeedDown) => self.speed_up(false),
+            // Density controls
+            Some(Action::DensityUp) => self.thicken(true),
+            Some(Action::DensityDown) => self.thicken(false),
+            // Length controls
+            Some(Action::LengthUp) => {
+                self.settings.max_length = (self.settings.max_length + 5).min(50).max(self.settings.min_length);
+                self.show_osd(format!("Length: {}", self.settings.max_length));
+            }
+            Some(Action::LengthDown) => {
+                self.settings.max_length = self.settings.max_length.saturating_sub(5).max(self.settings.min_length);
+                self.show_osd(format!("Length: {}", self.settings.max_length));
+            }
+            // Color schemes
+            Some(Action::Green) => self.set_scheme(ColorScheme::Green),
+            Some(Action::Blue) => self.set_scheme(ColorScheme::Blue),
+            Some(Action::Red) => self.set_scheme(ColorScheme::Red),
+            Some(Action::Purple) => self.set_scheme(ColorScheme::Purple),
+            Some(Action::Cyan) => self.set_scheme(ColorScheme::Cyan),
+            Some(Action::Rainbow) => self.set_scheme(ColorScheme::Rainbow),
+            // Wind controls
+            Some(Action::WindLeft) => {
+                self.settings.wind = (self.settings.wind - 0.1).max(-1.0);
+                self.show_osd(format!("Wind: {:+.1}", self.settings.wind));
+            }
+            Some(Action::WindRight) => {
+                self.settings.wind = (self.settings.wind + 0.1).min(1.0);
+                self.show_osd(format!("Wind: {:+.1}", self.settings.wind));
+            }
+            Some(Action::BrightnessDown) => {
+                self.settings.brightness = (self.settings.brightness - 0.1).max(0.1);
+                self.show_osd(format!("Brightness: {:.0}%", self.settings.brightness * 100.0));
+            }
+            Some(Action::BrightnessUp) => {
+                self.settings.brightness = (self.settings.brightness + 0.1).min(1.0);
+                self.show_osd(format!("Brightness: {:.0}%", self.settings.brightness * 100.0));
+            }
+            Some(Action::GhostingDown) => self.ghost(false),
+            Some(Action::GhostingUp) => self.ghost(true),
+            Some(Action::Shimmer) => {
+                self.settings.shimmer_zone = self.settings.shimmer_zone.next();
+                self.show_osd(format!("Shimmer: {}", self.settings.shimmer_zone.name()));
+            }
+            // Copy the screen as plain text or with its colors
+            #[cfg(feature = "clipboard")]
+            Some(Action::Copy) => {
+                let frame = Frame::new(self.cols, self.rows, self.frame());
+                self.clipboard.copy(frame.to_plain_string());
+            }
+            #[cfg(feature = "clipboard")]
+            Some(Action::CopyAnsi) => {
+                let frame = Frame::new(self.cols, self.rows, self.frame());
+                self.clipboard.copy(frame.to_ansi_string());
+            }
+            // Make the current look the default for next time
+            #[cfg(not(target_arch = "wasm32"))]
+            Some(Action::SaveConfig) => {
+                match config::save(&self.settings) {
+                    Ok(path) => self.notify(format!("Saved settings to {}", path.display())),
+                    Err(err) => self.notify(format!("Could not save settings: {}", err)),
+                }
+            }
+            // Save the screen to a file, with or without its colors
+            #[cfg(not(target_arch = "wasm32"))]
+            Some(Action::Screenshot) => self.screenshot(true),
+            #[cfg(not(target_arch = "wasm32"))]
+            Some(Action::ScreenshotPlain) => self.screenshot(false),
+            // Debug overlay
+            Some(Action::Debug) => {
+                self.settings.show_debug = !self.settings.show_debug;
+            }
+            // Frame rate overlay
+            Some(Action::Fps) => {
+                self.settings.show_fps = !self.settings.show_fps;
+            }
+            Some(Action::Pause) => {
+                self.paused = !self.paused;
+                let text = if self.paused {
+                    format!("Paused ({} to resume)", self.settings.keymap.describe(&[Action::Pause]))
+                } else {
+                    "Resumed".to_string()
+                };
+                self.notify(text);
+            }
+            Some(Action::Help) => self.help = true,
+            // Audition the presets, each on top of the defaults
+            Some(Action::NextPreset) => self.cycle_preset(1),
+            Some(Action::PrevPreset) => self.cycle_preset(self.presets.len() - 1),
+            // Back to the settings it started with
+            Some(Action::Reset) => {
+                self.settings.retune(&self.tuning);
+                self.show_osd("Settings reset".to_string());
+            }
+            // Bullet time; pressed again, it starts easing back to normal
+            Some(Action::BulletTime) => match self.bullet {
+                None => self.bullet = Some(0.0),
+                Some(elapsed) if elapsed < BULLET_EASE + BULLET_HOLD => {
+                    // Ease out from the same depth it has reached
+                    let depth = elapsed.min(BULLET_EASE);
+                    self.bullet = Some(BULLET_EASE * 2.0 + BULLET_HOLD - depth);
+                }
+                Some(_) => {}
+            },
+            // Step through a paused animation, one frame or ten at a time
+            Some(Action::Step) if self.paused => self.tick(),
+            Some(Action::StepTen) if self.paused => {
+                for _ in 0..10 {
+                    self.tick();
+                }
+            }
+            _ => {}
+        }
+        true
+    }
+
+    /// Advance by one frame and write it, with any overlays, to `out`. Only
+    /// the cells that changed since the last draw are written.
+    pub fn draw(&mut self, out: &mut impl Write) -> std::io::Result<()> {
+        self.step();
+        self.paint(out)
+    }
+
+    /// Write the current frame and overlays to `out` without advancing.
+    fn paint(&mut self, out: &mut impl Write) -> std::io::Result<()> {
+        let frame = if self.cols < MIN_COLS || self.rows < MIN_ROWS {
+            let mut glyphs = vec![Glyph::BLANK; self.cols as usize * self.rows as usize];
+            let too_small = TooSmall {
+                min_cols: MIN_COLS,
+                min_rows: MIN_ROWS,
+            };
+            let scheme = self.settings.color_scheme;
+            overlay::compose(&mut glyphs, self.cols, self.rows, scheme, &mut [&too_small]);
+            Frame::new(self.cols, self.rows, glyphs)
+        } else {
+            Frame::new(self.cols, self.rows, self.frame())
+        };
+        self.show(out, frame.glyphs())?;
+        self.fps.end();
+        Ok(())
+    }
+
+    /// Write what's changed in `frame` since the last one. With rows
+    /// reserved for the shell, its cursor and colors are put back after.
+    fn show(&mut self, out: &mut impl Write, frame: &[Glyph]) -> std::io::Result<()> {
+        let reserved = self.settings.reserve_bottom > 0;
+        if reserved {
+            write!(out, "\x1b7")?;
+        }
+        self.screen.draw(out, frame)?;
+        if reserved {
+            write!(out, "\x1b8")?;
+        }
+        Ok(())
+    }
+
+    pub(crate) fn settings_mut(&mut self) -> &mut Settings {
+        &mut self.settings
+    }
+
+    /// How many things the effect has moving.
+    pub(crate) fn population(&self) -> usize {
+        self.effect.population()
+    }
+
+    /// Whether `run` got as far as taking over the terminal, so an error
+    /// from it was the terminal's or another I/O failure along the way.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn is_on_screen(&self) -> bool {
+        self.on_screen
+    }
+
+    /// Stamps that missed the buffer since the last resize.
+    pub(crate) fn clipped(&self) -> usize {
+        self.layers.clipped()
+    }
+
+    /// End `run` once `flag` is set, as if a `--while` command had finished.
+    #[cfg(unix)]
+    pub(crate) fn stop_on(&mut self, flag: Arc<AtomicBool>) {
+        self.stop = Some(flag);
+    }
+
+    /// How long to wait between frames.
+    pub fn frame_delay(&self) -> Duration {
+        self.settings.frame_delay
+    }
+
+    /// Take over the terminal and run until an exit key, `--timeout`,
+    /// SIGTERM, or the `--while` command finishing. Returns why it stopped.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn run(&mut self) -> std::io::Result<Exit> {
+        let mut stdout = term::output();
+        let mut recorder = match &self.settings.record {
+            Some(path) => Some(Recorder::create(path, self.cols, self.rows)?),
+            None => None,
+        };
+        let mut profiler = match &self.settings.profile {
+            Some(path) => Some(Profiler::create(path)?),
+            None => None,
+        };
+
+        // Bound or connected before taking over the terminal, so a busy
+        // port or a missing server is a plain error
+        let server = match &self.settings.sync_server {
+            Some(addr) => Some(SyncServer::bind(addr)?),
+            None => None,
+        };
+        let mut client = match &self.settings.sync_client {
+            Some(addr) => Some(SyncClient::connect(addr)?),
+            None => None,
+        };
+        let mut resized = true; // Since the clients last started over
+        let mut sent = String::new(); // Tuning the clients last heard
+        let mut following: Option<Tuning> = None; // Tuning the server last sent
+
+        let mut job = match &self.settings.while_cmd {
+            Some(cmd) => Some(Job::spawn(cmd)?),
+            None => None,
+        };
+        // With rows reserved for the shell, the keyboard is the shell's and
+        // the terminal stays out of raw mode
+        let reserved = self.settings.reserve_bottom;
+        let listening = reserved == 0;
+        // SIGTERM would otherwise leave the terminal in raw mode, and out of
+        // raw mode Ctrl+C and a closed terminal would leave it scrolling in
+        // the reserved rows only
+        #[cfg(unix)]
+        let terminated = Arc::new(AtomicBool::new(false));
+        #[cfg(unix)]
+        signal_hook::flag::register(signal_hook::consts::SIGTERM, Arc::clone(&terminated))?;
+        #[cfg(unix)]
+        if !listening {
+            signal_hook::flag::register(signal_hook::consts::SIGINT, Arc::clone(&terminated))?;
+            signal_hook::flag::register(signal_hook::consts::SIGHUP, Arc::clone(&terminated))?;
+        }
+        let began = Instant::now();
+        self.ends = self.settings.timeout.map(|timeout| began + timeout);
+
+        // Each frame is built up here, then written to the terminal and to
+        // the recording in one go
+        let mut out = vec![];
+        // The old Windows console has the Linux console's 16 colors, and
+        // its raster font no katakana
+        if !term::supports_ansi() {
+            self.settings.linux_console = true;
+        }
+        self.on_screen = true;
+        if listening {
+            term::enter(&mut out)?;
+            // Straight to the terminal rather than the recording, where
+            // focus reports would mean nothing
+            execute!(stdout, EnableFocusChange)?;
+        } else {
+            term::enter_above(&mut out, self.rows, reserved)?;
+        }
+        // Terminals that speak the kitty keyboard protocol can tell Ctrl
+        // combinations apart and report held keys
+        let enhanced = listening && matches!(terminal::supports_keyboard_enhancement(), Ok(true));
+        if enhanced {
+            execute!(
+                stdout,
+                PushKeyboardEnhancementFlags(
+                    KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES
+                        | KeyboardEnhancementFlags::REPORT_EVENT_TYPES
+                        | KeyboardEnhancementFlags::REPORT_ALTERNATE_KEYS
+                )
+            )?;
+        }
+        // Titles and notifications also go straight to the terminal
+        let mut title = self.title();
+        if self.settings.title {
+            term::push_title(&mut stdout, &title)?;
+        }
+        let mut percent = None;
+        let settings = &self.settings;
+        let capture = listening && (settings.mouse || settings.exit_on_mouse || settings.cursor_effect.is_some());
+        if capture {
+            execute!(stdout, EnableMouseCapture)?;
+        }
+
+        // Low-power mode caps the frame rate, stretches frames further if
+        // they cost more CPU than the ceiling allows, and waits for the next
+        // frame inside `poll` so the process sleeps until it or a key is due
+        let ceiling = self.settings.low_power.map(|pct| pct.clamp(0.1, 100.0) / 100.0);
+        if ceiling.is_some() {
+            self.settings.frame_delay = self.settings.frame_delay.max(LOW_POWER_DELAY);
+            self.tuning = self.settings.tuning();
+        }
+        let mut work = Duration::ZERO; // Smoothed time spent on a frame
+
+        // On battery the rain slows down and thins out until it's plugged in
+        #[cfg(feature = "battery")]
+        let mut battery = self.settings.battery_saver.then(BatteryWatch::new);
+        #[cfg(feature = "battery")]
+        let mut saving = false;
+
+        // Edits to the config file, as they're saved
+        #[cfg(feature = "watch")]
+        let watch = self.settings.watch.take();
+
+        // No sound to be had is no reason not to rain
+        #[cfg(feature = "sound")]
+        if let Some(volume) = self.settings.sound {
+            match Sound::start(volume) {
+                Ok(sound) => self.sound = Some(sound),
+                Err(err) => self.notify(format!("No sound: {}", err)),
+            }
+        }
+
+        let forecast = self.settings.weather.as_deref().map(Forecast::start);
+
+        // Quit from the chooser, there's no intro either
+        let mut intro = match self.settings.chooser && listening {
+            true => self.choose(&mut stdout, &mut out, &mut recorder)?,
+            false => None,
+        };
+        if intro.is_none() && self.settings.intro {
+            intro = self.intro(&mut stdout, &mut out, &mut recorder)?;
+        }
+
+        // After the intro the rain crashes in instead
+        if self.settings.animation && !self.settings.intro {
+            self.appearing = Some(Instant::now());
+        }
+
+        let mut pacer = Pacer::new();
+        let mut exit = 'frames: loop {
+            // Quit during the chooser or intro: no rain at all
+            if let Some(exit) = intro {
+                break exit;
+            }
+            // Check for key press (non-blocking)
+            if listening && ceiling.is_none() && poll(Duration::from_millis(1))? {
+                if let Some(exit) = self.handle_event(read()?) {
+                    break exit;
+                }
+            }
+
+            #[cfg(unix)]
+            if terminated.load(Ordering::Relaxed) {
+                break Exit::Terminated;
+            }
+            #[cfg(unix)]
+            if self.stop.as_ref().is_some_and(|stop| stop.load(Ordering::Relaxed)) {
+                break Exit::Finished(0);
+            }
+            if self.settings.timeout.is_some_and(|timeout| began.elapsed() >= timeout) {
+                break Exit::Timeout;
+            }
+            if let Some(job) = &mut job {
+                if job.done()? {
+                    break Exit::Finished(0);
+                }
+            }
+
+            // Behind other windows: sleep until focus or a key comes back,
+            // waking now and then for the checks above
+            if self.hidden {
+                if poll(HIDDEN_WAKE)? {
+                    if let Some(exit) = self.handle_event(read()?) {
+                        break exit;
+                    }
+                }
+                if !self.hidden {
+                    // Pick up from now rather than catching up on the pause
+                    pacer = Pacer::new();
+                }
+                continue;
+            }
+
+            // Update terminal size
+            if let Ok((w, h)) = terminal_size(&self.settings) {
+                if (w, h) != (self.cols, self.rows) {
+                    self.resize(w, h);
+                    resized = true;
+                    if let Some(recorder) = &mut recorder {
+                        recorder.resize(w, h)?;
+                    }
+                    if listening {
+                        queue!(out, Clear(ClearType::All))?;
+                    } else {
+                        term::reserve(&mut out, h, reserved)?;
+                    }
+                }
+            }
+
+            #[cfg(feature = "battery")]
+            if let Some(on_battery) = battery.as_mut().and_then(BatteryWatch::changed) {
+                saving = on_battery;
+                if saving {
+                    self.settings.density /= 2.0;
+                    self.notify("On battery: saving power".to_string());
+                } else {
+                    self.settings.density = (self.settings.density * 2.0).min(1.0);
+                    self.notify("On AC power".to_string());
+                }
+            }
+
+            #[cfg(feature = "watch")]
+            if let Some(reloaded) = watch.as_ref().and_then(ConfigWatch::changed) {
+                self.follow_config(reloaded);
+            }
+
+            if let Some(conditions) = forecast.as_ref().and_then(Forecast::latest) {
+                self.follow_weather(&conditions);
+            }
+
+            if self.settings.title && self.title() != title {
+                title = self.title();
+                term::set_title(&mut stdout, &title)?;
+            }
+            if let Some(timeout) = self.settings.timeout.filter(|_| self.settings.notify) {
+                let done = began.elapsed().as_secs_f64() / timeout.as_secs_f64().max(f64::EPSILON);
+                let done = Some((done * 100.0) as u8);
+                if done != percent {
+                    percent = done;
+                    term::progress(&mut stdout, percent)?;
+                }
+            }
+            if std::mem::take(&mut self.alarm) {
+                self.settings.alert.fire(&mut stdout, "matrix-rain: the countdown's over")?;
+            }
+
+            let started = Instant::now();
+            let period = match ceiling {
+                Some(ceiling) => self.frame_delay().max(work.div_f64(ceiling)),
+                None => self.frame_delay(),
+            };
+            #[cfg(feature = "battery")]
+            let period = if saving { period.max(BATTERY_DELAY) } else { period };
+            // Everyone starts over together when a client joins or the
+            // server's terminal changes size
+            if let Some(server) = &server {
+                if server.joined() || resized {
+                    resized = false;
+                    let seed = self.rng.gen();
+                    self.restart(seed);
+                    server.send(&format!("reset {} {} {}", seed, self.cols, self.rows));
+                    sent.clear();
+                }
+                let tuning = self.settings.tuning().words();
+                if tuning != sent {
+                    server.send(&format!("tune {}", tuning));
+                    sent = tuning;
+                }
+            }
+
+            if let Some(sync) = &client {
+                // A client only moves when the server says, however the
+                // frames line up
+                self.fps.begin();
+                let (messages, connected) = sync.messages();
+                for message in messages {
+                    match message {
+                        Message::Reset { seed, cols, rows } => {
+                            self.restart(seed);
+                            if (cols, rows) != (self.cols, self.rows) {
+                                self.notify(format!("Sync server is {}x{}, so the rain won't match", cols, rows));
+                            }
+                        }
+                        Message::Tune(tuning) => {
+                            self.settings.retune(&tuning);
+                            following = Some(tuning);
+                        }
+                        Message::Tick(ticks) => {
+                            while self.ticks < ticks {
+                                self.tick();
+                            }
+                        }
+                    }
+                }
+                // Keys here don't get to pull it out of step
+                if let Some(tuning) = &following {
+                    self.settings.retune(tuning);
+                }
+                if !connected {
+                    client = None;
+                    self.notify("Lost the sync server".to_string());
+                }
+            } else {
+                // Catch up on updates a slow frame left behind, without drawing them
+                for _ in 1..pacer.due(period) {
+                    self.step();
+                }
+                self.step();
+            }
+            if let Some(server) = &server {
+                server.send(&format!("tick {}", self.ticks));
+            }
+            let updated = Instant::now();
+            self.paint(&mut out)?;
+            let drawn = Instant::now();
+            stdout.write_all(&out)?;
+            stdout.flush()?;
+            let flushed = Instant::now();
+            if let Some(recorder) = &mut recorder {
+                recorder.output(&out)?;
+            }
+
+            work = (work * 7 + (flushed - started)) / 8;
+
+            if listening && ceiling.is_some() {
+                while let Some(wait) = pacer.remaining() {
+                    if poll(wait)? {
+                        if let Some(exit) = self.handle_event(read()?) {
+                            break 'frames exit;
+                        }
+                    }
+                }
+            } else {
+                pacer.wait();
+            }
+            if let Some(profiler) = &mut profiler {
+                profiler.record(&FrameTimes {
+                    update: updated - started,
+                    draw: drawn - updated,
+                    flush: flushed - drawn,
+                    sleep: flushed.elapsed(),
+                    bytes: out.len(),
+                })?;
+            }
+            out.clear();
+        };
+
+        // Killed from outside, it's no time for a show, and quit during the
+        // chooser or intro there's no rain to dissolve
+        if self.settings.animation && intro.is_none() && !matches!(exit, Exit::Terminated) {
+            self.dissolve(&mut stdout, &mut out, &mut recorder)?;
+        }
+        if listening {
+            execute!(stdout, DisableFocusChange)?;
+        }
+        if self.settings.title {
+            term::pop_title(&mut stdout)?;
+        }
+        if percent.is_some() {
+            term::progress(&mut stdout, None)?;
+        }
+        match exit {
+            Exit::Timeout => self.settings.alert.fire(&mut stdout, "matrix-rain: time's up")?,
+            Exit::Finished(_) if self.settings.notify => {
+                term::notify(&mut stdout, "matrix-rain: the command finished")?
+            }
+            _ => {}
+        }
+        if enhanced {
+            execute!(stdout, PopKeyboardEnhancementFlags)?;
+        }
+        if capture {
+            execute!(stdout, DisableMouseCapture)?;
+        }
+        if listening {
+            term::leave(&mut out)?;
+        } else {
+            term::leave_above(&mut out, self.rows)?;
+        }
+        stdout.write_all(&out)?;
+        stdout.flush()?;
+        if let Some(mut recorder) = recorder {
+            recorder.output(&out)?;
+            recorder.finish()?;
+        }
+        if let Some(profiler) = profiler {
+            profiler.finish()?;
+        }
+
+        if let Some(mut job) = job {
+            match exit {
+                // Stopped from outside: the command goes too
+                Exit::Timeout | Exit::Terminated => job.kill()?,
+                _ if !job.done()? => eprintln!("Waiting for the --while command to finish..."),
+                _ => {}
+            }
+            let code = job.finish()?;
+            if let Exit::Key(_) | Exit::Mouse | Exit::Finished(_) = exit {
+                exit = Exit::Finished(code);
+            }
+        }
+        if let Some(cmd) = &self.settings.on_exit {
+            job::run(cmd)?;
+        }
+        Ok(exit)
+    }
+}
+
+/// The rain's size: `--width` and `--height` where given, otherwise the
+/// terminal's, less the rows `--reserve-bottom` leaves the shell. When the
+/// terminal can't say, `COLUMNS` and `LINES` can.
+#[cfg(not(target_arch = "wasm32"))]
+fn terminal_size(settings: &Settings) -> std::io::Result<(u16, u16)> {
+    let env = |name| std::env::var(name).ok()?.parse::<u16>().ok().filter(|&n| n > 0);
+    let (cols, rows) = match (settings.width, settings.height) {
+        (Some(cols), Some(rows)) => return Ok((cols, rows)),
+        _ => match terminal::size() {
+            Ok(size) => size,
+            Err(err) => (env("COLUMNS").ok_or(err)?, env("LINES").unwrap_or(24)),
+        },
+    };
+    Ok((
+        settings.width.unwrap_or(cols),
+        settings.height.unwrap_or(rows.saturating_sub(settings.reserve_bottom).max(1)),
+    ))
+}
@@ -0,0 +1,111 @@
+//! `matrix motd`: a few rows of rain with the machine's name set into it
+//! in big bright glyphs, for provisioning scripts to add to `/etc/motd`.
+
+use std::{fs, process::Command};
+
+use rand::{seq::SliceRandom, Rng};
+
+use crate::{
+    cli,
+    color::{brighten, Sweep},
+    drop::CHARS,
+    kiosk::pixel,
+    matrix::Matrix,
+    platform::Color,
+    render::Glyph,
+};
+
+/// Run `matrix motd [--rows N] [--width N] [--host NAME] [--no-color]
+/// [OPTIONS]`, printing the block to stdout.
+pub fn run(args: &[String]) -> std::io::Result<()> {
+    let mut width: u16 = 80;
+    let mut rows: u16 = 7;
+    let mut host = None;
+    let mut color = true;
+    let mut options = vec![];
+    let mut i = 0;
+    while i < args.len() {
+        let val = args.get(i + 1);
+        match (args[i].as_str(), val) {
+            ("--width", Some(val)) => width = val.parse().ok().filter(|&n| n > 0).unwrap_or(width),
+            ("--rows", Some(val)) => rows = val.parse().ok().filter(|&n| n > 0).unwrap_or(rows),
+            ("--host", Some(val)) => host = Some(val.clone()),
+            ("--no-color", _) => {
+                color = false;
+                i += 1;
+                continue;
+            }
+            _ => {
+                options.push(args[i].clone());
+                i += 1;
+                continue;
+            }
+        }
+        i += 2;
+    }
+    let host = host.or_else(hostname).unwrap_or_default();
+
+    let settings = cli::parse(&options);
+    let scheme = settings.color_scheme;
+    let mut matrix = Matrix::with_size(settings, width, rows);
+    matrix.warm_up();
+    let mut frame = matrix.render_frame();
+
+    // The name in 3x5 letters made of glyphs where there's room, as a line
+    // of plain text where there isn't, with a cell of clear margin round it
+    let chars: Vec<char> = host.chars().collect();
+    let big_width = (chars.len() * 4).saturating_sub(1);
+    let big = big_width + 4 <= width as usize && rows >= 7;
+    let (name_width, name_height) = if big {
+        (big_width as u16, 5)
+    } else {
+        ((chars.len() as u16).min(width.saturating_sub(4)), 1)
+    };
+    let (box_width, box_height) = match chars.is_empty() {
+        true => (0, 0),
+        false => ((name_width + 4).min(width), (name_height + 2).min(rows)),
+    };
+    let (left, top) = ((width - box_width) / 2, (rows - box_height) / 2);
+    let mut rng = rand::thread_rng();
+    // Single-width glyphs only, so the letters keep their shape
+    let letters: Vec<char> = CHARS.iter().copied().filter(char::is_ascii_alphanumeric).collect();
+    for y in 0..box_height {
+        for x in 0..box_width {
+            let (cx, cy) = (x as i32 - 2, y as i32 - 1);
+            let inside = cx >= 0 && cy >= 0 && cx < name_width as i32 && cy < name_height as i32;
+            let glyph = match (cx as usize, cy as usize) {
+                (cx, cy) if inside && big => {
+                    let ch = chars.get(cx / 4).copied().unwrap_or(' ');
+                    if pixel(ch.to_ascii_uppercase(), cx % 4, cy) {
+                        // The head color, lit a little unevenly so the
+                        // letters shimmer like the rain
+                        let color = brighten(scheme.get_colors(0, 10, left + x, Sweep::Horizontal), rng.gen_range(0.0..0.5));
+                        Glyph::new(*letters.choose(&mut rng).unwrap_or(&'#'), color)
+                    } else {
+                        Glyph::BLANK
+                    }
+                }
+                (cx, _) if inside => Glyph::new(chars[cx], Color::White),
+                _ => Glyph::BLANK,
+            };
+            frame.set(left + x, top + y, glyph);
+        }
+    }
+
+    if color {
+        println!("{}", frame.to_ansi_string());
+    } else {
+        print!("{}", frame.to_plain_string());
+    }
+    Ok(())
+}
+
+/// This machine's name, without its domain.
+pub(crate) fn hostname() -> Option<String> {
+    let name = fs::read_to_string("/etc/hostname").ok().or_else(|| {
+        let output = Command::new("hostname").output().ok()?;
+        Some(String::from_utf8_lossy(&output.stdout).into_owned())
+    })?;
+    let name = name.trim().split('.').next()?.to_string();
+    (!name.is_empty()).then_some(name)
+}
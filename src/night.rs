@@ -0,0 +1,94 @@
+use crate::{
+    color::dim,
+    platform::{Color, SystemTime, UNIX_EPOCH},
+    render::Glyph,
+};
+
+/// How bright the rain is at night, as a fraction of daytime.
+const NIGHT_BRIGHTNESS: f32 = 0.45;
+
+/// How far `--night-warm` pulls colors toward amber.
+const WARMTH: f32 = 0.7;
+
+/// The `--night` hours, as minutes past midnight. `end` before `start`
+/// means the night runs past midnight, as nights do.
+#[derive(Clone, Copy)]
+pub struct Night {
+    start: u16,
+    end: u16,
+}
+
+impl Night {
+    /// Parse `HH:MM-HH:MM`, e.g. `22:00-07:00`; `22-7` works too.
+    pub fn from_str(s: &str) -> Option<Self> {
+        let (start, end) = s.split_once('-')?;
+        Some(Night {
+            start: parse_time(start)?,
+            end: parse_time(end)?,
+        })
+    }
+
+    /// Whether it's night now, by the local clock.
+    pub fn now(&self) -> bool {
+        let minute = minute_of_day();
+        if self.start <= self.end {
+            (self.start..self.end).contains(&minute)
+        } else {
+            minute >= self.start || minute < self.end
+        }
+    }
+}
+
+/// Parse a time of day, `HH:MM` or just `HH`, as minutes past midnight.
+pub fn parse_time(time: &str) -> Option<u16> {
+    let (hours, minutes) = time.trim().split_once(':').unwrap_or((time.trim(), "0"));
+    let (hours, minutes): (u16, u16) = (hours.parse().ok()?, minutes.parse().ok()?);
+    (hours < 24 && minutes < 60).then_some(hours * 60 + minutes)
+}
+
+/// Minutes since local midnight.
+#[cfg(unix)]
+pub fn minute_of_day() -> u16 {
+    let now = unsafe { libc::time(std::ptr::null_mut()) };
+    let mut local: libc::tm = unsafe { std::mem::zeroed() };
+    if unsafe { libc::localtime_r(&now, &mut local) }.is_null() {
+        return utc_minute_of_day();
+    }
+    (local.tm_hour * 60 + local.tm_min) as u16
+}
+
+/// Minutes since midnight; without a portable way to the time zone here,
+/// UTC's.
+#[cfg(not(unix))]
+pub fn minute_of_day() -> u16 {
+    utc_minute_of_day()
+}
+
+fn utc_minute_of_day() -> u16 {
+    let secs = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |since| since.as_secs());
+    (secs / 60 % (24 * 60)) as u16
+}
+
+/// Dim `frame` for the night, and with `warm`, shift it toward amber the
+/// way a night light does.
+pub fn apply(frame: &mut [Glyph], warm: bool) {
+    let shade = |color: Color| {
+        let color = dim(color, NIGHT_BRIGHTNESS);
+        match color {
+            Color::Rgb { r, g, b } if warm => {
+                let level = r.max(g).max(b) as f32;
+                let pull = |c: u8, target: f32| (c as f32 + (target - c as f32) * WARMTH) as u8;
+                Color::Rgb {
+                    r: pull(r, level),
+                    g: pull(g, level * 0.6),
+                    b: pull(b, level * 0.2),
+                }
+            }
+            color => color,
+        }
+    };
+    for glyph in frame {
+        glyph.fg = shade(glyph.fg);
+        glyph.bg = shade(glyph.bg);
+    }
+}
@@ -0,0 +1,290 @@
+use std::time::Duration;
+
+use crate::{color::ColorScheme, platform::Color, render::Glyph};
+
+/// How high each kind of overlay sits: higher ones cover lower ones where
+/// they overlap.
+pub const Z_WATERMARK: u8 = 5;
+pub const Z_REVEAL: u8 = 10;
+pub const Z_PANEL: u8 = 15;
+pub const Z_MESSAGE: u8 = 20;
+pub const Z_STATUS: u8 = 30;
+pub const Z_STATS: u8 = 40;
+pub const Z_NOTICE: u8 = 50;
+pub const Z_HELP: u8 = 60;
+
+/// Something drawn over the rain rather than by it: text, boxes, messages.
+/// Overlays are put into the frame itself, after the rain, so drops never
+/// paint over them and they never leave anything behind when they go.
+/// `Matrix::add_overlay` keeps one of your own up.
+pub trait Overlay {
+    /// Where it sits in the stack, see the `Z_` constants.
+    fn z(&self) -> u8;
+
+    /// Put the overlay's cells on `canvas`, if it's showing.
+    fn draw(&self, canvas: &mut Canvas);
+}
+
+/// The frame as overlays see it. A cell one overlay has drawn on is masked:
+/// the ones below it in the stack can't touch it.
+pub struct Canvas<'a> {
+    frame: &'a mut [Glyph],
+    mask: Vec<bool>,
+    pub cols: u16,
+    pub rows: u16,
+    pub(crate) scheme: ColorScheme, // The rain's colors, for overlays that blend in
+}
+
+impl Canvas<'_> {
+    /// Set the cell at `x`, `y`, unless it's off screen or already taken.
+    pub(crate) fn put(&mut self, x: u16, y: u16, glyph: Glyph) {
+        if x >= self.cols || y >= self.rows {
+            return;
+        }
+        let idx = y as usize * self.cols as usize + x as usize;
+        if let (Some(cell), Some(masked)) = (self.frame.get_mut(idx), self.mask.get_mut(idx)) {
+            if !*masked {
+                *cell = glyph;
+                *masked = true;
+            }
+        }
+    }
+
+    /// Write `text` along row `y` from `x`, cut off at the edge.
+    pub fn text(&mut self, x: u16, y: u16, text: &str, color: Color) {
+        for (i, ch) in text.chars().enumerate() {
+            let Some(x) = x.checked_add(i as u16) else {
+                return;
+            };
+            self.put(x, y, Glyph::new(ch, color));
+        }
+    }
+}
+
+/// Draw `overlays` on a `cols` x `rows` frame, top of the stack first so
+/// it keeps the cells it covers.
+pub(crate) fn compose(
+    frame: &mut [Glyph],
+    cols: u16,
+    rows: u16,
+    scheme: ColorScheme,
+    overlays: &mut [&dyn Overlay],
+) {
+    overlays.sort_by_key(|overlay| std::cmp::Reverse(overlay.z()));
+    let mut canvas = Canvas {
+        mask: vec![false; frame.len()],
+        frame,
+        cols,
+        rows,
+        scheme,
+    };
+    for overlay in overlays.iter() {
+        overlay.draw(&mut canvas);
+    }
+}
+
+/// Cut `text` to `width` characters.
+fn fit(text: &str, width: u16) -> String {
+    text.chars().take(width as usize).collect()
+}
+
+/// The `--status-bar` row along the bottom: what's running and how it's
+/// going.
+pub(crate) struct StatusBar {
+    pub scheme: &'static str,
+    pub fps: f64,
+    pub density: f64,
+    pub drops: usize,
+    pub running: Duration,
+    pub dx: u16, // It has a row to itself, so it only shifts along it
+}
+
+impl Overlay for StatusBar {
+    fn z(&self) -> u8 {
+        Z_STATUS
+    }
+
+    fn draw(&self, canvas: &mut Canvas) {
+        let Some(y) = canvas.rows.checked_sub(1) else {
+            return;
+        };
+        let secs = self.running.as_secs();
+        let text = format!(
+            " {} │ {:.1} fps │ density {:.0}% │ {} drops │ {:02}:{:02}:{:02} ",
+            self.scheme,
+            self.fps,
+            self.density * 100.0,
+            self.drops,
+            secs / 3600,
+            secs / 60 % 60,
+            secs % 60
+        );
+        let row: String = std::iter::repeat_n(' ', self.dx as usize)
+            .chain(text.chars())
+            .chain(std::iter::repeat(' '))
+            .take(canvas.cols as usize)
+            .collect();
+        canvas.text(0, y, &row, Color::Grey);
+    }
+}
+
+/// Frame rate, frame time and drop count in the top right corner.
+pub(crate) struct Fps {
+    pub fps: f64,
+    pub work_ms: f64,
+    pub drops: usize,
+    pub shift: (u16, u16),
+}
+
+impl Overlay for Fps {
+    fn z(&self) -> u8 {
+        Z_STATS
+    }
+
+    fn draw(&self, canvas: &mut Canvas) {
+        let text = format!(" {:.1} fps  {:.1} ms  {} drops ", self.fps, self.work_ms, self.drops);
+        let text = fit(&text, canvas.cols);
+        let len = text.chars().count() as u16;
+        let (dx, y) = self.shift;
+        canvas.text((canvas.cols - len).saturating_sub(dx), y, &text, Color::White);
+    }
+}
+
+/// The `d` overlay in the top left corner: drops against the cap, and the
+/// weather if there is any.
+pub(crate) struct Debug {
+    pub drops: usize,
+    pub max_drops: usize,
+    pub storm: Option<f64>,
+    pub wind: f32,
+    pub shift: (u16, u16),
+}
+
+impl Overlay for Debug {
+    fn z(&self) -> u8 {
+        Z_STATS
+    }
+
+    fn draw(&self, canvas: &mut Canvas) {
+        let mut text = format!(" drops: {}/{} ", self.drops, self.max_drops);
+        if let Some(intensity) = self.storm {
+            text.push_str(&format!("storm: {:.2} ", intensity));
+        }
+        if self.wind != 0.0 {
+            text.push_str(&format!("wind: {:+.2} ", self.wind));
+        }
+        let (x, y) = self.shift;
+        canvas.text(x, y, &text, Color::White);
+    }
+}
+
+/// The current `--script` message, in the middle of the screen.
+pub(crate) struct Message<'a>(pub &'a str);
+
+impl Overlay for Message<'_> {
+    fn z(&self) -> u8 {
+        Z_MESSAGE
+    }
+
+    fn draw(&self, canvas: &mut Canvas) {
+        let text = fit(&format!(" {} ", self.0), canvas.cols);
+        let len = text.chars().count() as u16;
+        canvas.text((canvas.cols - len) / 2, canvas.rows / 2, &text, Color::White);
+    }
+}
+
+/// A confirmation along the middle of the bottom row.
+pub(crate) struct Notice<'a>(pub &'a str);
+
+impl Overlay for Notice<'_> {
+    fn z(&self) -> u8 {
+        Z_NOTICE
+    }
+
+    fn draw(&self, canvas: &mut Canvas) {
+        let text = fit(&format!(" {} ", self.0), canvas.cols);
+        let len = text.chars().count() as u16;
+        canvas.text((canvas.cols - len) / 2, canvas.rows.saturating_sub(1), &text, Color::White);
+    }
+}
+
+/// The value a key just changed, in the bottom right corner, fading out
+/// over a second.
+pub(crate) struct Osd<'a> {
+    pub text: &'a str,
+    pub age: Duration,
+}
+
+impl Overlay for Osd<'_> {
+    fn z(&self) -> u8 {
+        Z_NOTICE
+    }
+
+    fn draw(&self, canvas: &mut Canvas) {
+        let color = match self.age.as_millis() {
+            0..=499 => Color::White,
+            500..=749 => Color::Grey,
+            750..=999 => Color::DarkGrey,
+            _ => return,
+        };
+        let text = fit(&format!(" {} ", self.text), canvas.cols);
+        let len = text.chars().count() as u16;
+        canvas.text(canvas.cols - len, canvas.rows.saturating_sub(1), &text, color);
+    }
+}
+
+/// A box of lines in the middle of the screen, for the `?` keys.
+pub(crate) struct Help {
+    pub lines: Vec<String>,
+}
+
+impl Overlay for Help {
+    fn z(&self) -> u8 {
+        Z_HELP
+    }
+
+    fn draw(&self, canvas: &mut Canvas) {
+        // Leave room for the border, and cut what doesn't fit
+        let inner = (canvas.cols as usize).saturating_sub(4);
+        let width = self.lines.iter().map(|line| line.chars().count()).max().unwrap_or(0).min(inner);
+        let lines = &self.lines[..self.lines.len().min((canvas.rows as usize).saturating_sub(2))];
+        let (box_width, box_height) = (width as u16 + 4, lines.len() as u16 + 2);
+        let (x, y) = (
+            canvas.cols.saturating_sub(box_width) / 2,
+            canvas.rows.saturating_sub(box_height) / 2,
+        );
+
+        let rule = "─".repeat(width + 2);
+        let mut rows = vec![format!("┌{}┐", rule)];
+        for line in lines {
+            rows.push(format!("│ {:<width$} │", fit(line, width as u16), width = width));
+        }
+        rows.push(format!("└{}┘", rule));
+        for (dy, row) in rows.iter().enumerate() {
+            canvas.text(x, y + dy as u16, row, Color::White);
+        }
+    }
+}
+
+/// What's shown instead of the rain in a terminal too small for it: as
+/// much of the reason as fits.
+pub(crate) struct TooSmall {
+    pub min_cols: u16,
+    pub min_rows: u16,
+}
+
+impl Overlay for TooSmall {
+    fn z(&self) -> u8 {
+        Z_HELP
+    }
+
+    fn draw(&self, canvas: &mut Canvas) {
+        let needs = format!("Terminal too small, needs {}x{}", self.min_cols, self.min_rows);
+        let text = [needs.as_str(), "Terminal too small", "Too small"]
+            .into_iter()
+            .find(|text| text.chars().count() <= canvas.cols as usize)
+            .map_or_else(|| fit("Too small", canvas.cols), str::to_string);
+        let len = text.chars().count() as u16;
+        canvas.text((canvas.cols - len) / 2, canvas.rows / 2, &text, Color::DarkGrey);
+    }
+}
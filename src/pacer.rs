@@ -0,0 +1,53 @@
+use std::time::Duration;
+
+use crate::platform::Instant;
+
+/// Most updates run back to back to catch up after a slow frame; beyond
+/// this the backlog is dropped so a long stall doesn't fast-forward the rain.
+const MAX_CATCH_UP: u32 = 5;
+
+/// Keeps the animation on a fixed timestep. Frames are scheduled against a
+/// clock instead of sleeping a fixed delay after each one, so time spent
+/// drawing comes out of the wait. When a terminal or SSH link is too slow
+/// to keep up, updates run without being drawn so the rain still moves at
+/// the same speed.
+pub struct Pacer {
+    next: Instant, // When the next update is due
+}
+
+impl Pacer {
+    pub fn new() -> Self {
+        Pacer {
+            next: Instant::now(),
+        }
+    }
+
+    /// How many updates are due now, one every `period`. Always at least
+    /// one, so every call is followed by a frame.
+    pub fn due(&mut self, period: Duration) -> u32 {
+        let now = Instant::now();
+        let mut steps = 0;
+        while self.next <= now && steps < MAX_CATCH_UP {
+            self.next += period;
+            steps += 1;
+        }
+        if self.next <= now {
+            // Too far behind to catch up: start afresh from now
+            self.next = now + period;
+        }
+        steps.max(1)
+    }
+
+    /// Time left until the next update is due, None if it already is.
+    pub fn remaining(&self) -> Option<Duration> {
+        let now = Instant::now();
+        (self.next > now).then(|| self.next - now)
+    }
+
+    /// Sleep until the next update is due.
+    pub fn wait(&self) {
+        if let Some(remaining) = self.remaining() {
+            std::thread::sleep(remaining);
+        }
+    }
+}
@@ -0,0 +1,54 @@
+use crossterm::style::Color;
+use image::{imageops::FilterType, RgbImage};
+
+use crate::{color::dim, render::Glyph};
+
+/// `--image` picture the rain paints: every glyph takes the color of the
+/// pixel under it, keeping its own brightness, so the picture shows through
+/// wherever the rain falls.
+pub struct Picture {
+    source: RgbImage,
+    colors: Vec<Color>, // The picture scaled down to one pixel per terminal cell
+}
+
+impl Picture {
+    pub fn new(source: RgbImage) -> Self {
+        Picture {
+            source,
+            colors: vec![],
+        }
+    }
+
+    /// Scale the picture to a `cols` x `rows` terminal.
+    pub fn fit(&mut self, cols: u16, rows: u16) {
+        let scaled =
+            image::imageops::resize(&self.source, cols as u32, rows as u32, FilterType::Triangle);
+        self.colors = scaled
+            .pixels()
+            .map(|pixel| {
+                let [r, g, b] = pixel.0;
+                Color::Rgb { r, g, b }
+            })
+            .collect();
+    }
+
+    /// Recolor a row-major frame with the picture.
+    pub fn tint(&self, frame: &mut [Glyph]) {
+        for (glyph, &pixel) in frame.iter_mut().zip(&self.colors) {
+            if glyph.ch != ' ' {
+                glyph.fg = dim(pixel, brightness(glyph.fg));
+            }
+            if glyph.bg != Color::Reset {
+                glyph.bg = dim(pixel, brightness(glyph.bg));
+            }
+        }
+    }
+}
+
+/// How bright a color is, 0.0-1.0, judged by its strongest channel.
+fn brightness(color: Color) -> f32 {
+    match color {
+        Color::Rgb { r, g, b } => r.max(g).max(b) as f32 / 255.0,
+        _ => 1.0,
+    }
+}
@@ -0,0 +1,184 @@
+//! The few things that differ between a native terminal and the browser.
+//! Natively colors and keys are crossterm's; crossterm doesn't build for
+//! wasm, so there they're small stand-ins with the same shape. Escape codes
+//! are written by hand either way so both produce the same output.
+
+use std::io::Write;
+
+use crate::render::Style;
+
+#[cfg(not(target_arch = "wasm32"))]
+pub use crossterm::{
+    event::{KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    style::Color,
+};
+#[cfg(not(target_arch = "wasm32"))]
+pub use std::time::{Instant, SystemTime, UNIX_EPOCH};
+#[cfg(target_arch = "wasm32")]
+pub use web_time::{Instant, SystemTime, UNIX_EPOCH};
+
+/// Whether `key` is one of the keys that quit.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn is_exit_key(key: KeyEvent) -> bool {
+    match key.code {
+        KeyCode::Char('q') | KeyCode::Esc | KeyCode::Enter | KeyCode::Char(' ') => true,
+        KeyCode::Char('c') | KeyCode::Char('d') | KeyCode::Char('z') => {
+            key.modifiers.contains(KeyModifiers::CONTROL)
+        }
+        _ => false,
+    }
+}
+
+/// Whether `key` is a key going down, or held down and repeating when
+/// `repeats`, rather than one let go of.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn counts(key: KeyEvent, repeats: bool) -> bool {
+    match key.kind {
+        KeyEventKind::Press => true,
+        KeyEventKind::Repeat => repeats,
+        KeyEventKind::Release => false,
+    }
+}
+
+/// Whether `key` is Ctrl+C, which quits even when other keys are ignored.
+pub fn is_interrupt(key: KeyEvent) -> bool {
+    key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL)
+}
+
+/// Move the cursor to cell (`x`, `y`), zero-based.
+pub fn move_to(out: &mut impl Write, x: u16, y: u16) -> std::io::Result<()> {
+    write!(out, "\x1b[{};{}H", y + 1, x + 1)
+}
+
+pub fn set_fg(out: &mut impl Write, color: Color) -> std::io::Result<()> {
+    match sgr(color) {
+        Some(code) => write!(out, "\x1b[38;{}m", code),
+        None => write!(out, "\x1b[39m"),
+    }
+}
+
+pub fn set_bg(out: &mut impl Write, color: Color) -> std::io::Result<()> {
+    match sgr(color) {
+        Some(code) => write!(out, "\x1b[48;{}m", code),
+        None => write!(out, "\x1b[49m"),
+    }
+}
+
+/// Switch underline and blink on or off to match `style`.
+pub fn set_style(out: &mut impl Write, style: Style) -> std::io::Result<()> {
+    match style {
+        Style::Plain => write!(out, "\x1b[24;25m"),
+        Style::Underline => write!(out, "\x1b[25;4m"),
+        Style::Blink => write!(out, "\x1b[24;5m"),
+    }
+}
+
+/// The SGR color arguments for `color`, None for the terminal's default.
+fn sgr(color: Color) -> Option<String> {
+    let index = match color {
+        Color::Reset => return None,
+        Color::Rgb { r, g, b } => return Some(format!("2;{};{};{}", r, g, b)),
+        Color::AnsiValue(value) => value,
+        Color::Black => 0,
+        Color::DarkRed => 1,
+        Color::DarkGreen => 2,
+        Color::DarkYellow => 3,
+        Color::DarkBlue => 4,
+        Color::DarkMagenta => 5,
+        Color::DarkCyan => 6,
+        Color::Grey => 7,
+        Color::DarkGrey => 8,
+        Color::Red => 9,
+        Color::Green => 10,
+        Color::Yellow => 11,
+        Color::Blue => 12,
+        Color::Magenta => 13,
+        Color::Cyan => 14,
+        Color::White => 15,
+    };
+    Some(format!("5;{}", index))
+}
+
+#[cfg(target_arch = "wasm32")]
+pub use web::*;
+
+/// Stand-ins for the crossterm types the rain uses.
+#[cfg(target_arch = "wasm32")]
+mod web {
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    pub enum Color {
+        Reset,
+        Black,
+        DarkGrey,
+        Red,
+        DarkRed,
+        Green,
+        DarkGreen,
+        Yellow,
+        DarkYellow,
+        Blue,
+        DarkBlue,
+        Magenta,
+        DarkMagenta,
+        Cyan,
+        DarkCyan,
+        White,
+        Grey,
+        Rgb { r: u8, g: u8, b: u8 },
+        AnsiValue(u8),
+    }
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    pub enum KeyCode {
+        Char(char),
+        Up,
+        Down,
+        Left,
+        Right,
+        Enter,
+        Esc,
+        Backspace,
+        Tab,
+        BackTab,
+    }
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    pub struct KeyModifiers(u8);
+
+    impl KeyModifiers {
+        pub const NONE: Self = Self(0);
+        pub const SHIFT: Self = Self(1);
+        pub const CONTROL: Self = Self(2);
+        pub const ALT: Self = Self(4);
+
+        pub fn contains(self, other: Self) -> bool {
+            self.0 & other.0 == other.0
+        }
+    }
+
+    impl std::ops::BitOr for KeyModifiers {
+        type Output = Self;
+
+        fn bitor(self, other: Self) -> Self {
+            Self(self.0 | other.0)
+        }
+    }
+
+    impl std::ops::BitOrAssign for KeyModifiers {
+        fn bitor_assign(&mut self, other: Self) {
+            self.0 |= other.0;
+        }
+    }
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    pub struct KeyEvent {
+        pub code: KeyCode,
+        pub modifiers: KeyModifiers,
+    }
+
+    impl KeyEvent {
+        pub fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+            KeyEvent { code, modifiers }
+        }
+    }
+}
@@ -0,0 +1,143 @@
+//! `matrix play`: replay a session written with `--record`, or any other
+//! asciicast v2 file, in the terminal.
+
+use crossterm::event::{poll, read, Event, KeyEvent};
+use std::{
+    io::Write,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    platform::{counts, is_exit_key},
+    term,
+};
+
+/// One `[seconds, code, data]` line of an asciicast file.
+struct CastEvent {
+    time: f64,
+    code: String,
+    data: String,
+}
+
+/// Parse an event line. The header and anything else that isn't an event
+/// gives None.
+fn parse_event(line: &str) -> Option<CastEvent> {
+    let rest = line.trim().strip_prefix('[')?.strip_suffix(']')?;
+    let (time, rest) = rest.split_once(',')?;
+    let time = time.trim().parse().ok()?;
+    let (code, rest) = parse_string(rest.trim_start())?;
+    let rest = rest.trim_start().strip_prefix(',')?;
+    let (data, _) = parse_string(rest.trim_start())?;
+    Some(CastEvent { time, code, data })
+}
+
+/// Parse the JSON string at the start of `text`, returning it and whatever
+/// follows it.
+fn parse_string(text: &str) -> Option<(String, &str)> {
+    let mut chars = text.strip_prefix('"')?.char_indices();
+    let mut parsed = String::new();
+    let mut pending_high = None; // First half of a UTF-16 surrogate pair
+    while let Some((i, ch)) = chars.next() {
+        match ch {
+            '"' => return Some((parsed, &text[i + 2..])),
+            '\\' => {
+                let (_, escape) = chars.next()?;
+                match escape {
+                    'n' => parsed.push('\n'),
+                    'r' => parsed.push('\r'),
+                    't' => parsed.push('\t'),
+                    'b' => parsed.push('\x08'),
+                    'f' => parsed.push('\x0c'),
+                    'u' => {
+                        let hex: String = (0..4)
+                            .filter_map(|_| chars.next())
+                            .map(|(_, c)| c)
+                            .collect();
+                        let unit = u16::from_str_radix(&hex, 16).ok()?;
+                        if (0xd800..0xdc00).contains(&unit) {
+                            pending_high = Some(unit);
+                            continue;
+                        }
+                        let units = match pending_high.take() {
+                            Some(high) => vec![high, unit],
+                            None => vec![unit],
+                        };
+                        parsed.extend(char::decode_utf16(units).map(|c| c.unwrap_or('\u{fffd}')));
+                    }
+                    other => parsed.push(other), // \" \\ \/
+                }
+            }
+            ch => parsed.push(ch),
+        }
+    }
+    None
+}
+
+/// Whether `key` stops the playback: an exit key going down, or held.
+/// Windows reports releases too, such as of the Enter that started it.
+pub fn quits(key: KeyEvent) -> bool {
+    counts(key, true) && is_exit_key(key)
+}
+
+/// Run `matrix play <FILE.cast> [--speed FACTOR]`: play the recording back
+/// with its original timing, `FACTOR` times as fast, until it ends or an
+/// exit key is pressed.
+pub fn run(args: &[String]) -> std::io::Result<()> {
+    let mut path = None;
+    let mut speed: f64 = 1.0;
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--speed" => {
+                if let Some(val) = args.get(i + 1) {
+                    speed = val.parse().unwrap_or(speed);
+                    i += 1;
+                }
+            }
+            arg => path = Some(arg.to_string()),
+        }
+        i += 1;
+    }
+    let Some(path) = path else {
+        eprintln!("Usage: matrix play <FILE.cast> [--speed FACTOR]");
+        std::process::exit(1);
+    };
+    let cast = match std::fs::read_to_string(&path) {
+        Ok(cast) => cast,
+        Err(err) => {
+            eprintln!("Could not load recording {}: {}", path, err);
+            std::process::exit(1);
+        }
+    };
+    let speed = if speed > 0.0 { speed } else { 1.0 };
+
+    let mut stdout = term::output();
+    let mut out = vec![];
+    term::enter(&mut out)?;
+    stdout.write_all(&out)?;
+    stdout.flush()?;
+    out.clear();
+
+    let started = Instant::now();
+    'events: for event in cast.lines().filter_map(parse_event) {
+        let due = Duration::from_secs_f64(event.time.max(0.0) / speed);
+        // Wait for the event, watching for exit keys meanwhile
+        while let Some(wait) = due.checked_sub(started.elapsed()) {
+            if poll(wait.min(Duration::from_millis(50)))? {
+                if let Event::Key(key) = read()? {
+                    if quits(key) {
+                        break 'events;
+                    }
+                }
+            }
+        }
+        if event.code == "o" {
+            stdout.write_all(event.data.as_bytes())?;
+            stdout.flush()?;
+        }
+    }
+
+    term::leave(&mut out)?;
+    stdout.write_all(&out)?;
+    stdout.flush()
+}
@@ -0,0 +1,242 @@
+use crossterm::style::Color;
+use libloading::Library;
+use rand::rngs::SmallRng;
+use std::{
+    cell::Cell,
+    ffi::{c_char, c_void, CStr},
+    path::PathBuf,
+    rc::Rc,
+};
+
+use crate::{
+    buffer::PhosphorBuffer,
+    effect::{Effect, Weather},
+    settings::Settings,
+};
+
+/// A cell a plugin effect lights up, as handed back by
+/// `matrix_effect_render`.
+#[repr(C)]
+#[derive(Clone, Copy, Default)]
+struct PluginCell {
+    x: u16,
+    y: u16,
+    ch: u32,  // Unicode scalar value
+    rgb: u32, // 0xRRGGBB
+}
+
+type NameFn = unsafe extern "C" fn() -> *const c_char;
+type ColorFn = unsafe extern "C" fn(i: u32, length: u32, x: u16) -> u32;
+type NewFn = unsafe extern "C" fn(width: u16, height: u16) -> *mut c_void;
+type UpdateFn = unsafe extern "C" fn(state: *mut c_void, wind: f32, intensity: f64, density: f64);
+type RenderFn =
+    unsafe extern "C" fn(state: *mut c_void, cells: *mut PluginCell, cap: usize) -> usize;
+type FreeFn = unsafe extern "C" fn(state: *mut c_void);
+
+struct EffectFns {
+    new: NewFn,
+    update: UpdateFn,
+    render: RenderFn,
+    free: FreeFn,
+}
+
+/// A shared library from the plugin directory providing a color scheme, an
+/// effect, or both. The C ABI is:
+///
+/// ```c
+/// const char *matrix_plugin_name(void);
+///
+/// // Color scheme: glyph `i` (0 = head) of a drop `length` long in column `x`
+/// uint32_t matrix_color(uint32_t i, uint32_t length, uint16_t x); // 0xRRGGBB
+///
+/// // Effect
+/// typedef struct { uint16_t x, y; uint32_t ch, rgb; } MatrixCell;
+/// void *matrix_effect_new(uint16_t width, uint16_t height);
+/// void matrix_effect_update(void *state, float wind, double intensity, double density);
+/// size_t matrix_effect_render(void *state, MatrixCell *cells, size_t cap);
+/// void matrix_effect_free(void *state);
+/// ```
+pub struct Plugin {
+    pub name: String,
+    color: Option<ColorFn>,
+    effect: Option<EffectFns>,
+    _library: Library, // Keeps the function pointers above valid
+}
+
+impl Plugin {
+    fn load(path: &PathBuf) -> Result<Self, String> {
+        // SAFETY: loading a library runs its initialisers; plugins are code
+        // the user chose to install, same as any other binary they run
+        let library = unsafe { Library::new(path) }.map_err(|err| err.to_string())?;
+        // SAFETY: each symbol is only used with the signature the plugin ABI
+        // above gives it, and the pointers don't outlive `library`
+        unsafe {
+            let name = library
+                .get::<NameFn>(b"matrix_plugin_name\0")
+                .map_err(|err| err.to_string())?;
+            let name = (*name)();
+            if name.is_null() {
+                return Err("matrix_plugin_name returned null".to_string());
+            }
+            let name = CStr::from_ptr(name).to_string_lossy().into_owned();
+            let color = library.get::<ColorFn>(b"matrix_color\0").ok().map(|f| *f);
+            let effect = (|| {
+                Some(EffectFns {
+                    new: *library.get::<NewFn>(b"matrix_effect_new\0").ok()?,
+                    update: *library.get::<UpdateFn>(b"matrix_effect_update\0").ok()?,
+                    render: *library.get::<RenderFn>(b"matrix_effect_render\0").ok()?,
+                    free: *library.get::<FreeFn>(b"matrix_effect_free\0").ok()?,
+                })
+            })();
+            Ok(Plugin {
+                name,
+                color,
+                effect,
+                _library: library,
+            })
+        }
+    }
+
+    pub fn has_color(&self) -> bool {
+        self.color.is_some()
+    }
+
+    pub fn has_effect(&self) -> bool {
+        self.effect.is_some()
+    }
+
+    /// The plugin's color for glyph `i` of a drop `length` long in column `x`.
+    pub fn color(&self, i: usize, length: usize, x: u16) -> Option<Color> {
+        let color = self.color?;
+        // SAFETY: plain values in and out, as the ABI defines
+        let rgb = unsafe { color(i as u32, length as u32, x) };
+        let [_, r, g, b] = rgb.to_be_bytes();
+        Some(Color::Rgb { r, g, b })
+    }
+}
+
+/// Where plugins live: `$XDG_CONFIG_HOME/matrix-rain/plugins`, falling back
+/// to `~/.config/matrix-rain/plugins`.
+fn plugin_dir() -> Option<PathBuf> {
+    let config = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(config.join("matrix-rain").join("plugins"))
+}
+
+/// Load every plugin in the plugin directory. Libraries that fail to load
+/// are reported and skipped.
+pub fn discover() -> Vec<Rc<Plugin>> {
+    let Some(entries) = plugin_dir().and_then(|dir| std::fs::read_dir(dir).ok()) else {
+        return vec![];
+    };
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|ext| ext == std::env::consts::DLL_EXTENSION)
+        })
+        .collect();
+    paths.sort();
+
+    let mut plugins = vec![];
+    for path in paths {
+        match Plugin::load(&path) {
+            Ok(plugin) => plugins.push(Rc::new(plugin)),
+            Err(err) => eprintln!("Skipping plugin {}: {}", path.display(), err),
+        }
+    }
+    plugins
+}
+
+/// An effect provided by a plugin.
+pub struct PluginEffect {
+    plugin: Rc<Plugin>,
+    state: *mut c_void,
+    width: u16,
+    height: u16,
+    lit: Cell<usize>, // Cells lit last frame, for the debug overlay
+}
+
+impl PluginEffect {
+    /// `plugin` must have an effect.
+    pub fn new(plugin: Rc<Plugin>) -> Self {
+        PluginEffect {
+            plugin,
+            state: std::ptr::null_mut(),
+            width: 0,
+            height: 0,
+            lit: Cell::new(0),
+        }
+    }
+
+    fn fns(&self) -> &EffectFns {
+        self.plugin.effect.as_ref().expect("plugin has no effect")
+    }
+
+    fn free(&mut self) {
+        if !self.state.is_null() {
+            // SAFETY: `state` came from this plugin's `matrix_effect_new`
+            // and is freed exactly once
+            unsafe { (self.fns().free)(self.state) };
+            self.state = std::ptr::null_mut();
+        }
+    }
+}
+
+impl Drop for PluginEffect {
+    fn drop(&mut self) {
+        self.free();
+    }
+}
+
+impl Effect for PluginEffect {
+    fn resize(&mut self, width: u16, height: u16) {
+        self.free();
+        self.width = width;
+        self.height = height;
+        // SAFETY: plain values in, an opaque state pointer out
+        self.state = unsafe { (self.fns().new)(width, height) };
+    }
+
+    fn update(&mut self, settings: &Settings, weather: &Weather, _rng: &mut SmallRng) {
+        if self.state.is_null() {
+            return;
+        }
+        let update = self.fns().update;
+        // SAFETY: `state` is live and owned by this effect
+        unsafe {
+            update(
+                self.state,
+                weather.wind,
+                weather.intensity,
+                settings.density,
+            )
+        };
+    }
+
+    fn render(&self, buffer: &mut PhosphorBuffer, _settings: &Settings) {
+        if self.state.is_null() {
+            return;
+        }
+        let mut cells = vec![PluginCell::default(); self.width as usize * self.height as usize];
+        // SAFETY: `cells` has room for `cap` cells and the plugin writes at
+        // most that many
+        let count = unsafe { (self.fns().render)(self.state, cells.as_mut_ptr(), cells.len()) };
+        let count = count.min(cells.len());
+        for cell in &cells[..count] {
+            let Some(ch) = char::from_u32(cell.ch) else {
+                continue;
+            };
+            if cell.x < self.width && cell.y < self.height {
+                let [_, r, g, b] = cell.rgb.to_be_bytes();
+                buffer.stamp(cell.x, cell.y, ch, Color::Rgb { r, g, b });
+            }
+        }
+        self.lit.set(count);
+    }
+
+    fn population(&self) -> usize {
+        self.lit.get()
+    }
+}
@@ -0,0 +1,48 @@
+//! `--profile`: per-frame timings of a live run written to a CSV file, for
+//! attaching to reports of slow terminals and SSH links.
+
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    time::Duration,
+};
+
+/// How long each part of one frame took.
+pub struct FrameTimes {
+    pub update: Duration,
+    pub draw: Duration,
+    pub flush: Duration,
+    pub sleep: Duration,
+    pub bytes: usize,
+}
+
+pub struct Profiler {
+    file: BufWriter<File>,
+    frame: u64,
+}
+
+impl Profiler {
+    pub fn create(path: &str) -> std::io::Result<Self> {
+        let mut file = BufWriter::new(File::create(path)?);
+        writeln!(file, "frame,update_us,draw_us,flush_us,sleep_us,bytes")?;
+        Ok(Profiler { file, frame: 0 })
+    }
+
+    pub fn record(&mut self, times: &FrameTimes) -> std::io::Result<()> {
+        self.frame += 1;
+        writeln!(
+            self.file,
+            "{},{},{},{},{},{}",
+            self.frame,
+            times.update.as_micros(),
+            times.draw.as_micros(),
+            times.flush.as_micros(),
+            times.sleep.as_micros(),
+            times.bytes
+        )
+    }
+
+    pub fn finish(mut self) -> std::io::Result<()> {
+        self.file.flush()
+    }
+}
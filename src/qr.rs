@@ -0,0 +1,99 @@
+use qrcode::{Color as Module, QrCode};
+
+use crate::{color::{ColorScheme, Sweep}, render::Glyph};
+
+/// Quiet zone the QR spec asks for around the code, in modules. Shrunk on
+/// small terminals, since most scanners cope with less.
+const QUIET_ZONE: usize = 4;
+
+/// `--qr` code that the rain slowly fills in: each dark module lights up for
+/// good once a drop head passes over it, while the rest of the code area
+/// stays clear. Every terminal cell holds two modules stacked with half
+/// blocks, which keeps modules square.
+pub struct Qr {
+    code_width: usize, // Modules per side, without the quiet zone
+    dark: Vec<bool>,
+    locked: Vec<bool>,
+    quiet: usize,
+    left: u16, // Terminal cell of the top-left corner of the quiet zone
+    top: u16,
+    visible: bool, // False when the terminal is too small to fit the code
+}
+
+impl Qr {
+    /// Encode `text`, or None if it's too long for a QR code.
+    pub fn new(text: &str) -> Option<Self> {
+        let code = QrCode::new(text.as_bytes()).ok()?;
+        let dark: Vec<bool> = code.to_colors().into_iter().map(|m| m == Module::Dark).collect();
+        Some(Qr {
+            code_width: code.width(),
+            locked: vec![false; dark.len()],
+            dark,
+            quiet: QUIET_ZONE,
+            left: 0,
+            top: 0,
+            visible: false,
+        })
+    }
+
+    /// Center the code on a `cols` x `rows` terminal.
+    pub fn place(&mut self, cols: u16, rows: u16) {
+        let (cols, rows) = (cols as usize, rows as usize);
+        let fits = |quiet: usize| {
+            let size = self.code_width + quiet * 2;
+            size <= cols && size.div_ceil(2) <= rows
+        };
+        self.quiet = (1..=QUIET_ZONE).rev().find(|&quiet| fits(quiet)).unwrap_or(1);
+        self.visible = fits(self.quiet);
+        let size = self.size();
+        self.left = (cols.saturating_sub(size) / 2) as u16;
+        self.top = (rows.saturating_sub(size.div_ceil(2)) / 2) as u16;
+    }
+
+    /// Modules per side, quiet zone included.
+    fn size(&self) -> usize {
+        self.code_width + self.quiet * 2
+    }
+
+    /// Index of the code module at (`mx`, `my`) counted from the corner of
+    /// the quiet zone, or None in the quiet zone.
+    fn module(&self, mx: usize, my: usize) -> Option<usize> {
+        let (cx, cy) = (mx.checked_sub(self.quiet)?, my.checked_sub(self.quiet)?);
+        (cx < self.code_width && cy < self.code_width).then(|| cy * self.code_width + cx)
+    }
+
+    /// The modules (top, bottom) shown in terminal cell (`x`, `y`), or None
+    /// if the cell is outside the code area.
+    fn cell(&self, x: u16, y: u16) -> Option<(Option<usize>, Option<usize>)> {
+        if !self.visible || x < self.left || y < self.top {
+            return None;
+        }
+        let (mx, my) = ((x - self.left) as usize, (y - self.top) as usize * 2);
+        if mx >= self.size() || my >= self.size() {
+            return None;
+        }
+        Some((self.module(mx, my), self.module(mx, my + 1)))
+    }
+
+    /// A drop head passed terminal cell (`x`, `y`): light its dark modules.
+    pub fn lock(&mut self, x: u16, y: u16) {
+        if let Some((top, bottom)) = self.cell(x, y) {
+            for idx in [top, bottom].into_iter().flatten() {
+                self.locked[idx] |= self.dark[idx];
+            }
+        }
+    }
+
+    /// What terminal cell (`x`, `y`) shows, or None outside the code area.
+    pub fn glyph(&self, x: u16, y: u16, color_scheme: ColorScheme) -> Option<Glyph> {
+        let (top, bottom) = self.cell(x, y)?;
+        let lit = |idx: Option<usize>| idx.is_some_and(|idx| self.locked[idx]);
+        let ch = match (lit(top), lit(bottom)) {
+            (false, false) => return Some(Glyph::BLANK),
+            (true, false) => '▀',
+            (false, true) => '▄',
+            (true, true) => '█',
+        };
+        Some(Glyph::new(ch, color_scheme.get_colors(1, 2, x, Sweep::Horizontal)))
+    }
+}
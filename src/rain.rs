@@ -0,0 +1,421 @@
+use rand::{rngs::SmallRng, Rng};
+
+use crate::{
+    buffer::PhosphorBuffer,
+    color::{dim, mix},
+    drop::{self, Churn, Collisions, Drop, Layer},
+    effect::{sample_poisson, Effect, Head, Weather},
+    keymap::Action,
+    platform::KeyEvent,
+    settings::Settings,
+    splash::Splash,
+};
+
+/// The classic digital rain.
+#[derive(Default)]
+pub struct MatrixRain {
+    drops: Vec<Drop>,
+    spare: Vec<Drop>,    // Finished drops, whose memory the next ones reuse
+    branches: Vec<Drop>, // Children of drops that split this frame
+    speeds: Vec<u8>,     // Each column's speed, with `--column-speeds`
+    order: Vec<usize>,   // Drops by layer and column, front to back
+    held: Vec<bool>,     // Drops that merged away or wait this frame
+    splashes: Vec<Splash>, // Where drops landed, with `--splash`
+    panels: Vec<usize>,  // Drops in each panel, with `--segments`
+    width: u16,
+    height: u16,
+}
+
+impl MatrixRain {
+    /// A drop from the spares if there are any, so the hot loop doesn't
+    /// allocate once the rain has got going.
+    fn new_drop(&mut self, across: f32, travel: u16, layer: Layer, settings: &Settings, rng: &mut SmallRng) -> Drop {
+        match self.spare.pop() {
+            Some(spare) => spare.renew(across, travel, layer, settings, rng),
+            None => Drop::new(across, travel, layer, settings, rng),
+        }
+    }
+
+    /// The speed for a new drop in column `across` of `cross`. A column that
+    /// has no drops left in it now and then picks a new one, so the speeds
+    /// shuffle around over time without a drop ever overrunning another.
+    fn column_speed(&mut self, across: f32, cross: u16, layer: Layer, settings: &Settings, rng: &mut SmallRng) -> u8 {
+        let (min, max) = (settings.min_speed, settings.max_speed);
+        if self.speeds.len() != cross as usize {
+            self.speeds = (0..cross).map(|_| settings.speed_distribution.sample(min, max, rng)).collect();
+        }
+        // Upwind spawns off the edge borrow the speed of a column on screen
+        let column = (across as i32).rem_euclid(cross.max(1) as i32) as usize;
+        let Some(speed) = self.speeds.get_mut(column) else {
+            return settings.speed_distribution.sample(min, max, rng);
+        };
+        let clear = !self.drops.iter().any(|drop| drop.across().floor() == across);
+        if clear && rng.gen_bool(0.25) {
+            *speed = settings.speed_distribution.sample(min, max, rng);
+        }
+        let speed = (*speed).clamp(min, max);
+        // Back drops keep their parallax, all slower by the same amount
+        if layer == Layer::Back {
+            speed.saturating_add(2)
+        } else {
+            speed
+        }
+    }
+
+    /// Walk each column's drops front to back and deal with the ones that
+    /// have caught up with the drop ahead, as `collisions` says: marks in
+    /// `held` the ones that have merged into it or have to wait.
+    fn collide(&mut self, collisions: Collisions) {
+        let drops = &mut self.drops;
+        self.order.clear();
+        self.order.extend(0..drops.len());
+        self.order.sort_by_key(|&i| {
+            let drop = &drops[i];
+            (drop.layer == Layer::Front, drop.column(), std::cmp::Reverse(drop.travelled()))
+        });
+        self.held.clear();
+        self.held.resize(drops.len(), false);
+
+        let mut ahead: Option<usize> = None;
+        for &i in &self.order {
+            let Some(front) = ahead.filter(|&front| {
+                let (a, b) = (&drops[front], &drops[i]);
+                a.layer == b.layer && a.column() == b.column() && a.is_whole() && b.is_whole()
+            }) else {
+                ahead = Some(i);
+                continue;
+            };
+            let gap = drops[i].gap(&drops[front]);
+            match collisions {
+                Collisions::Merge if gap <= 0 => {
+                    // The merged drop stays in front for the next one
+                    if let Ok([front, behind]) = drops.get_disjoint_mut([front, i]) {
+                        front.absorb(behind);
+                    }
+                    self.held[i] = true;
+                }
+                Collisions::Block => {
+                    // One more step would take it into the other's tail
+                    self.held[i] = gap <= 1;
+                    ahead = Some(i);
+                }
+                _ => ahead = Some(i),
+            }
+        }
+    }
+
+    fn spawn_drops(&mut self, settings: &Settings, weather: &Weather, rng: &mut SmallRng) {
+        let max_drops = settings.max_drops;
+        if self.drops.len() >= max_drops {
+            return;
+        }
+
+        // Backpressure: spawn less eagerly as we approach the cap
+        let headroom = 1.0 - self.drops.len() as f64 / max_drops as f64;
+        let (travel, cross) = settings.direction.extents(self.width, self.height);
+        let lean = (slope(settings) + weather.wind) * travel as f32;
+
+        // Heavier weather also means faster drops
+        let intensity = weather.intensity;
+        let speed_shift = ((intensity - 1.0) * 1.5).round() as i32;
+        let max_spawns = (settings.spawns_per_frame as f64 * intensity.max(1.0)) as usize;
+
+        // With `--split` each half of the cross axis rains at its own
+        // density, and with `--segments` each panel rains on its own
+        let region = |i: u16| match (&settings.segments, &settings.split) {
+            (Some(segments), _) => {
+                let (start, end) = segments.bounds(i, cross);
+                (start, end, settings.density)
+            }
+            (None, Some(_)) if i == 0 => (0, cross / 2, settings.density),
+            (None, Some(split)) => (cross / 2, cross, split.density.unwrap_or(settings.density)),
+            (None, None) => (0, cross, settings.density),
+        };
+        let regions = match (&settings.segments, &settings.split) {
+            (Some(segments), _) => segments.count,
+            (None, Some(_)) => 2,
+            (None, None) => 1,
+        };
+        if let Some(segments) = &settings.segments {
+            self.panels.clear();
+            self.panels.resize(segments.count as usize, 0);
+            for drop in &self.drops {
+                if let Some(i) = segments.panel(drop.across(), cross) {
+                    self.panels[i as usize] += 1;
+                }
+            }
+        }
+
+        for i in 0..regions {
+            let (start, end, density) = region(i);
+            if start >= end {
+                continue;
+            }
+            // Drops drift sideways on the way, so spawn across the upwind
+            // span too and scale the rate to keep coverage the same
+            let spawn_min = start as f32 - lean.max(0.0);
+            let spawn_max = end as f32 - lean.min(0.0);
+            let span = (spawn_max - spawn_min) as f64 / (end - start) as f64;
+            // A panel keeps to its share of the drops, so a busy one can't
+            // starve the others
+            let headroom = match settings.segments {
+                Some(_) => {
+                    let share = max_drops as f64 * (end - start) as f64 / cross as f64;
+                    (1.0 - self.panels[i as usize] as f64 / share).max(0.0)
+                }
+                None => headroom,
+            };
+
+            let rate = spawn_rate(settings, density, travel, end - start)
+                * headroom
+                * intensity
+                * span
+                * weather.time as f64;
+            let count = sample_poisson(rng, rate)
+                .min(max_spawns)
+                .min(max_drops.saturating_sub(self.drops.len()));
+            for _ in 0..count {
+                let across = rng.gen_range(spawn_min..spawn_max).floor();
+                // Fewer where the mask is dark, none where it's black
+                if let Some(mask) = &settings.mask {
+                    let across = across.clamp(0.0, cross.saturating_sub(1) as f32);
+                    if !rng.gen_bool(mask.chance(across, cross, settings.direction.is_vertical())) {
+                        continue;
+                    }
+                }
+                let layer = if settings.layers && rng.gen_bool(0.55) {
+                    Layer::Back
+                } else {
+                    Layer::Front
+                };
+                let mut drop = self.new_drop(across, travel, layer, settings, rng);
+                if settings.column_speeds {
+                    drop.speed = self.column_speed(across, cross, layer, settings, rng);
+                }
+                if speed_shift != 0 {
+                    let max_speed = settings.max_speed as i32 + 2;
+                    drop.speed = (drop.speed as i32 - speed_shift).clamp(1, max_speed) as u8;
+                }
+                #[cfg(feature = "lua")]
+                if let Some(lua) = &settings.lua {
+                    if !lua.on_spawn(across, &mut drop, rng) {
+                        self.spare.push(drop);
+                        continue;
+                    }
+                }
+                self.drops.push(drop);
+            }
+        }
+    }
+
+    /// Light up the drops on `layer`.
+    fn render_layer(&self, layer: Layer, buffer: &mut PhosphorBuffer, settings: &Settings) {
+        let (width, height) = (self.width, self.height);
+        let (_, cross) = settings.direction.extents(width, height);
+        let stamp = |buffer: &mut PhosphorBuffer, (x, y, ch, color)| {
+            // `--segments` gutters stay blank, whatever drifts into them
+            if let Some(segments) = settings.segments.as_ref().filter(|segments| segments.gutters) {
+                let across = if settings.direction.is_vertical() { x } else { y };
+                if segments.panel(across as f32, cross).is_none() {
+                    return;
+                }
+            }
+            buffer.stamp(x, y, ch, color);
+            // Every drop has a twin on the other side of the mirror
+            if let Some(mirror) = settings.mirror {
+                let (x, y) = mirror.reflect(x, y, width, height);
+                buffer.stamp(x, y, ch, color);
+            }
+        };
+        if layer == Layer::Front {
+            for splash in &self.splashes {
+                let scheme = settings.scheme_across(splash.across(), width, height);
+                for (x, y, ch, level) in splash.glyphs() {
+                    if x < 0 || y < 0 || x >= width as i32 || y >= height as i32 {
+                        continue;
+                    }
+                    let color = scheme.get_colors(2, 10, x as u16, settings.rainbow_axis.at(y as u16));
+                    stamp(buffer, (x as u16, y as u16, ch, dim(color, level)));
+                }
+            }
+        }
+        // Trails first, so a drop passing through another keeps its head
+        for heads in [false, true] {
+            for drop in self.drops.iter().filter(|drop| drop.layer == layer) {
+                let scheme = settings.scheme_across(drop.across(), width, height);
+                let paint = |i, length, x, y| {
+                    #[cfg(feature = "lua")]
+                    if let Some(color) = settings.lua.as_ref().and_then(|lua| lua.color(i, length, x)) {
+                        return color;
+                    }
+                    #[cfg(feature = "plugins")]
+                    if let Some(color) = settings.color_plugin.as_ref().and_then(|plugin| plugin.color(i, length, x)) {
+                        return color;
+                    }
+                    let sweep = settings.rainbow_axis.at(y);
+                    let color = scheme.get_colors(i, length, x, sweep);
+                    match settings.crossfade {
+                        Some((from, amount)) if scheme == settings.color_scheme => {
+                            mix(from.get_colors(i, length, x, sweep), color, amount)
+                        }
+                        _ => color,
+                    }
+                };
+                let head = drop.head().map(|(x, y)| (x as u16, y as u16));
+                let mut glyphs = drop.glyphs(width, height, paint);
+                // The head, if it's on screen, is the first glyph
+                if heads {
+                    if let Some(glyph) = glyphs.next().filter(|&(x, y, ..)| Some((x, y)) == head) {
+                        stamp(buffer, glyph);
+                    }
+                } else {
+                    for glyph in glyphs.filter(|&(x, y, ..)| Some((x, y)) != head) {
+                        stamp(buffer, glyph);
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl Effect for MatrixRain {
+    fn resize(&mut self, width: u16, height: u16) {
+        self.width = width;
+        self.height = height;
+    }
+
+    fn update(&mut self, settings: &Settings, weather: &Weather, rng: &mut SmallRng) {
+        self.spawn_drops(settings, weather, rng);
+        if settings.collisions == Collisions::Pass {
+            self.held.clear();
+        } else {
+            self.collide(settings.collisions);
+        }
+        if settings.collisions == Collisions::Merge {
+            let mut held = self.held.iter();
+            self.spare.extend(self.drops.extract_if(.., |_| held.next() == Some(&true)));
+            self.held.clear();
+        }
+
+        let slope = slope(settings);
+        let churn = Churn {
+            shimmer: settings.shimmer_zone.reach().map(|reach| (settings.shimmer, reach)),
+            head_glyphs: settings.head_glyphs.as_deref(),
+            head_mutate: settings.head_mutate,
+        };
+        let avoid = weather.avoid.map(|(x, y)| settings.direction.axes(x as i32, y as i32));
+        let (width, height) = (self.width, self.height);
+        for (i, drop) in self.drops.iter_mut().enumerate() {
+            if self.held.get(i) == Some(&true) {
+                continue;
+            }
+            if let Some((along, across)) = avoid {
+                drop.avoid(along, across as f32, weather.time);
+            }
+            let travelled = drop.travelled();
+            // Each drop runs on its own clock inside a `--dilate` zone
+            let time = weather.time * drop.head().map_or(1.0, |(x, y)| settings.time_at(x, y));
+            drop.update(slope, weather.wind, settings.gravity, time, churn, rng);
+            if let Some(reach) = settings.splash.filter(|_| drop.layer == Layer::Front) {
+                // Only as it arrives, not each frame a slow drop sits there
+                if drop.travelled() != travelled {
+                    self.splashes.extend(drop.splash(width, height, reach, rng));
+                }
+            }
+            if let Some(branches) = drop.branch(settings.head_glyphs.is_some()) {
+                self.branches.extend(branches);
+            }
+        }
+        for splash in &mut self.splashes {
+            splash.update(weather.time);
+        }
+        self.splashes.retain(|splash| !splash.is_done());
+        self.spare.extend(self.drops.extract_if(.., |drop| drop.is_done(width, height)));
+        let room = settings.max_drops.saturating_sub(self.drops.len());
+        self.branches.truncate(room);
+        self.drops.append(&mut self.branches);
+    }
+
+    fn render(&self, buffer: &mut PhosphorBuffer, settings: &Settings) {
+        self.render_layer(Layer::Front, buffer, settings);
+    }
+
+    fn render_back(&self, buffer: &mut PhosphorBuffer, settings: &Settings) {
+        self.render_layer(Layer::Back, buffer, settings);
+    }
+
+    fn handle_key(&mut self, key: KeyEvent, settings: &mut Settings) -> bool {
+        match settings.keymap.action(key) {
+            // Direction toggle
+            Some(Action::Reverse) => {
+                settings.direction = settings.direction.reversed();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Front drops come first.
+    fn spawn_at(&mut self, x: u16, y: u16, count: usize, settings: &Settings, rng: &mut SmallRng) {
+        let direction = settings.direction;
+        let (travel, _) = direction.extents(self.width, self.height);
+        let (along, across) = direction.axes(x as i32, y as i32);
+        let room = settings.max_drops.saturating_sub(self.drops.len());
+        for i in 0..count.min(room) {
+            // A burst spreads over the neighbouring columns and staggers its
+            // heads so it doesn't move as one block
+            let (spread, lag) = if i == 0 {
+                (0, 0)
+            } else {
+                (rng.gen_range(-1..=1), rng.gen_range(1..5))
+            };
+            let mut drop = self.new_drop((across + spread) as f32, travel, Layer::Front, settings, rng);
+            drop.start_at(along - direction.step() * lag);
+            self.drops.push(drop);
+        }
+    }
+
+    fn heads(&self, heads: &mut Vec<Head>) {
+        let (width, height) = (self.width as i32, self.height as i32);
+        heads.clear();
+        for layer in [Layer::Front, Layer::Back] {
+            for drop in self.drops.iter().filter(|drop| drop.layer == layer) {
+                if let Some((x, y)) = drop.head() {
+                    if x >= 0 && y >= 0 && x < width && y < height {
+                        let (x, y) = (x as u16, y as u16);
+                        heads.push(Head { x, y, layer });
+                    }
+                }
+            }
+        }
+    }
+
+    fn population(&self) -> usize {
+        self.drops.len()
+    }
+}
+
+/// Columns per row drops move sideways because of `--angle`.
+fn slope(settings: &Settings) -> f32 {
+    settings.angle.to_radians().tan()
+}
+
+/// Expected spawns per frame that keep `density` of a strip `cross` cells
+/// wide and `travel` long covered.
+///
+/// A drop lights `length` cells of every row it passes for `speed` frames
+/// each, so by Little's law the average number of lit cells is
+/// `rate * height * length * speed`. Solving for a target of
+/// `density * width * height` cancels out the height entirely, and since
+/// both sides are counted in frames the result is frame-rate independent.
+/// (Rows and columns swap roles when the rain runs sideways.)
+///
+/// Mirroring draws every drop twice, so only half as many are needed.
+fn spawn_rate(settings: &Settings, density: f64, travel: u16, cross: u16) -> f64 {
+    let s = settings;
+    let (min_length, max_length) = drop::lengths(s, travel);
+    let mean_length = s.length_distribution.mean(min_length as f64, max_length as f64);
+    let mean_speed = s.speed_distribution.mean(s.min_speed as f64, s.max_speed as f64);
+    let twins = if s.mirror.is_some() { 2.0 } else { 1.0 };
+    density * cross as f64 / (mean_length * mean_speed * twins).max(1.0)
+}
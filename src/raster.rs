@@ -0,0 +1,168 @@
+//! Drawing frames as images, for the exporters.
+
+use ab_glyph::{Font, FontVec, PxScale, ScaleFont};
+use image::{Rgb, RgbImage};
+
+use crate::{frame::Frame, platform::Color};
+
+/// Background wherever a cell leaves the terminal's own.
+const BACKGROUND: [u8; 3] = [0, 0, 0];
+
+/// Turns frames into images, each terminal cell a `cell_width` x
+/// `cell_height` block of pixels. Glyphs are drawn with `font` if there is
+/// one and it has them, and as solid blocks otherwise.
+pub struct Rasterizer {
+    cell_width: u32,
+    cell_height: u32,
+    font: Option<FontVec>,
+}
+
+impl Rasterizer {
+    pub fn new(cell_width: u32, cell_height: u32, font: Option<FontVec>) -> Self {
+        Rasterizer {
+            cell_width: cell_width.max(1),
+            cell_height: cell_height.max(1),
+            font,
+        }
+    }
+
+    /// Load a TrueType or OpenType font to draw glyphs with.
+    pub fn load_font(path: &str) -> Result<FontVec, String> {
+        let data = std::fs::read(path).map_err(|err| err.to_string())?;
+        FontVec::try_from_vec(data).map_err(|err| err.to_string())
+    }
+
+    pub fn render(&self, frame: &Frame) -> RgbImage {
+        let (cw, ch) = (self.cell_width, self.cell_height);
+        let mut image = RgbImage::from_pixel(
+            frame.width() as u32 * cw,
+            frame.height() as u32 * ch,
+            Rgb(BACKGROUND),
+        );
+        for (idx, glyph) in frame.glyphs().iter().enumerate() {
+            let x = (idx % frame.width() as usize) as u32 * cw;
+            let y = (idx / frame.width() as usize) as u32 * ch;
+            if let Some(bg) = rgb(glyph.bg) {
+                fill(&mut image, x, y, cw, ch, bg);
+            }
+            if glyph.ch == ' ' {
+                continue;
+            }
+            let fg = rgb(glyph.fg).unwrap_or([255; 3]);
+            if !self.draw_glyph(&mut image, x, y, glyph.ch, fg) {
+                // A block a little inside the cell, so neighbours stay apart
+                let (inset_x, inset_y) = (cw / 6, ch / 8);
+                fill(
+                    &mut image,
+                    x + inset_x,
+                    y + inset_y,
+                    cw - 2 * inset_x,
+                    ch - 2 * inset_y,
+                    fg,
+                );
+            }
+        }
+        image
+    }
+
+    /// Draw `ch` from the font into the cell at (`x`, `y`). Returns false if
+    /// there's no font or it lacks the character.
+    fn draw_glyph(&self, image: &mut RgbImage, x: u32, y: u32, ch: char, fg: [u8; 3]) -> bool {
+        let Some(font) = &self.font else {
+            return false;
+        };
+        let id = font.glyph_id(ch);
+        if id.0 == 0 {
+            return false;
+        }
+        let scaled = font.as_scaled(PxScale::from(self.cell_height as f32));
+        // Center the glyph across the cell and sit it on the baseline
+        let left = (self.cell_width as f32 - scaled.h_advance(id)) / 2.0;
+        let glyph = id.with_scale_and_position(
+            scaled.scale(),
+            ab_glyph::point(x as f32 + left, y as f32 + scaled.ascent()),
+        );
+        let Some(outline) = font.outline_glyph(glyph) else {
+            return true; // Nothing to draw, but the font does have it
+        };
+        let bounds = outline.px_bounds();
+        let (right, bottom) = (x + self.cell_width, y + self.cell_height);
+        outline.draw(|gx, gy, coverage| {
+            let px = bounds.min.x as i32 + gx as i32;
+            let py = bounds.min.y as i32 + gy as i32;
+            if px < x as i32 || py < y as i32 || px >= right as i32 || py >= bottom as i32 {
+                return;
+            }
+            let pixel = image.get_pixel_mut(px as u32, py as u32);
+            for (channel, &target) in pixel.0.iter_mut().zip(&fg) {
+                *channel = (*channel as f32 + (target as f32 - *channel as f32) * coverage) as u8;
+            }
+        });
+        true
+    }
+}
+
+fn fill(image: &mut RgbImage, x: u32, y: u32, width: u32, height: u32, color: [u8; 3]) {
+    for py in y..y + height {
+        for px in x..x + width {
+            image.put_pixel(px, py, Rgb(color));
+        }
+    }
+}
+
+/// `color` as RGB, using the xterm palette for indexed colors. None for
+/// the terminal's default.
+fn rgb(color: Color) -> Option<[u8; 3]> {
+    let index = match color {
+        Color::Reset => return None,
+        Color::Rgb { r, g, b } => return Some([r, g, b]),
+        Color::AnsiValue(value) => value,
+        Color::Black => 0,
+        Color::DarkRed => 1,
+        Color::DarkGreen => 2,
+        Color::DarkYellow => 3,
+        Color::DarkBlue => 4,
+        Color::DarkMagenta => 5,
+        Color::DarkCyan => 6,
+        Color::Grey => 7,
+        Color::DarkGrey => 8,
+        Color::Red => 9,
+        Color::Green => 10,
+        Color::Yellow => 11,
+        Color::Blue => 12,
+        Color::Magenta => 13,
+        Color::Cyan => 14,
+        Color::White => 15,
+    };
+    const BASE: [[u8; 3]; 16] = [
+        [0, 0, 0],
+        [205, 0, 0],
+        [0, 205, 0],
+        [205, 205, 0],
+        [0, 0, 238],
+        [205, 0, 205],
+        [0, 205, 205],
+        [229, 229, 229],
+        [127, 127, 127],
+        [255, 0, 0],
+        [0, 255, 0],
+        [255, 255, 0],
+        [92, 92, 255],
+        [255, 0, 255],
+        [0, 255, 255],
+        [255, 255, 255],
+    ];
+    Some(match index {
+        0..=15 => BASE[index as usize],
+        16..=231 => {
+            // 6x6x6 color cube
+            let level = |v: u8| if v == 0 { 0 } else { 55 + v * 40 };
+            let i = index - 16;
+            [level(i / 36), level(i / 6 % 6), level(i % 6)]
+        }
+        _ => {
+            let grey = 8 + (index - 232) * 10;
+            [grey; 3]
+        }
+    })
+}
@@ -0,0 +1,83 @@
+//! `--record`: write a session to an asciicast v2 file that asciinema can
+//! play back or upload, without running under an external recorder.
+
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    time::{Instant, SystemTime, UNIX_EPOCH},
+};
+
+/// An asciicast v2 file being written: a JSON header line, then one
+/// `[seconds, "o", output]` event line per frame.
+pub struct Recorder {
+    file: BufWriter<File>,
+    started: Instant,
+}
+
+impl Recorder {
+    /// Start a recording of a `cols` x `rows` terminal at `path`.
+    pub fn create(path: &str, cols: u16, rows: u16) -> std::io::Result<Self> {
+        let mut file = BufWriter::new(File::create(path)?);
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |since| since.as_secs());
+        writeln!(
+            file,
+            r#"{{"version": 2, "width": {}, "height": {}, "timestamp": {}, "env": {{"TERM": "xterm-256color"}}}}"#,
+            cols, rows, timestamp
+        )?;
+        Ok(Recorder {
+            file,
+            started: Instant::now(),
+        })
+    }
+
+    fn event(&mut self, code: &str, data: &str) -> std::io::Result<()> {
+        let time = self.started.elapsed().as_secs_f64();
+        writeln!(
+            self.file,
+            "[{:.6}, \"{}\", {}]",
+            time,
+            code,
+            json_string(data)
+        )
+    }
+
+    /// Record what was just written to the terminal.
+    pub fn output(&mut self, bytes: &[u8]) -> std::io::Result<()> {
+        if bytes.is_empty() {
+            return Ok(());
+        }
+        self.event("o", &String::from_utf8_lossy(bytes))
+    }
+
+    /// Record the terminal changing size.
+    pub fn resize(&mut self, cols: u16, rows: u16) -> std::io::Result<()> {
+        self.event("r", &format!("{}x{}", cols, rows))
+    }
+
+    pub fn finish(mut self) -> std::io::Result<()> {
+        self.file.flush()
+    }
+}
+
+/// `text` as a quoted JSON string.
+fn json_string(text: &str) -> String {
+    let mut quoted = String::with_capacity(text.len() + 2);
+    quoted.push('"');
+    for ch in text.chars() {
+        match ch {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            ch if (ch as u32) < 0x20 || ch == '\x7f' => {
+                quoted.push_str(&format!("\\u{:04x}", ch as u32))
+            }
+            ch => quoted.push(ch),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
@@ -0,0 +1,202 @@
+
+use crate::{
+    buffer::Cell,
+    color::{brighten, dim},
+    platform::Color,
+};
+
+/// Braille dot bits for each sub-pixel of a 2x4 cell, indexed `[y][x]`.
+const BRAILLE_DOTS: [[u32; 2]; 4] = [[0x01, 0x08], [0x02, 0x10], [0x04, 0x20], [0x40, 0x80]];
+
+/// One terminal cell as it should appear on screen.
+#[derive(Clone, Copy, PartialEq)]
+pub struct Glyph {
+    pub ch: char,
+    pub fg: Color,
+    pub bg: Color, // Color::Reset leaves the terminal's own background
+    pub style: Style,
+}
+
+impl Glyph {
+    pub const BLANK: Glyph = Glyph {
+        ch: ' ',
+        fg: Color::Black,
+        bg: Color::Reset,
+        style: Style::Plain,
+    };
+
+    pub fn new(ch: char, fg: Color) -> Self {
+        Glyph {
+            ch,
+            fg,
+            bg: Color::Reset,
+            style: Style::Plain,
+        }
+    }
+}
+
+/// Text attributes a glyph can carry besides its colors, `--head-style`.
+#[derive(Clone, Copy, PartialEq)]
+pub enum Style {
+    Plain,
+    Underline,
+    Blink, // Slow blink, where the terminal does it at all
+}
+
+impl Style {
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "plain" | "none" => Some(Self::Plain),
+            "underline" => Some(Self::Underline),
+            "blink" => Some(Self::Blink),
+            _ => None,
+        }
+    }
+}
+
+/// Axis `--mirror` reflects the rain across, through the screen's center.
+#[derive(Clone, Copy, PartialEq)]
+pub enum Mirror {
+    Vertical,   // Left half mirrors the right
+    Horizontal, // Top half mirrors the bottom
+}
+
+impl Mirror {
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "vertical" => Some(Self::Vertical),
+            "horizontal" => Some(Self::Horizontal),
+            _ => None,
+        }
+    }
+
+    /// The cell opposite (`x`, `y`) on a `width` x `height` grid.
+    pub fn reflect(self, x: u16, y: u16, width: u16, height: u16) -> (u16, u16) {
+        match self {
+            Self::Vertical => (width.saturating_sub(x + 1), y),
+            Self::Horizontal => (x, height.saturating_sub(y + 1)),
+        }
+    }
+}
+
+/// How the simulation grid maps onto terminal cells.
+#[derive(Clone, Copy, PartialEq)]
+pub enum RenderMode {
+    /// One simulation cell per terminal cell, drawn with the drop's glyphs.
+    Text,
+    /// Two simulation rows per terminal row, drawn with `▀`/`▄` so the top
+    /// and bottom halves can take independent colors.
+    HalfBlock,
+    /// A 2x4 grid of simulation cells per terminal cell, drawn as braille
+    /// dots for a fine-grained pixel rain. Each cell takes the color of its
+    /// brightest dot.
+    Braille,
+}
+
+impl RenderMode {
+    /// Simulation cells per terminal cell, horizontally and vertically.
+    pub fn scale(self) -> (u16, u16) {
+        match self {
+            Self::Text => (1, 1),
+            Self::HalfBlock => (1, 2),
+            Self::Braille => (2, 4),
+        }
+    }
+
+    /// Turn the simulation grid, each cell of it from `cell`, into a
+    /// row-major frame of terminal cells. `background` supplies what shows
+    /// through cells with nothing lit.
+    pub fn compose(
+        self,
+        cell: impl Fn(u16, u16) -> Cell,
+        cols: u16,
+        rows: u16,
+        background: impl Fn(u16, u16) -> Glyph,
+    ) -> Vec<Glyph> {
+        let mut frame = Vec::with_capacity(cols as usize * rows as usize);
+        for y in 0..rows {
+            for x in 0..cols {
+                let glyph = match self {
+                    Self::Text => {
+                        let cell = cell(x, y);
+                        cell.is_lit().then(|| Glyph::new(cell.ch, cell.shade()))
+                    }
+                    Self::HalfBlock => {
+                        let top = cell(x, y * 2);
+                        let bottom = cell(x, y * 2 + 1);
+                        match (top.is_lit(), bottom.is_lit()) {
+                            (false, false) => None,
+                            (true, false) => Some(Glyph::new('▀', top.shade())),
+                            (false, true) => Some(Glyph::new('▄', bottom.shade())),
+                            (true, true) => Some(Glyph {
+                                ch: '▀',
+                                fg: top.shade(),
+                                bg: bottom.shade(),
+                                style: Style::Plain,
+                            }),
+                        }
+                    }
+                    Self::Braille => braille(&cell, x, y),
+                };
+                frame.push(glyph.unwrap_or_else(|| background(x, y)));
+            }
+        }
+        frame
+    }
+}
+
+fn braille(cell: impl Fn(u16, u16) -> Cell, x: u16, y: u16) -> Option<Glyph> {
+    let mut dots = 0;
+    let mut brightest: Option<Cell> = None;
+    for (dy, row) in BRAILLE_DOTS.iter().enumerate() {
+        for (dx, bit) in row.iter().enumerate() {
+            let lit = cell(x * 2 + dx as u16, y * 4 + dy as u16);
+            if lit.is_lit() {
+                dots |= bit;
+                if brightest.is_none_or(|b| lit.intensity > b.intensity) {
+                    brightest = Some(lit);
+                }
+            }
+        }
+    }
+    let ch = char::from_u32(0x2800 + dots)?;
+    brightest.map(|cell| Glyph::new(ch, cell.shade()))
+}
+
+/// Light up glyphs within `radius` columns of terminal cell (`x`, `y`),
+/// most at the middle. Rows count double, being about twice as tall.
+pub fn spotlight(frame: &mut [Glyph], cols: u16, rows: u16, x: u16, y: u16, radius: f32) {
+    let reach = radius.ceil() as i32;
+    for dy in -reach / 2..=reach / 2 {
+        for dx in -reach..=reach {
+            let (gx, gy) = (x as i32 + dx, y as i32 + dy);
+            if gx < 0 || gy < 0 || gx >= cols as i32 || gy >= rows as i32 {
+                continue;
+            }
+            let distance = ((dx * dx + 4 * dy * dy) as f32).sqrt();
+            if distance < radius {
+                let glyph = &mut frame[gy as usize * cols as usize + gx as usize];
+                glyph.fg = brighten(glyph.fg, (1.0 - distance / radius) * 0.7);
+            }
+        }
+    }
+}
+
+/// Tint the background around a drop head at terminal cell (`x`, `y`) so
+/// the bright head seems to bloom. Cells that already have a background
+/// (e.g. half blocks) are left alone.
+pub fn glow(frame: &mut [Glyph], cols: u16, rows: u16, x: u16, y: u16, color: Color) {
+    for dy in -1i32..=1 {
+        for dx in -1i32..=1 {
+            let (gx, gy) = (x as i32 + dx, y as i32 + dy);
+            if gx < 0 || gy < 0 || gx >= cols as i32 || gy >= rows as i32 {
+                continue;
+            }
+            let glyph = &mut frame[gy as usize * cols as usize + gx as usize];
+            if glyph.bg == Color::Reset {
+                let strength = if dx == 0 && dy == 0 { 0.25 } else { 0.12 };
+                glyph.bg = dim(color, strength);
+            }
+        }
+    }
+}
@@ -0,0 +1,45 @@
+use std::time::Duration;
+
+use crate::{
+    platform::Instant,
+    settings::Settings,
+};
+
+/// How long the wipe takes to cross the screen before the next effect.
+const WIPE: Duration = Duration::from_millis(1200);
+
+/// `--rotate`: the effects take turns, each wiped away down the screen
+/// before the next starts, so a display left on all day doesn't show the
+/// same animation all day.
+pub struct Rotation {
+    every: Duration,
+    next: Instant, // When the next effect starts, with the wipe just before
+}
+
+impl Rotation {
+    pub fn new(every: Duration) -> Self {
+        Rotation {
+            every,
+            next: Instant::now() + every,
+        }
+    }
+
+    /// Move `settings` on to the next effect once the wipe has crossed the
+    /// screen. True when it just changed and has to be rebuilt; a plugin
+    /// effect, which has no next, just starts over.
+    pub fn tick(&mut self, settings: &mut Settings) -> bool {
+        if Instant::now() < self.next {
+            return false;
+        }
+        self.next = Instant::now() + self.every;
+        settings.effect = settings.effect.next();
+        true
+    }
+
+    /// How far down the screen the wipe has got, 0.0 to 1.0, while it's
+    /// crossing.
+    pub fn wipe(&self) -> Option<f32> {
+        let left = self.next.saturating_duration_since(Instant::now());
+        (left < WIPE).then(|| 1.0 - left.as_secs_f32() / WIPE.as_secs_f32())
+    }
+}
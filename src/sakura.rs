@@ -0,0 +1,117 @@
+use rand::{rngs::SmallRng, Rng};
+
+use crate::{
+    buffer::PhosphorBuffer,
+    color::hsv_to_rgb,
+    effect::{sample_poisson, Effect, Weather},
+    platform::Color,
+    settings::Settings,
+};
+
+const PETALS: [char; 6] = ['✿', '❀', '*', '~', '`', ','];
+
+/// Share of the screen covered by petals at 100% density.
+const COVERAGE: f64 = 0.05;
+
+struct Petal {
+    anchor: f32, // Column the petal swings around
+    y: f32,
+    fall: f32,  // Rows per frame at the bottom of a swing
+    swing: f32, // How far either side of `anchor` the petal flutters
+    phase: f32, // Where the petal is in its swing
+    tempo: f32, // Phase advance per frame
+    ch: char,
+    color: Color,
+}
+
+impl Petal {
+    fn x(&self) -> f32 {
+        self.anchor + self.phase.sin() * self.swing
+    }
+}
+
+/// `--effect sakura`: cherry blossom petals and autumn leaves fluttering
+/// down, swinging side to side and slowing at the top of each swing like a
+/// falling leaf does.
+#[derive(Default)]
+pub struct Sakura {
+    width: u16,
+    height: u16,
+    petals: Vec<Petal>,
+}
+
+impl Sakura {
+    /// Pink through to orange, pale to saturated.
+    fn petal_color(rng: &mut impl Rng) -> Color {
+        let hue = rng.gen_range(0.90..1.08) % 1.0;
+        let (r, g, b) = hsv_to_rgb(hue, rng.gen_range(0.3..0.75), rng.gen_range(0.8..1.0));
+        Color::Rgb { r, g, b }
+    }
+
+}
+
+impl Effect for Sakura {
+    fn resize(&mut self, width: u16, height: u16) {
+        *self = Sakura {
+            width,
+            height,
+            petals: vec![],
+        };
+    }
+
+    /// Spawn, flutter, and drop off finished petals.
+    fn update(&mut self, settings: &Settings, weather: &Weather, rng: &mut SmallRng) {
+        let (wind, intensity, time) = (weather.wind, weather.intensity, weather.time);
+        if self.width == 0 || self.height == 0 {
+            return;
+        }
+
+        let mean_speed = settings.speed_distribution.mean(settings.min_speed as f64, settings.max_speed as f64) as f32;
+        let mean_fall = 1.0 / (mean_speed.max(1.0) * 2.0);
+        // Keep `COVERAGE * density` of the cells holding a petal: each one
+        // lives for `height / fall` frames
+        let target = settings.density * COVERAGE * self.width as f64 * self.height as f64;
+        let rate = target * mean_fall as f64 / self.height as f64 * intensity * time as f64;
+        let room = settings.max_drops.saturating_sub(self.petals.len());
+        for _ in 0..sample_poisson(rng, rate).min(room) {
+            let speed = settings
+                .speed_distribution
+                .sample(settings.min_speed, settings.max_speed, rng)
+                .max(1);
+            self.petals.push(Petal {
+                anchor: rng.gen_range(0.0..self.width as f32),
+                y: -1.0,
+                fall: 1.0 / (speed as f32 * 2.0) * rng.gen_range(0.7..1.3),
+                swing: rng.gen_range(1.0..4.0),
+                phase: rng.gen_range(0.0..std::f32::consts::TAU),
+                tempo: rng.gen_range(0.04..0.12),
+                ch: PETALS[rng.gen_range(0..PETALS.len())],
+                color: Self::petal_color(rng),
+            });
+        }
+
+        let (width, height) = (self.width as f32, self.height as f32);
+        self.petals.retain_mut(|petal| {
+            petal.phase += petal.tempo * time;
+            petal.anchor += wind * petal.fall * time;
+            // Fastest through the bottom of the swing, hanging at either end
+            petal.y += petal.fall * (0.4 + 0.6 * petal.phase.cos().abs()) * time;
+            let x = petal.x();
+            petal.y < height && x > -petal.swing - 1.0 && x < width + petal.swing + 1.0
+        });
+    }
+
+    fn render(&self, buffer: &mut PhosphorBuffer, _settings: &Settings) {
+        for petal in &self.petals {
+            let x = petal.x().round();
+            // Petals swing past the sides and back, so some are off screen
+            if x >= 0.0 && x < self.width as f32 && petal.y >= 0.0 {
+                buffer.stamp(x as u16, petal.y as u16, petal.ch, petal.color);
+            }
+        }
+    }
+
+    fn population(&self) -> usize {
+        self.petals.len()
+    }
+}
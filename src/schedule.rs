@@ -0,0 +1,141 @@
+use std::time::Duration;
+
+use crate::{color::ColorScheme, night::minute_of_day, platform::Instant, settings::Settings};
+
+/// How long a `flash` holds its scheme before the rain fades back.
+const FLASH: Duration = Duration::from_secs(3);
+/// How long a `message` stays up.
+const MESSAGE: Duration = Duration::from_secs(5);
+
+/// One field of a time of day: any value, every so many, or just the one.
+#[derive(Clone, Copy)]
+enum Field {
+    Any,
+    Every(u16),
+    At(u16),
+}
+
+impl Field {
+    fn parse(s: &str, below: u16) -> Option<Self> {
+        match s.trim() {
+            "*" => Some(Self::Any),
+            s => match s.strip_prefix("*/") {
+                Some(every) => every.parse().ok().filter(|&n| n > 0 && n < below).map(Self::Every),
+                None => s.parse().ok().filter(|&n| n < below).map(Self::At),
+            },
+        }
+    }
+
+    fn matches(self, value: u16) -> bool {
+        match self {
+            Self::Any => true,
+            Self::Every(n) => value.is_multiple_of(n),
+            Self::At(at) => value == at,
+        }
+    }
+}
+
+/// What happens when an entry comes due.
+enum Event {
+    Burst,              // A dense wave of drops
+    Flash(ColorScheme), // The rain in another scheme for a moment
+    Message(String),    // Text in the middle of the screen
+}
+
+/// `--at`: things that happen at times of day by the local clock, so the
+/// rain can chime the hours like a clock would. Each entry is a time, then
+/// what to do, e.g.
+///
+/// ```text
+/// hourly burst
+/// *:30 flash rainbow
+/// */2:15 burst
+/// 17:00 message Time to go home
+/// ```
+///
+/// Times are `HOUR:MINUTE`, where either can be `*` for any or `*/N` for
+/// every Nth, as in cron; `hourly` is `*:00`.
+pub struct Schedule {
+    entries: Vec<(Field, Field, Event)>,
+    minute: u16, // Minute of the day last looked at, so each fires once
+    flash: Option<(ColorScheme, ColorScheme, Instant)>, // Scheme flashed to, the one before, and when it ends
+    message: Option<(String, Instant)>, // Message on screen and when it comes down
+}
+
+impl Schedule {
+    pub fn new() -> Self {
+        Schedule {
+            entries: vec![],
+            minute: minute_of_day(),
+            flash: None,
+            message: None,
+        }
+    }
+
+    /// Add an entry, `WHEN EVENT`.
+    pub fn add(&mut self, entry: &str) -> Result<(), String> {
+        let (when, event) = entry.trim().split_once(' ').ok_or("expected a time and an event")?;
+        let when = if when == "hourly" { "*:00" } else { when };
+        let (hour, minute) = when
+            .split_once(':')
+            .and_then(|(hour, minute)| Some((Field::parse(hour, 24)?, Field::parse(minute, 60)?)))
+            .ok_or_else(|| format!("invalid time '{}': expected HH:MM, with * or */N for either", when))?;
+        let (name, arg) = event.trim().split_once(' ').unwrap_or((event.trim(), ""));
+        let event = match (name, arg.trim()) {
+            ("burst", "") => Event::Burst,
+            ("flash", "") => Event::Flash(ColorScheme::Rainbow),
+            ("flash", scheme) => Event::Flash(
+                ColorScheme::from_str(scheme).ok_or_else(|| format!("unknown color scheme '{}'", scheme))?,
+            ),
+            ("message", "") => return Err("message needs some text".to_string()),
+            ("message", text) => Event::Message(text.to_string()),
+            _ => return Err(format!("unknown event '{}': expected burst, flash or message", event.trim())),
+        };
+        self.entries.push((hour, minute, event));
+        Ok(())
+    }
+
+    /// Play the entries due this minute, if it's a new one, and end any
+    /// flash or message that's been up long enough. Returns whether to
+    /// start a burst.
+    pub fn tick(&mut self, settings: &mut Settings) -> bool {
+        let now = Instant::now();
+        if let Some((flashed, before, until)) = self.flash {
+            if now >= until {
+                // Unless it's been changed since, in which case that stays
+                if settings.color_scheme == flashed {
+                    settings.color_scheme = before;
+                }
+                self.flash = None;
+            }
+        }
+        if matches!(&self.message, Some((_, until)) if now >= *until) {
+            self.message = None;
+        }
+
+        let minute = minute_of_day();
+        if minute == self.minute {
+            return false;
+        }
+        self.minute = minute;
+        let mut burst = false;
+        let due = self.entries.iter().filter(|(hour, min, _)| hour.matches(minute / 60) && min.matches(minute % 60));
+        for (_, _, event) in due {
+            match event {
+                Event::Burst => burst = true,
+                Event::Flash(scheme) => {
+                    let before = self.flash.map_or(settings.color_scheme, |(_, before, _)| before);
+                    settings.color_scheme = *scheme;
+                    self.flash = Some((*scheme, before, now + FLASH));
+                }
+                Event::Message(text) => self.message = Some((text.clone(), now + MESSAGE)),
+            }
+        }
+        burst
+    }
+
+    /// The message that should be on screen right now, if any.
+    pub fn message(&self) -> Option<&str> {
+        self.message.as_ref().map(|(text, _)| text.as_str())
+    }
+}
@@ -0,0 +1,64 @@
+//! `matrix colors`: every color scheme by name, each with a swatch of its
+//! colors from a drop's head down to its tail, to pick one by eye.
+
+use std::io::Write;
+
+#[cfg(feature = "plugins")]
+use crate::plugin;
+use crate::{
+    cli,
+    color::{ColorScheme, Sweep},
+    dither,
+    frame::Frame,
+    platform::Color,
+    render::Glyph,
+};
+
+/// The color of glyph `i` of a drop `length` long in column `x`.
+type Swatch = Box<dyn Fn(usize, usize, u16) -> Color>;
+
+/// Run `matrix colors [--width N] [OPTIONS]`, printing the list to stdout.
+/// `--colors` shows the swatches as a terminal with fewer colors would.
+pub fn run(args: &[String]) -> std::io::Result<()> {
+    let mut width: u16 = 32;
+    let mut options = vec![];
+    let mut i = 0;
+    while i < args.len() {
+        match (args[i].as_str(), args.get(i + 1)) {
+            ("--width", Some(val)) => {
+                width = val.parse().ok().filter(|&n| n > 0).unwrap_or(width);
+                i += 2;
+            }
+            _ => {
+                options.push(args[i].clone());
+                i += 1;
+            }
+        }
+    }
+    let depth = cli::parse(&options).colors;
+
+    let schemes = ColorScheme::ALL.into_iter().map(|scheme| {
+        let swatch: Swatch = Box::new(move |i, length, x| scheme.get_colors(i, length, x, Sweep::Horizontal));
+        (scheme.name().to_string(), swatch)
+    });
+    // Then the ones plugins add
+    #[cfg(feature = "plugins")]
+    let schemes = schemes.chain(plugin::discover().into_iter().filter(|plugin| plugin.has_color()).map(|plugin| {
+        let name = format!("{} (plugin)", plugin.name);
+        let swatch: Swatch = Box::new(move |i, length, x| plugin.color(i, length, x).unwrap_or(Color::Reset));
+        (name, swatch)
+    }));
+    let schemes: Vec<(String, Swatch)> = schemes.collect();
+
+    let mut out = std::io::stdout().lock();
+    let pad = schemes.iter().map(|(name, _)| name.chars().count()).max().unwrap_or(0);
+    for (name, swatch) in &schemes {
+        // Head on the left, tail on the right, a cell a glyph
+        let mut row: Vec<Glyph> = (0..width)
+            .map(|x| Glyph::new('█', swatch(x as usize, width as usize, x)))
+            .collect();
+        dither::apply(&mut row, width, depth);
+        writeln!(out, "{:pad$}  {}", name, Frame::new(width, 1, row).to_ansi_string())?;
+    }
+    Ok(())
+}
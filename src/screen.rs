@@ -0,0 +1,66 @@
+use std::io::Write;
+
+use crate::{
+    platform::{move_to, set_bg, set_fg, set_style, Color},
+    render::{Glyph, Style},
+};
+
+/// What's currently shown on the terminal, so each frame only the cells that
+/// actually changed get written.
+pub struct Screen {
+    width: u16,
+    shown: Vec<Option<Glyph>>, // None = unknown, always redraw
+}
+
+impl Screen {
+    pub fn new(width: u16, height: u16) -> Self {
+        Screen {
+            width,
+            shown: vec![None; width as usize * height as usize],
+        }
+    }
+
+    pub fn resize(&mut self, width: u16, height: u16) {
+        *self = Screen::new(width, height);
+    }
+
+    /// Write every cell of `frame` (row-major, `width * height` cells) that
+    /// differs from what the terminal is showing.
+    pub fn draw(&mut self, out: &mut impl Write, frame: &[Glyph]) -> std::io::Result<()> {
+        let width = self.width as usize;
+        // Colors the terminal is currently set to, to skip redundant changes
+        let mut fg = None;
+        let mut bg = None;
+        // Underline and blink show on blanks too, so they're always kept right
+        let mut style = Style::Plain;
+        for (idx, &glyph) in frame.iter().enumerate() {
+            if self.shown[idx] == Some(glyph) {
+                continue;
+            }
+            let (x, y) = ((idx % width) as u16, (idx / width) as u16);
+            move_to(out, x, y)?;
+            if bg != Some(glyph.bg) {
+                set_bg(out, glyph.bg)?;
+                bg = Some(glyph.bg);
+            }
+            if glyph.ch != ' ' && fg != Some(glyph.fg) {
+                set_fg(out, glyph.fg)?;
+                fg = Some(glyph.fg);
+            }
+            if style != glyph.style {
+                set_style(out, glyph.style)?;
+                style = glyph.style;
+            }
+            write!(out, "{}", glyph.ch)?;
+            self.shown[idx] = Some(glyph);
+        }
+        // Leave the background clean for anything drawn on top afterwards
+        if bg.is_some_and(|bg| bg != Color::Reset) {
+            set_bg(out, Color::Reset)?;
+        }
+        if style != Style::Plain {
+            set_style(out, Style::Plain)?;
+        }
+        Ok(())
+    }
+}
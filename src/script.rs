@@ -0,0 +1,125 @@
+use serde::Deserialize;
+use std::time::Duration;
+
+use crate::{color::ColorScheme, platform::Instant, settings::Settings};
+
+/// One step of a `--script` timeline: at `at` seconds, change whichever
+/// settings are given and optionally show a message.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Cue {
+    at: f64,
+    color: Option<String>,
+    density: Option<f64>,    // Percent, like `--density`
+    speed: Option<u64>,      // Frame delay in ms, like `--speed`
+    length: Option<usize>,   // Max drop length, like `--length`
+    wind: Option<f32>,       // -10..10, like `--wind`
+    message: Option<String>, // Text shown in the middle of the screen
+    #[serde(default = "default_hold")]
+    hold: f64, // Seconds the message stays up
+}
+
+fn default_hold() -> f64 {
+    5.0
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Script {
+    loop_after: Option<f64>, // Start over this many seconds in, None = play once
+    #[serde(default, rename = "cue")]
+    cues: Vec<Cue>,
+}
+
+/// A declarative `--script` timeline, e.g.
+///
+/// ```toml
+/// loop_after = 90
+///
+/// [[cue]]
+/// at = 0
+/// color = "green"
+/// density = 20
+///
+/// [[cue]]
+/// at = 30
+/// color = "rainbow"
+/// density = 90
+/// speed = 15
+///
+/// [[cue]]
+/// at = 60
+/// message = "Wake up, Neo..."
+/// ```
+pub struct Timeline {
+    script: Script,
+    started: Instant,
+    next: usize, // First cue that hasn't played yet this time round
+    message: Option<(String, Instant)>, // Message on screen and when it comes down
+}
+
+impl Timeline {
+    /// Read and check a script file.
+    pub fn load(path: &str) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path).map_err(|err| err.to_string())?;
+        let mut script: Script = toml::from_str(&text).map_err(|err| err.to_string())?;
+        for cue in &script.cues {
+            if let Some(color) = &cue.color {
+                if ColorScheme::from_str(color).is_none() {
+                    return Err(format!("unknown color scheme \"{}\"", color));
+                }
+            }
+        }
+        script.cues.sort_by(|a, b| a.at.total_cmp(&b.at));
+        Ok(Timeline {
+            script,
+            started: Instant::now(),
+            next: 0,
+            message: None,
+        })
+    }
+
+    /// Play every cue that's come due.
+    pub fn tick(&mut self, settings: &mut Settings) {
+        let now = Instant::now();
+        if let Some(period) = self.script.loop_after {
+            if now.duration_since(self.started).as_secs_f64() >= period.max(1.0) {
+                self.started = now;
+                self.next = 0;
+            }
+        }
+        if matches!(&self.message, Some((_, until)) if now >= *until) {
+            self.message = None;
+        }
+
+        let elapsed = now.duration_since(self.started).as_secs_f64();
+        let cues = &self.script.cues;
+        while let Some(cue) = cues.get(self.next).filter(|cue| cue.at <= elapsed) {
+            if let Some(scheme) = cue.color.as_deref().and_then(ColorScheme::from_str) {
+                settings.color_scheme = scheme;
+            }
+            if let Some(pct) = cue.density {
+                settings.density = (pct / 100.0).clamp(0.01, 1.0);
+            }
+            if let Some(ms) = cue.speed {
+                settings.frame_delay = Duration::from_millis(ms.clamp(5, 1000));
+            }
+            if let Some(length) = cue.length {
+                settings.max_length = length.max(settings.min_length);
+            }
+            if let Some(wind) = cue.wind {
+                settings.wind = (wind / 10.0).clamp(-1.0, 1.0);
+            }
+            if let Some(text) = &cue.message {
+                let until = now + Duration::from_secs_f64(cue.hold.max(0.0));
+                self.message = Some((text.clone(), until));
+            }
+            self.next += 1;
+        }
+    }
+
+    /// The message that should be on screen right now, if any.
+    pub fn message(&self) -> Option<&str> {
+        self.message.as_ref().map(|(text, _)| text.as_str())
+    }
+}
@@ -0,0 +1,234 @@
+//! `matrix serve`: stream the rain to anyone who telnets in, in the spirit
+//! of towel.blinkenlights.nl. Every client gets its own rain, sized to the
+//! window their telnet client reports.
+
+use std::{
+    io::{Read, Write},
+    net::{TcpListener, TcpStream},
+    sync::mpsc::{self, Receiver, Sender},
+    thread,
+};
+
+use crate::{
+    cli,
+    matrix::Matrix,
+    pacer::Pacer,
+    platform::{KeyCode, KeyEvent, KeyModifiers},
+};
+
+// Telnet protocol bytes (RFC 854) and the options we negotiate
+const IAC: u8 = 255;
+const DONT: u8 = 254;
+const DO: u8 = 253;
+const WONT: u8 = 252;
+const WILL: u8 = 251;
+const SB: u8 = 250;
+const SE: u8 = 240;
+const ECHO: u8 = 1;
+const SUPPRESS_GO_AHEAD: u8 = 3;
+const NAWS: u8 = 31; // Negotiate About Window Size, RFC 1073
+
+/// Size used until the client tells us its own.
+const DEFAULT_SIZE: (u16, u16) = (80, 24);
+
+/// Something a client sent.
+enum Input {
+    Resize(u16, u16),
+    Key(KeyEvent),
+}
+
+/// Turns the byte stream from a telnet client into window sizes and key
+/// presses, skipping over the rest of the protocol.
+#[derive(Default)]
+struct TelnetParser {
+    state: State,
+    sub: Vec<u8>, // Subnegotiation collected so far
+}
+
+#[derive(Default)]
+enum State {
+    #[default]
+    Data,
+    Iac,
+    Negotiation, // After WILL/WONT/DO/DONT, waiting for the option
+    Sub,
+    SubIac,
+    Escape, // After ESC, which may start an arrow key
+    Csi,    // After ESC [
+}
+
+impl TelnetParser {
+    fn feed(&mut self, byte: u8) -> Option<Input> {
+        let key = |code| Some(Input::Key(KeyEvent::new(code, KeyModifiers::NONE)));
+        match self.state {
+            State::Data | State::Escape | State::Csi if byte == IAC => {
+                self.state = State::Iac;
+                None
+            }
+            State::Data => match byte {
+                0x1b => {
+                    self.state = State::Escape;
+                    None
+                }
+                0x03 => Some(Input::Key(KeyEvent::new(
+                    KeyCode::Char('c'),
+                    KeyModifiers::CONTROL,
+                ))),
+                b'\r' => key(KeyCode::Enter),
+                0x20..=0x7e => key(KeyCode::Char(byte as char)),
+                _ => None,
+            },
+            State::Escape if byte == b'[' => {
+                self.state = State::Csi;
+                None
+            }
+            State::Escape => {
+                self.state = State::Data;
+                key(KeyCode::Esc)
+            }
+            State::Csi => {
+                self.state = State::Data;
+                match byte {
+                    b'A' => key(KeyCode::Up),
+                    b'B' => key(KeyCode::Down),
+                    b'C' => key(KeyCode::Right),
+                    b'D' => key(KeyCode::Left),
+                    _ => None,
+                }
+            }
+            State::Iac => {
+                self.state = match byte {
+                    WILL | WONT | DO | DONT => State::Negotiation,
+                    SB => {
+                        self.sub.clear();
+                        State::Sub
+                    }
+                    _ => State::Data,
+                };
+                None
+            }
+            State::Negotiation => {
+                self.state = State::Data;
+                None
+            }
+            State::Sub if byte == IAC => {
+                self.state = State::SubIac;
+                None
+            }
+            State::Sub => {
+                self.sub.push(byte);
+                None
+            }
+            State::SubIac if byte == SE => {
+                self.state = State::Data;
+                match self.sub[..] {
+                    [NAWS, w1, w0, h1, h0] => {
+                        let (cols, rows) =
+                            (u16::from_be_bytes([w1, w0]), u16::from_be_bytes([h1, h0]));
+                        (cols > 0 && rows > 0).then_some(Input::Resize(cols, rows))
+                    }
+                    _ => None,
+                }
+            }
+            State::SubIac => {
+                // An escaped 255 inside the subnegotiation
+                self.sub.push(byte);
+                self.state = State::Sub;
+                None
+            }
+        }
+    }
+}
+
+/// Parse what the client sends on its own thread, so the animation never
+/// waits on it.
+fn read_input(mut stream: TcpStream, inputs: Sender<Input>) {
+    let mut parser = TelnetParser::default();
+    let mut buf = [0; 256];
+    while let Ok(n @ 1..) = stream.read(&mut buf) {
+        for &byte in &buf[..n] {
+            if let Some(input) = parser.feed(byte) {
+                if inputs.send(input).is_err() {
+                    return;
+                }
+            }
+        }
+    }
+}
+
+/// Stream rain to one client until they quit or hang up.
+fn serve_client(mut stream: TcpStream, options: &[String]) -> std::io::Result<()> {
+    stream.set_nodelay(true)?;
+    let (sender, inputs): (Sender<Input>, Receiver<Input>) = mpsc::channel();
+    let reader = stream.try_clone()?;
+    thread::spawn(move || read_input(reader, sender));
+
+    // We echo nothing and want keys as they're pressed, and the window size
+    stream.write_all(&[IAC, WILL, ECHO, IAC, WILL, SUPPRESS_GO_AHEAD, IAC, DO, NAWS])?;
+    stream.write_all(b"\x1b[?25l\x1b[2J")?;
+
+    let (cols, rows) = DEFAULT_SIZE;
+    let mut matrix = Matrix::with_size(cli::parse(options), cols, rows);
+    let mut out = vec![];
+    let mut pacer = Pacer::new();
+    'frames: loop {
+        for input in inputs.try_iter() {
+            match input {
+                Input::Resize(cols, rows) => {
+                    matrix.resize(cols, rows);
+                    stream.write_all(b"\x1b[2J")?;
+                }
+                Input::Key(key) => {
+                    if !matrix.handle_key(key) {
+                        break 'frames;
+                    }
+                }
+            }
+        }
+        out.clear();
+        for _ in 1..pacer.due(matrix.frame_delay()) {
+            matrix.step();
+        }
+        matrix.draw(&mut out)?;
+        stream.write_all(&out)?;
+        pacer.wait();
+    }
+
+    stream.write_all(b"\x1b[0m\x1b[2J\x1b[H\x1b[?25h")?;
+    Ok(())
+}
+
+/// Run `matrix serve [--port PORT] [OPTIONS]`: accept telnet connections
+/// forever, each with its own rain made from the same options.
+pub fn run(args: &[String]) -> std::io::Result<()> {
+    let mut port: u16 = 2323;
+    let mut options = vec![];
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--port" => {
+                if let Some(val) = args.get(i + 1) {
+                    port = val.parse().unwrap_or(port);
+                    i += 1;
+                }
+            }
+            _ => options.push(args[i].clone()),
+        }
+        i += 1;
+    }
+    // Fail now rather than on the first connection if an option can't load
+    cli::parse(&options);
+
+    let listener = TcpListener::bind(("0.0.0.0", port))?;
+    println!("Serving the rain on telnet port {} (Ctrl+C to stop)", port);
+    for stream in listener.incoming() {
+        let Ok(stream) = stream else {
+            continue;
+        };
+        let options = options.clone();
+        thread::spawn(move || {
+            let _ = serve_client(stream, &options);
+        });
+    }
+    Ok(())
+}
@@ -0,0 +1,581 @@
+#[cfg(feature = "lua")]
+use crate::lua::LuaHooks;
+#[cfg(feature = "image")]
+use crate::picture::Picture;
+#[cfg(feature = "plugins")]
+use crate::plugin::Plugin;
+#[cfg(all(feature = "watch", not(target_arch = "wasm32")))]
+use crate::watch::ConfigWatch;
+use std::rc::Rc;
+use std::time::Duration;
+use crate::{
+    alert::Alert,
+    cli::Preset,
+    color::{ColorScheme, Sweep},
+    countdown::Countdown,
+    daylight::Daylight,
+    dither::ColorDepth,
+    drop::{Collisions, Direction, Distribution, Shimmer},
+    keymap::Keymap,
+    kiosk::Kiosk,
+    mask::Mask,
+    night::Night,
+    render::{Mirror, RenderMode, Style},
+    schedule::Schedule,
+    script::Timeline,
+};
+
+/// Which animation runs.
+#[derive(Clone, Copy, PartialEq)]
+pub enum EffectKind {
+    Rain,
+    Snow,
+    Sakura,
+}
+
+impl EffectKind {
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "rain" | "matrix" => Some(Self::Rain),
+            "snow" => Some(Self::Snow),
+            "sakura" | "leaves" => Some(Self::Sakura),
+            _ => None,
+        }
+    }
+
+    /// The effect after this one, for `--rotate`.
+    pub fn next(self) -> Self {
+        match self {
+            Self::Rain => Self::Snow,
+            Self::Snow => Self::Sakura,
+            Self::Sakura => Self::Rain,
+        }
+    }
+}
+
+/// How the rain reacts to the mouse pointer with `--cursor-effect`.
+#[derive(Clone, Copy, PartialEq)]
+pub enum CursorEffect {
+    Spotlight, // Glyphs near the pointer light up
+    Avoid,     // Drops bend around the pointer
+}
+
+impl CursorEffect {
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "spotlight" => Some(Self::Spotlight),
+            "avoid" | "avoidance" => Some(Self::Avoid),
+            _ => None,
+        }
+    }
+}
+
+/// The far half of the screen under `--split`, which runs its own color
+/// scheme and density.
+pub struct Split {
+    pub color_scheme: ColorScheme,
+    pub density: Option<f64>, // None = same as the main density
+}
+
+/// `--segments`: a very wide screen cut into panels across the rain, each
+/// spawning its own drops, so 300 columns look like a few monitors' worth
+/// rather than one stretched-out rain.
+pub struct Segments {
+    pub count: u16,
+    pub gutters: bool, // A blank column between panels
+    pub schemes: bool, // Each panel in the next color scheme along
+}
+
+impl Segments {
+    /// Parse `N`, optionally followed by `,gutters` and `,schemes`.
+    pub fn from_str(s: &str) -> Option<Self> {
+        let mut parts = s.split(',');
+        let count = parts.next()?.trim().parse().ok().filter(|count| (2..=16).contains(count))?;
+        let mut segments = Segments { count, gutters: false, schemes: false };
+        for part in parts {
+            match part.trim() {
+                "gutters" => segments.gutters = true,
+                "schemes" => segments.schemes = true,
+                _ => return None,
+            }
+        }
+        Some(segments)
+    }
+
+    /// First and last-plus-one position of panel `i` on a `cross` wide
+    /// screen, gutter not included.
+    pub fn bounds(&self, i: u16, cross: u16) -> (u16, u16) {
+        let edge = |i: u16| (i as u32 * cross as u32 / self.count as u32) as u16;
+        let end = edge(i + 1);
+        let gutter = self.gutters && i + 1 < self.count;
+        (edge(i), if gutter { end.saturating_sub(1) } else { end })
+    }
+
+    /// Which panel position `across` is in, None in a gutter or off screen.
+    pub fn panel(&self, across: f32, cross: u16) -> Option<u16> {
+        if across < 0.0 || across >= cross as f32 {
+            return None;
+        }
+        // The last panel whose start isn't past it, less its gutter
+        let across = across as u32;
+        let i = (((across + 1) * self.count as u32 - 1) / cross as u32) as u16;
+        let (start, end) = self.bounds(i, cross);
+        (across >= start as u32 && across < end as u32).then_some(i)
+    }
+}
+
+/// A rectangle of terminal cells, e.g. a `--hole` the rain stays out of.
+#[derive(Clone, Copy)]
+pub struct Rect {
+    pub x: u16,
+    pub y: u16,
+    pub width: u16,
+    pub height: u16,
+}
+
+impl Rect {
+    /// Parse `x,y,w,h`.
+    pub fn from_str(s: &str) -> Option<Self> {
+        let parts: Vec<u16> = s
+            .split(',')
+            .map(|part| part.trim().parse().ok())
+            .collect::<Option<_>>()?;
+        match parts[..] {
+            [x, y, width, height] => Some(Rect {
+                x,
+                y,
+                width,
+                height,
+            }),
+            _ => None,
+        }
+    }
+
+    pub fn contains(&self, x: u16, y: u16) -> bool {
+        x >= self.x
+            && y >= self.y
+            && x < self.x.saturating_add(self.width)
+            && y < self.y.saturating_add(self.height)
+    }
+}
+
+/// A `--dilate` zone: a rectangle where time runs at `speed` times the
+/// usual rate, so drops slow down through it, or hurry.
+#[derive(Clone, Copy)]
+pub struct Dilation {
+    pub rect: Rect,
+    pub speed: f32,
+}
+
+impl Dilation {
+    /// Parse `x,y,w,h:speed`.
+    pub fn from_str(s: &str) -> Option<Self> {
+        let (rect, speed) = s.split_once(':')?;
+        let speed = speed.trim().parse().ok().filter(|speed| (0.05..=10.0).contains(speed))?;
+        Some(Dilation { rect: Rect::from_str(rect)?, speed })
+    }
+}
+
+/// The settings the runtime keys change, as given at startup, so `r` can
+/// put them back.
+#[derive(Clone, Copy)]
+pub struct Tuning {
+    frame_delay: Duration,
+    density: f64,
+    spawns_per_frame: u32,
+    max_length: usize,
+    color_scheme: ColorScheme,
+    wind: f32,
+    direction: Direction,
+}
+
+impl Tuning {
+    /// The tuning as a line of words, for sending to `--sync-client`s.
+    pub fn words(&self) -> String {
+        format!(
+            "{} {} {} {} {} {} {}",
+            self.frame_delay.as_micros(),
+            self.density,
+            self.spawns_per_frame,
+            self.max_length,
+            self.color_scheme.name(),
+            self.wind,
+            self.direction.name()
+        )
+    }
+
+    /// Read back a line written by `words`.
+    pub fn from_words(line: &str) -> Option<Self> {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let [delay, density, spawns, length, color, wind, direction] = words[..] else {
+            return None;
+        };
+        Some(Tuning {
+            frame_delay: Duration::from_micros(delay.parse().ok()?),
+            density: density.parse().ok()?,
+            spawns_per_frame: spawns.parse().ok()?,
+            max_length: length.parse().ok()?,
+            color_scheme: ColorScheme::from_str(color)?,
+            wind: wind.parse().ok()?,
+            direction: Direction::from_str(direction)?,
+        })
+    }
+}
+
+pub struct Settings {
+    pub effect: EffectKind,    // Matrix rain, or one of the alternative animations
+    pub frame_delay: Duration, // Time per frame, lower = faster
+    pub sim_rate: Option<f64>, // Simulation steps per second, None = one a frame
+    pub density: f64,          // Target screen coverage 0.0-1.0 (default 0.4)
+    pub spawns_per_frame: u32, // Max spawns per frame (default 4)
+    pub min_length: usize,     // Min drop length (default 5)
+    pub max_length: usize,     // Max drop length (default 25)
+    pub min_speed: u8,         // Min drop speed (default 1)
+    pub max_speed: u8,         // Max drop speed, lower = faster (default 3)
+    pub length_distribution: Distribution, // How drop lengths spread between min and max
+    pub speed_distribution: Distribution,  // How drop speeds spread between min and max
+    pub max_drops: usize,      // Hard cap on concurrently active drops
+    pub storm_period: Option<u64>, // Storm cycle length in seconds, None = steady rain
+    pub demo: Option<u64>,     // Seconds per attract-mode scene, None = settings stay put
+    pub rotate: Option<Duration>, // How long each effect runs before the next takes over
+    pub wind: f32,             // Horizontal drift in columns per row, negative = left
+    pub angle: f32,            // Fall angle in degrees from vertical, positive = down-right
+    pub direction: Direction,  // Which way new drops travel
+    pub mirror: Option<Mirror>, // Reflect the rain across the screen's center, None = off
+    pub gravity: f32,          // Per-frame speed gain in steps/frame, 0 = constant speed
+    pub branch_chance: f64,    // Chance a drop splits into two children on the way
+    pub dissolve_chance: f64,  // Chance a drop evaporates in mid-air before it's through
+    pub gap_chance: f64,       // Chance a drop's trail is broken up by dark gaps
+    pub layers: bool,          // Dim, slow background drops behind the main rain
+    pub column_speeds: bool,   // Drops in the same column all fall at that column's speed
+    pub collisions: Collisions, // What a drop does when it catches up with the one ahead
+    pub shimmer: f32,          // Glyphs a drop changes per step, on average
+    pub shimmer_zone: Shimmer, // Which of its glyphs can change
+    pub charset: Option<Rc<[char]>>, // Glyphs drops are made of, None = all of them
+    pub back_charset: Option<Rc<[char]>>, // Glyphs of the --layers background drops, None = as the front's
+    pub head_glyphs: Option<Vec<char>>, // Glyphs drop heads are drawn from, None = as the trail
+    pub head_mutate: Option<f64>, // Chance a head changes glyph each step, None = with the shimmer
+    pub head_style: Style,     // Underline or blink on drop heads
+    pub backdrop: bool,        // Faint static glyph field behind everything
+    pub inverse: bool,         // Drops cut dark trails through a lit glyph field
+    pub glow: bool,            // Tint the background around drop heads
+    pub transparent: bool,     // Leave the terminal's own background, clearing rather than darkening
+    pub glitch: f64,           // Average glitches per minute, 0 = never
+    pub lightning: f64,        // Average lightning strikes per minute, 0 = never
+    pub stuck_glyphs: f64,     // Average glyphs frozen on screen per minute, 0 = never
+    pub persistence: f32,      // Brightness a vacated cell keeps per frame, 0 = instant clear
+    pub splash: Option<u16>,   // Cells a landing drop splashes to each side, None = no splash
+    pub reduced_motion: bool,  // Slow, steady rain with no flashes, for vestibular sensitivity
+    pub render_mode: RenderMode, // Glyphs, or finer sub-cell blocks
+    pub linux_console: bool,   // Only the colors and glyphs a bare Linux console can show
+    pub colors: ColorDepth,    // How many colors the terminal shows, fewer are dithered
+    pub oled: bool,            // Keep varying the whole screen so nothing burns in
+    pub night: Option<Night>,  // Hours the rain dims itself, None = same all day
+    pub night_warm: bool,      // Warmer colors at night as well
+    pub brightness: f32,       // Share of the light the screen gives off, 1.0 = full
+    pub time_of_day: Option<Daylight>, // Curve density and speed follow round the clock
+    pub split: Option<Split>,  // Second half of the screen, None = one scheme everywhere
+    pub segments: Option<Segments>, // Panels across an ultrawide screen, None = one rain
+    pub holes: Vec<Rect>,      // Terminal areas kept free of rain
+    pub dilations: Vec<Dilation>, // Areas where time runs slower or faster
+    pub mask: Option<Mask>,    // Where drops start more and less often, None = evenly
+    pub qr: Option<String>,    // Text the rain gradually draws as a QR code
+    pub script: Option<Timeline>, // Scene timeline that changes settings as it plays
+    pub schedule: Option<Schedule>, // Bursts, flashes and messages at times of day
+    pub kiosk: Option<Kiosk>,     // Lines shown one after another as big messages
+    pub countdown: Option<Countdown>, // Big time left to a moment, then a label
+    pub watermark: Option<String>, // Text set faintly into the rain near a corner
+    pub kiosk_lock: bool,         // Ignore all input but Ctrl+Alt+Q
+    pub forever: bool,            // Start over when the terminal fails rather than quit
+    pub hollywood: bool,          // Fake hacking panels flashing up over the rain
+    pub animation: bool,          // Fade in at startup and dissolve on the way out
+    pub intro: bool,              // "Wake up, Neo..." typed out before the rain
+    pub chooser: bool,            // Pick the color scheme from previews before the rain
+    pub record: Option<String>,   // asciicast file the session is written to
+    pub seed: Option<u64>,        // Fixed RNG seed for reproducible runs, None = random
+    pub sync_server: Option<String>, // Address other terminals follow this rain from
+    pub sync_client: Option<String>, // Server whose rain to show instead of our own
+    pub weather: Option<String>, // Place whose real weather the rain follows
+    pub profile: Option<String>,  // CSV file per-frame timings are logged to
+    pub low_power: Option<f64>,   // CPU ceiling in percent, None = run flat out
+    pub static_frame: bool,       // Print one frame and exit instead of animating
+    pub help: bool,               // Print the options and exit, as asked with --help
+    pub list_plugins: bool,       // Print the plugins found and exit
+    pub lines: Option<u16>,       // Rows of that frame, None = terminal height
+    #[cfg(feature = "battery")]
+    pub battery_saver: bool,      // Throttle while running on battery
+    #[cfg(feature = "http")]
+    pub serve_http: Option<String>, // Address to stream the rain from instead of drawing it
+    #[cfg(feature = "image")]
+    pub picture: Option<Picture>, // Image the rain is tinted with
+    #[cfg(feature = "lua")]
+    pub lua: Option<LuaHooks>,    // Script hooks for spawning and coloring drops
+    #[cfg(feature = "plugins")]
+    pub effect_plugin: Option<Rc<Plugin>>, // Plugin effect run instead of `effect`
+    #[cfg(feature = "plugins")]
+    pub color_plugin: Option<Rc<Plugin>>, // Plugin colors used instead of `color_scheme`
+    #[cfg(feature = "sound")]
+    pub sound: Option<f32>,       // Volume of the generated rain sounds 0-1, None = silent
+    #[cfg(all(feature = "watch", not(target_arch = "wasm32")))]
+    pub watch: Option<ConfigWatch>, // Config file followed for edits while running
+    pub color_scheme: ColorScheme,
+    pub rainbow_axis: Sweep, // Which way the rainbow scheme's hues run
+    pub crossfade: Option<(ColorScheme, f32)>, // Scheme the rain is still fading over from, and how far it's come
+    pub show_debug: bool,
+    pub show_fps: bool,
+    pub status_bar: bool, // Keep the bottom row for a line of live settings
+    pub reserve_bottom: u16, // Rows under the rain left to the shell, which keeps the keyboard
+    pub width: Option<u16>, // Columns to rain in whatever the terminal's size, None = the terminal's
+    pub height: Option<u16>, // Rows to rain in whatever the terminal's size, None = the terminal's
+    pub exit_on_any_key: bool, // Quit on the first key, like a screensaver
+    pub grace: Duration, // Keys but Ctrl+C are ignored for this long after starting
+    pub lock: Option<String>, // Passphrase that has to be typed to quit
+    pub ctrl_c: bool, // Ctrl+C quits, even when locked
+    pub while_cmd: Option<String>, // Shell command the rain lasts as long as
+    pub on_exit: Option<String>, // Shell command run once the terminal's handed back
+    pub timeout: Option<Duration>, // Quit by itself after this long
+    pub ramp: Option<Duration>, // Build up to full speed over this long, and wind down before the timeout
+    pub print_key: bool, // Print the name of the key that quit
+    pub title: bool, // Name the scheme and frame rate in the terminal's title
+    pub notify: bool, // OSC 9 progress for --timeout and a notification when it's up
+    pub alert: Alert, // Bell, notifications and command for when --timeout or --countdown is done
+    pub mouse: bool, // Clicks and drags start drops
+    pub exit_on_mouse: bool, // Quit when the mouse moves, like a screensaver
+    pub cursor_effect: Option<CursorEffect>,
+    pub reactive: bool, // Keys start drops as well as doing what they do
+    pub presets: Vec<Preset>, // The config file's presets, for cycling with Tab
+    pub keymap: Keymap, // The runtime keys, as the config file's [keys] maps them
+    pub vim: bool, // hjkl for speed and density, and Space pauses rather than quits
+    pub on_lock: bool, // Rain whenever the session's locked, per logind
+}
+
+impl Settings {
+    pub fn tuning(&self) -> Tuning {
+        Tuning {
+            frame_delay: self.frame_delay,
+            density: self.density,
+            spawns_per_frame: self.spawns_per_frame,
+            max_length: self.max_length,
+            color_scheme: self.color_scheme,
+            wind: self.wind,
+            direction: self.direction,
+        }
+    }
+
+    /// Put back what the runtime keys changed.
+    pub fn retune(&mut self, tuning: &Tuning) {
+        self.frame_delay = tuning.frame_delay;
+        self.density = tuning.density;
+        self.spawns_per_frame = tuning.spawns_per_frame;
+        // A preset's length can't undercut --min-length
+        self.max_length = tuning.max_length.max(self.min_length);
+        self.color_scheme = tuning.color_scheme;
+        self.wind = tuning.wind;
+        self.direction = tuning.direction;
+    }
+
+    /// Take on the look and feel of `fresh`, reloaded from the config file;
+    /// what's only set up at startup, like the terminal, effects and files,
+    /// stays as it is.
+    #[cfg(all(feature = "watch", not(target_arch = "wasm32")))]
+    pub fn restyle(&mut self, fresh: Settings) {
+        self.min_length = fresh.min_length;
+        self.retune(&fresh.tuning());
+        self.min_speed = fresh.min_speed;
+        self.max_speed = fresh.max_speed;
+        self.length_distribution = fresh.length_distribution;
+        self.speed_distribution = fresh.speed_distribution;
+        self.max_drops = fresh.max_drops;
+        self.angle = fresh.angle;
+        self.gravity = fresh.gravity;
+        self.branch_chance = fresh.branch_chance;
+        self.dissolve_chance = fresh.dissolve_chance;
+        self.gap_chance = fresh.gap_chance;
+        self.collisions = fresh.collisions;
+        self.shimmer = fresh.shimmer;
+        self.shimmer_zone = fresh.shimmer_zone;
+        self.charset = fresh.charset;
+        self.back_charset = fresh.back_charset;
+        self.head_glyphs = fresh.head_glyphs;
+        self.head_mutate = fresh.head_mutate;
+        self.head_style = fresh.head_style;
+        self.glow = fresh.glow;
+        self.persistence = fresh.persistence;
+        self.night_warm = fresh.night_warm;
+        self.brightness = fresh.brightness;
+        self.split = fresh.split;
+        self.segments = fresh.segments;
+        self.rainbow_axis = fresh.rainbow_axis;
+        self.presets = fresh.presets;
+        self.keymap = fresh.keymap;
+        if self.vim {
+            self.keymap.vim();
+        }
+    }
+
+    /// Color scheme at a position across the travel axis of a `width` x
+    /// `height` grid; with `--split` the far half has its own, and with
+    /// `--segments ...,schemes` each panel takes the next one along.
+    pub fn scheme_across(&self, across: f32, width: u16, height: u16) -> ColorScheme {
+        let (_, cross) = self.direction.extents(width, height);
+        if let Some(segments) = self.segments.as_ref().filter(|segments| segments.schemes) {
+            let panel = segments.panel(across, cross).unwrap_or(0) as usize;
+            let first = ColorScheme::ALL.iter().position(|&scheme| scheme == self.color_scheme).unwrap_or(0);
+            return ColorScheme::ALL[(first + panel) % ColorScheme::ALL.len()];
+        }
+        match &self.split {
+            Some(split) if across >= (cross / 2) as f32 => split.color_scheme,
+            _ => self.color_scheme,
+        }
+    }
+
+    /// How fast time runs for a drop whose head is at (`x`, `y`) on the
+    /// rain's grid: the first `--dilate` zone its terminal cell is in says,
+    /// else as usual. With `--hires` or `--braille` a cell holds several.
+    pub fn time_at(&self, x: i32, y: i32) -> f32 {
+        let (Ok(x), Ok(y)) = (u16::try_from(x), u16::try_from(y)) else {
+            return 1.0;
+        };
+        let (sx, sy) = self.render_mode.scale();
+        let (x, y) = (x / sx, y / sy);
+        self.dilations.iter().find(|zone| zone.rect.contains(x, y)).map_or(1.0, |zone| zone.speed)
+    }
+
+    /// Color scheme for cell (`x`, `y`) of a `width` x `height` grid.
+    pub fn scheme_at(&self, x: u16, y: u16, width: u16, height: u16) -> ColorScheme {
+        let across = if self.direction.is_vertical() { x } else { y };
+        self.scheme_across(across as f32, width, height)
+    }
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            effect: EffectKind::Rain,
+            frame_delay: Duration::from_millis(50), // 20 fps, slower and more relaxed
+            sim_rate: None,        // A step a frame
+            density: 0.4,          // Moderate density
+            spawns_per_frame: 4,   // Moderate coverage
+            min_length: 10,        // Longer trails for fade effect
+            max_length: 30,        // Long trails
+            min_speed: 2,          // Slower drops
+            max_speed: 4,          // Even slower variation
+            length_distribution: Distribution::Uniform, // Any length as likely as any other
+            speed_distribution: Distribution::Uniform,  // And any speed
+            max_drops: 2000,       // Plenty for ultrawide terminals
+            storm_period: None,    // Steady rain
+            demo: None,            // No wandering
+            rotate: None,          // One effect throughout
+            wind: 0.0,             // Still air
+            angle: 0.0,            // Straight down
+            direction: Direction::Down,
+            mirror: None,          // No symmetry
+            gravity: 0.0,          // Constant speed
+            branch_chance: 0.0,    // Never split
+            dissolve_chance: 0.0,  // Every drop makes it across
+            gap_chance: 0.0,       // Unbroken trails
+            layers: false,         // Single flat layer
+            column_speeds: false,  // Every drop picks its own speed
+            collisions: Collisions::Pass, // Runs through, head on top
+            shimmer: 0.5,          // A glyph every other step
+            shimmer_zone: Shimmer::Trail, // Anywhere along the drop
+            charset: None,         // Katakana, latin letters, digits and symbols
+            back_charset: None,    // The same as the front
+            head_glyphs: None,     // The same glyphs as the rest of the drop
+            head_mutate: None,     // Heads shimmer like any other glyph
+            head_style: Style::Plain, // Heads stand out by color alone
+            backdrop: false,       // Empty black background
+            inverse: false,        // Drops light up the dark
+            glow: false,           // No bloom, fewer draw calls
+            transparent: false,    // Fade all the way down
+            glitch: 0.0,           // Clean signal
+            lightning: 0.0,        // No lightning
+            stuck_glyphs: 0.0,     // Nothing ever sticks
+            persistence: 0.0,      // Crisp trails, no ghosting
+            splash: None,          // Drops just leave
+            reduced_motion: false, // Everything the options ask for
+            render_mode: RenderMode::Text,
+            linux_console: false,  // Full colors and glyphs
+            colors: ColorDepth::TrueColor, // Any RGB color
+            oled: false,           // Drawn as is
+            night: None,           // Full brightness around the clock
+            night_warm: false,     // Same colors at night
+            brightness: 1.0,       // Colors as the scheme has them
+            time_of_day: None,     // Density and speed as set
+            split: None,           // One rain across the whole screen
+            segments: None,        // No panels
+            holes: vec![],         // Rain everywhere
+            dilations: vec![],     // Time runs the same everywhere
+            mask: None,            // As heavy in every column
+            qr: None,              // No code
+            script: None,          // Settings stay as given
+            schedule: None,        // Nothing happens on the clock
+            kiosk: None,           // No messages
+            countdown: None,       // No countdown
+            watermark: None,       // Unmarked
+            kiosk_lock: false,     // Keys work
+            forever: false,        // An error ends it
+            hollywood: false,      // Just the rain
+            animation: true,       // No abrupt start or end
+            intro: false,          // Straight into the rain
+            chooser: false,        // The scheme as given
+            record: None,          // Nothing written to disk
+            seed: None,            // Different every run
+            sync_server: None,     // Nobody follows
+            sync_client: None,     // Our own rain
+            weather: None,         // Rain as set
+            profile: None,         // No timing log
+            low_power: None,       // Full frame rate
+            static_frame: false,   // Animate
+            help: false,           // Rain
+            list_plugins: false,   // Rain
+            lines: None,           // Fill the terminal
+            #[cfg(feature = "battery")]
+            battery_saver: true,   // Throttle on battery
+            #[cfg(feature = "http")]
+            serve_http: None,      // Draw to the terminal
+            #[cfg(feature = "image")]
+            picture: None,         // Scheme colors only
+            #[cfg(feature = "lua")]
+            lua: None,             // No hooks
+            #[cfg(feature = "plugins")]
+            effect_plugin: None,   // Built-in effects
+            #[cfg(feature = "plugins")]
+            color_plugin: None,    // Built-in schemes
+            #[cfg(feature = "sound")]
+            sound: None,           // Silent
+            #[cfg(all(feature = "watch", not(target_arch = "wasm32")))]
+            watch: None,           // Config read once at startup
+            color_scheme: ColorScheme::Green,
+            rainbow_axis: Sweep::Horizontal, // Across the columns
+            crossfade: None,       // Not changing schemes
+            show_debug: false,
+            show_fps: false,
+            status_bar: false,
+            reserve_bottom: 0,     // The whole terminal
+            width: None,           // As wide as the terminal
+            height: None,          // As tall as the terminal
+            exit_on_any_key: false,
+            grace: Duration::ZERO,
+            lock: None,
+            ctrl_c: true,
+            while_cmd: None,
+            on_exit: None,
+            timeout: None,
+            ramp: None,
+            print_key: false,
+            title: true,
+            notify: false,
+            alert: Alert::default(), // Nothing but the screen
+            mouse: false,
+            exit_on_mouse: false,
+            cursor_effect: None,
+            reactive: false,
+            presets: vec![],
+            keymap: Keymap::default(),
+            vim: false,
+            on_lock: false,
+        }
+    }
+}
+
@@ -0,0 +1,194 @@
+//! `matrix simulate`: run the whole update and draw loop against memory
+//! instead of a terminal, checking invariants as it goes, so CI and tests
+//! can exercise the rain headless.
+
+use std::time::{Duration, Instant};
+
+use crate::{cli, frame::Frame, matrix::Matrix, settings::Settings};
+
+/// What a simulated run did, and anything it did wrong.
+pub struct Report {
+    pub frames: usize,
+    pub width: u16,
+    pub height: u16,
+    pub peak_population: usize,
+    pub bytes: usize, // Escape output written, overlays included
+    pub elapsed: Duration,
+    /// Frames it took everything to leave once spawning stopped, None if
+    /// it never did.
+    pub drained_after: Option<usize>,
+    pub violations: Vec<String>,
+}
+
+impl Report {
+    pub fn passed(&self) -> bool {
+        self.violations.is_empty()
+    }
+}
+
+/// Problems with one frame, if any.
+fn check_frame(frame: &Frame, width: u16, height: u16) -> Option<String> {
+    if (frame.width(), frame.height()) != (width, height) {
+        return Some(format!(
+            "frame is {}x{}, expected {}x{}",
+            frame.width(),
+            frame.height(),
+            width,
+            height
+        ));
+    }
+    let cells = frame.cells().count();
+    if cells != width as usize * height as usize {
+        return Some(format!(
+            "frame has {} cells for {}x{}",
+            cells, width, height
+        ));
+    }
+    frame
+        .cells()
+        .position(|(ch, _)| ch.is_control())
+        .map(|idx| format!("control character drawn at cell {}", idx))
+}
+
+/// Note a broken invariant, keeping the list short when something goes
+/// wrong every frame.
+fn violate(report: &mut Report, frame: usize, problem: String) {
+    if report.violations.len() < 20 {
+        report
+            .violations
+            .push(format!("frame {}: {}", frame, problem));
+    }
+}
+
+/// Run `frames` frames of a `width` x `height` rain made from `settings`,
+/// then stop spawning and let it drain.
+pub fn simulate(settings: Settings, width: u16, height: u16, frames: usize) -> Report {
+    let max_drops = settings.max_drops;
+    // Plugin effects count lit cells rather than drops, so have no cap
+    #[cfg(feature = "plugins")]
+    let capped = settings.effect_plugin.is_none();
+    #[cfg(not(feature = "plugins"))]
+    let capped = true;
+    let mut matrix = Matrix::with_size(settings, width, height);
+    let mut report = Report {
+        frames,
+        width,
+        height,
+        peak_population: 0,
+        bytes: 0,
+        elapsed: Duration::ZERO,
+        drained_after: None,
+        violations: vec![],
+    };
+    let started = Instant::now();
+    let mut out = vec![];
+    for n in 1..=frames {
+        out.clear();
+        // Writing to a Vec can't fail
+        let _ = matrix.draw(&mut out);
+        report.bytes += out.len();
+
+        let frame = Frame::new(width, height, matrix.frame());
+        if let Some(problem) = check_frame(&frame, width, height) {
+            violate(&mut report, n, problem);
+        }
+        let population = matrix.population();
+        report.peak_population = report.peak_population.max(population);
+        if capped && population > max_drops {
+            violate(
+                &mut report,
+                n,
+                format!("{} drops alive, over the cap of {}", population, max_drops),
+            );
+        }
+    }
+    report.elapsed = started.elapsed();
+
+    // Everything should clear out in about the time the slowest, longest
+    // drop takes to cross the screen
+    let settings = matrix.settings_mut();
+    settings.density = 0.0;
+    settings.spawns_per_frame = 0;
+    let limit =
+        4 * (width.max(height) as usize + settings.max_length) * (settings.max_speed as usize + 2);
+    for n in 0..=limit {
+        if matrix.population() == 0 {
+            report.drained_after = Some(n);
+            break;
+        }
+        matrix.step();
+    }
+    if report.drained_after.is_none() {
+        let left = matrix.population();
+        violate(
+            &mut report,
+            frames,
+            format!(
+                "{} drops still alive {} frames after spawning stopped",
+                left, limit
+            ),
+        );
+    }
+
+    if matrix.clipped() > 0 {
+        violate(
+            &mut report,
+            frames,
+            format!("{} glyphs stamped outside the screen", matrix.clipped()),
+        );
+    }
+    report
+}
+
+/// Run `matrix simulate [--frames N] [--width N] [--height N] [OPTIONS]`,
+/// print a summary, and exit with status 1 if an invariant broke.
+pub fn run(args: &[String]) -> std::io::Result<()> {
+    let mut frames: usize = 1000;
+    let mut width: u16 = 120;
+    let mut height: u16 = 40;
+    let mut options = vec![];
+    let mut i = 0;
+    while i < args.len() {
+        let val = args.get(i + 1);
+        match (args[i].as_str(), val) {
+            ("--frames", Some(val)) => frames = val.parse().unwrap_or(frames),
+            ("--width", Some(val)) => width = val.parse().ok().filter(|&n| n > 0).unwrap_or(width),
+            ("--height", Some(val)) => {
+                height = val.parse().ok().filter(|&n| n > 0).unwrap_or(height)
+            }
+            _ => {
+                options.push(args[i].clone());
+                i += 1;
+                continue;
+            }
+        }
+        i += 2;
+    }
+
+    let report = simulate(cli::parse(&options), width, height, frames);
+    let per_frame = |total: usize| total as f64 / report.frames.max(1) as f64;
+    println!(
+        "Simulated {} frames at {}x{}",
+        report.frames, report.width, report.height
+    );
+    println!(
+        "  time:     {:.2?} ({:.0} frames/s)",
+        report.elapsed,
+        report.frames as f64 / report.elapsed.as_secs_f64().max(f64::EPSILON)
+    );
+    println!("  peak:     {} drops", report.peak_population);
+    println!("  output:   {:.0} bytes/frame", per_frame(report.bytes));
+    match report.drained_after {
+        Some(n) => println!("  drained:  {} frames after spawning stopped", n),
+        None => println!("  drained:  never"),
+    }
+    if report.passed() {
+        println!("All invariants held");
+        Ok(())
+    } else {
+        for violation in &report.violations {
+            eprintln!("  FAIL {}", violation);
+        }
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,139 @@
+use rand::{rngs::SmallRng, Rng};
+
+use crate::{
+    buffer::PhosphorBuffer,
+    effect::{sample_poisson, Effect, Weather},
+    platform::Color,
+    settings::Settings,
+};
+
+const FLAKES: [char; 3] = ['*', '❄', '.'];
+
+const FLAKE_COLORS: [Color; 3] = [
+    Color::Rgb { r: 240, g: 245, b: 255 },
+    Color::Rgb { r: 200, g: 220, b: 255 },
+    Color::Rgb { r: 160, g: 190, b: 255 },
+];
+
+const PILE_COLOR: Color = Color::Rgb { r: 210, g: 220, b: 235 };
+
+/// Share of the screen covered by flakes at 100% density; snow looks best
+/// much sparser than rain.
+const COVERAGE: f64 = 0.08;
+
+struct Flake {
+    x: f32,
+    y: f32,
+    fall: f32,  // Rows per frame
+    phase: f32, // Where the flake is in its side-to-side wander
+    ch: char,
+    color: Color,
+}
+
+/// `--effect snow`: slow flakes that wander sideways as they drift down and
+/// pile up along the bottom of the screen.
+#[derive(Default)]
+pub struct Snow {
+    width: u16,
+    height: u16,
+    flakes: Vec<Flake>,
+    pile: Vec<u16>, // Snow depth per column, in rows
+}
+
+impl Snow {
+    /// Deepest the snow gets before it starts to melt away again.
+    fn max_depth(&self) -> u16 {
+        (self.height / 5).max(1)
+    }
+
+}
+
+impl Effect for Snow {
+    fn resize(&mut self, width: u16, height: u16) {
+        *self = Snow {
+            width,
+            height,
+            flakes: vec![],
+            pile: vec![0; width as usize],
+        };
+    }
+
+    /// Spawn, drift, and land flakes.
+    fn update(&mut self, settings: &Settings, weather: &Weather, rng: &mut SmallRng) {
+        let (wind, intensity, time) = (weather.wind, weather.intensity, weather.time);
+        if self.width == 0 || self.height == 0 {
+            return;
+        }
+
+        // Slower speed settings mean slower flakes, a few times slower than rain
+        let mean_speed = settings.speed_distribution.mean(settings.min_speed as f64, settings.max_speed as f64) as f32;
+        let mean_fall = 1.0 / (mean_speed.max(1.0) * 3.0);
+        // Keep `COVERAGE * density` of the cells holding a flake: each one
+        // lives for `height / fall` frames
+        let target = settings.density * COVERAGE * self.width as f64 * self.height as f64;
+        let rate = target * mean_fall as f64 / self.height as f64 * intensity * time as f64;
+        let room = settings.max_drops.saturating_sub(self.flakes.len());
+        for _ in 0..sample_poisson(rng, rate).min(room) {
+            let speed = settings.speed_distribution.sample(settings.min_speed, settings.max_speed, rng).max(1);
+            self.flakes.push(Flake {
+                x: rng.gen_range(0.0..self.width as f32),
+                y: -1.0,
+                fall: 1.0 / (speed as f32 * 3.0) * rng.gen_range(0.7..1.3),
+                phase: rng.gen_range(0.0..std::f32::consts::TAU),
+                ch: FLAKES[rng.gen_range(0..FLAKES.len())],
+                color: FLAKE_COLORS[rng.gen_range(0..FLAKE_COLORS.len())],
+            });
+        }
+
+        let max_depth = self.max_depth();
+        let (width, height) = (self.width, self.height);
+        let pile = &mut self.pile;
+        self.flakes.retain_mut(|flake| {
+            flake.phase += 0.08 * time;
+            flake.x += (wind * flake.fall + flake.phase.sin() * 0.15) * time;
+            flake.y += flake.fall * time;
+
+            let col = flake.x.round();
+            if col < 0.0 || col >= width as f32 {
+                return false;
+            }
+            let col = col as usize;
+            if flake.y < (height - pile[col]) as f32 - 1.0 {
+                return true;
+            }
+            // Settle into a lower neighbouring column so the pile stays smooth
+            let lowest = [col.saturating_sub(1), col, (col + 1).min(pile.len() - 1)]
+                .into_iter()
+                .min_by_key(|&c| pile[c])
+                .unwrap_or(col);
+            let landing = if pile[lowest] + 1 < pile[col] { lowest } else { col };
+            pile[landing] = (pile[landing] + 1).min(max_depth);
+            false
+        });
+
+        // Full drifts slowly melt so the pile never stops moving
+        for depth in pile.iter_mut() {
+            if *depth > 0 && rng.gen_bool(0.0005 * *depth as f64) {
+                *depth -= 1;
+            }
+        }
+    }
+
+    fn render(&self, buffer: &mut PhosphorBuffer, _settings: &Settings) {
+        for flake in &self.flakes {
+            if flake.y >= 0.0 {
+                buffer.stamp(flake.x.round() as u16, flake.y as u16, flake.ch, flake.color);
+            }
+        }
+        for (x, &depth) in self.pile.iter().enumerate() {
+            for row in 0..depth {
+                let ch = if row + 1 == depth { '▄' } else { '█' };
+                buffer.stamp(x as u16, self.height - 1 - row, ch, PILE_COLOR);
+            }
+        }
+    }
+
+    fn population(&self) -> usize {
+        self.flakes.len()
+    }
+}
@@ -0,0 +1,179 @@
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use rand::{rngs::SmallRng, Rng, SeedableRng};
+use rodio::{OutputStream, Source};
+
+use crate::{
+    drop::{Direction, Layer},
+    effect::Head,
+};
+
+const SAMPLE_RATE: u32 = 44_100;
+/// Samples between looks at what the rain has been doing, about 6ms.
+const BLOCK: u32 = 256;
+/// Most taps sounding at once; a downpour becomes the hiss instead.
+const VOICES: usize = 12;
+
+/// What the rain did since the audio last looked, shared with its thread.
+#[derive(Default)]
+struct Heard {
+    starts: AtomicU32,  // Drops that came on screen
+    landings: AtomicU32, // Drops whose heads reached the far edge
+    level: AtomicU32,   // How heavy the rain is, 0-1, as f32 bits
+}
+
+/// `--sound`: soft generated rain to go with the picture. Each drop gives a
+/// faint tick as it starts and a tap as it lands on the far edge, over a
+/// hiss that follows how heavy the rain is, all synthesized from noise.
+pub struct Sound {
+    heard: Arc<Heard>,
+    entering: Vec<(u16, u16)>, // Heads on the entry edge last tick
+    leaving: Vec<(u16, u16)>,  // Heads on the far edge last tick
+    _stream: OutputStream,     // Silence once this goes
+}
+
+impl Sound {
+    /// Start playing at `volume`, 0-1, on the default output device.
+    pub fn start(volume: f32) -> Result<Self, String> {
+        let (stream, handle) = OutputStream::try_default().map_err(|err| err.to_string())?;
+        let heard = Arc::new(Heard::default());
+        handle.play_raw(Rain::new(volume, Arc::clone(&heard))).map_err(|err| err.to_string())?;
+        Ok(Sound { heard, entering: vec![], leaving: vec![], _stream: stream })
+    }
+
+    /// Listen to a tick's drop `heads` on a `width` x `height` grid of rain
+    /// running `direction`. Only the front layer makes a sound.
+    pub fn hear(&mut self, heads: &[Head], direction: Direction, width: u16, height: u16) {
+        let (travel, cross) = direction.extents(width, height);
+        let (entry, far) = match direction.step() {
+            1 => (0, travel as i32 - 1),
+            _ => (travel as i32 - 1, 0),
+        };
+        let (mut entering, mut leaving) = (vec![], vec![]);
+        let heads: Vec<&Head> = heads.iter().filter(|head| head.layer == Layer::Front).collect();
+        for head in &heads {
+            let (along, _) = direction.axes(head.x as i32, head.y as i32);
+            if along == entry {
+                entering.push((head.x, head.y));
+            } else if along == far {
+                leaving.push((head.x, head.y));
+            }
+        }
+        // A slow drop sits on the edge for a few ticks, but sounds once
+        let starts = entering.iter().filter(|head| !self.entering.contains(head)).count();
+        let landings = leaving.iter().filter(|head| !self.leaving.contains(head)).count();
+        self.heard.starts.fetch_add(starts as u32, Ordering::Relaxed);
+        self.heard.landings.fetch_add(landings as u32, Ordering::Relaxed);
+        // About two drops a column is a downpour
+        let level = (heads.len() as f32 / (cross.max(1) as f32 * 2.0)).min(1.0);
+        self.heard.level.store(level.to_bits(), Ordering::Relaxed);
+        self.entering = entering;
+        self.leaving = leaving;
+    }
+}
+
+/// One tick or tap: a burst of filtered noise dying away.
+#[derive(Clone, Copy, Default)]
+struct Voice {
+    amplitude: f32,
+    decay: f32,    // Amplitude kept per sample
+    smoothing: f32, // Low-pass amount, higher = duller
+    last: f32,
+}
+
+/// The endless source the audio thread pulls samples from.
+struct Rain {
+    volume: f32,
+    heard: Arc<Heard>,
+    voices: [Voice; VOICES],
+    hiss: f32,  // Low-passed noise under everything
+    level: f32, // The hiss's level, eased toward the rain's
+    sample: u32,
+    rng: SmallRng,
+}
+
+impl Rain {
+    fn new(volume: f32, heard: Arc<Heard>) -> Self {
+        Rain {
+            volume,
+            heard,
+            voices: [Voice::default(); VOICES],
+            hiss: 0.0,
+            level: 0.0,
+            sample: 0,
+            rng: SmallRng::from_entropy(),
+        }
+    }
+
+    /// Set off the ticks and taps the rain has made since the last block.
+    fn listen(&mut self) {
+        let starts = self.heard.starts.swap(0, Ordering::Relaxed);
+        let landings = self.heard.landings.swap(0, Ordering::Relaxed);
+        for i in 0..starts + landings {
+            let Some(voice) = self.voices.iter_mut().min_by(|a, b| a.amplitude.total_cmp(&b.amplitude)) else {
+                break;
+            };
+            // Ticks are short and bright, taps fuller and a touch longer
+            *voice = match i < landings {
+                true => Voice {
+                    amplitude: self.rng.gen_range(0.25..0.45),
+                    decay: self.rng.gen_range(0.9975..0.9988),
+                    smoothing: self.rng.gen_range(0.6..0.8),
+                    last: 0.0,
+                },
+                false => Voice {
+                    amplitude: self.rng.gen_range(0.05..0.12),
+                    decay: 0.993,
+                    smoothing: 0.1,
+                    last: 0.0,
+                },
+            };
+        }
+    }
+}
+
+impl Iterator for Rain {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        if self.sample.is_multiple_of(BLOCK) {
+            self.listen();
+        }
+        self.sample = self.sample.wrapping_add(1);
+        let level = f32::from_bits(self.heard.level.load(Ordering::Relaxed));
+        self.level += (level - self.level) * 0.0005;
+
+        self.hiss = self.hiss * 0.97 + self.rng.gen_range(-1.0..1.0) * 0.03;
+        let mut out = self.hiss * self.level * 0.6;
+        for voice in &mut self.voices {
+            if voice.amplitude < 0.001 {
+                continue;
+            }
+            let noise: f32 = self.rng.gen_range(-1.0..1.0);
+            voice.last = voice.last * voice.smoothing + noise * (1.0 - voice.smoothing);
+            out += voice.last * voice.amplitude;
+            voice.amplitude *= voice.decay;
+        }
+        Some((out * self.volume).clamp(-1.0, 1.0))
+    }
+}
+
+impl Source for Rain {
+    fn current_frame_len(&self) -> Option<usize> {
+        None
+    }
+
+    fn channels(&self) -> u16 {
+        1
+    }
+
+    fn sample_rate(&self) -> u32 {
+        SAMPLE_RATE
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        None
+    }
+}
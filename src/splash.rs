@@ -0,0 +1,68 @@
+use rand::Rng;
+
+use crate::drop::{pick_char, Direction};
+
+/// Steps a splash takes to fade once it has spread all the way.
+const FADE: f32 = 3.0;
+/// Brightness of the glyphs next to where the drop landed, the most any
+/// of a splash gets.
+const LIGHT: f32 = 0.5;
+
+/// `--splash`: a drop that reaches the far edge bursts into a few dim
+/// glyphs that spread out to either side along the edge, then fade.
+pub struct Splash {
+    at: (i32, i32), // Cell the drop landed on
+    vertical: bool, // Falling rain splashes along the row, sideways rain along the column
+    reach: u16,     // Cells it spreads to each side
+    age: f32,       // Steps since it landed
+    chars: Vec<char>, // Its glyphs, a pair for each cell further out
+}
+
+impl Splash {
+    /// A splash `reach` cells to each side of `at`, for a drop going
+    /// `direction` made of glyphs from `charset`.
+    pub fn new(at: (i32, i32), direction: Direction, reach: u16, charset: Option<&[char]>, rng: &mut impl Rng) -> Self {
+        Splash {
+            at,
+            vertical: direction.is_vertical(),
+            reach,
+            age: 0.0,
+            chars: (0..reach * 2).map(|_| pick_char(charset, rng)).collect(),
+        }
+    }
+
+    /// Spread and fade for a step, `time` as long as usual.
+    pub fn update(&mut self, time: f32) {
+        self.age += time;
+    }
+
+    /// Where it landed across the rain, e.g. the column when falling.
+    pub fn across(&self) -> f32 {
+        match self.vertical {
+            true => self.at.0 as f32,
+            false => self.at.1 as f32,
+        }
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.age >= self.reach as f32 + FADE
+    }
+
+    /// Cells lit right now, each with its glyph and how brightly it shows.
+    pub fn glyphs(&self) -> impl Iterator<Item = (i32, i32, char, f32)> + '_ {
+        let fade = 1.0 - self.age / (self.reach as f32 + FADE);
+        let spread = (self.age.floor() as u16 + 1).min(self.reach);
+        (1..=spread).flat_map(move |distance| {
+            let level = LIGHT * fade * (1.0 - (distance - 1) as f32 / self.reach as f32);
+            [-1, 1].into_iter().map(move |side| {
+                let offset = side * distance as i32;
+                let (x, y) = match self.vertical {
+                    true => (self.at.0 + offset, self.at.1),
+                    false => (self.at.0, self.at.1 + offset),
+                };
+                let ch = self.chars[(distance - 1) as usize * 2 + (side > 0) as usize];
+                (x, y, ch, level)
+            })
+        })
+    }
+}
@@ -0,0 +1,43 @@
+use rand::Rng;
+use std::time::Duration;
+
+use crate::platform::Instant;
+
+/// Weather intensity for `--storm`: a slow swell between drizzle and downpour
+/// with the occasional short gust on top.
+pub struct Storm {
+    period: Duration,
+    started: Instant,
+    gust_until: Option<Instant>,
+}
+
+impl Storm {
+    pub fn new(period_secs: u64) -> Self {
+        Storm {
+            period: Duration::from_secs(period_secs.max(1)),
+            started: Instant::now(),
+            gust_until: None,
+        }
+    }
+
+    /// Current intensity multiplier: ~0.25 when calm, ~1.75 at the peak,
+    /// and up to ~2.5 while a gust is blowing.
+    pub fn intensity(&mut self, rng: &mut impl Rng) -> f64 {
+        let now = Instant::now();
+        let phase = now.duration_since(self.started).as_secs_f64() / self.period.as_secs_f64();
+        let swell = 1.0 - (phase * std::f64::consts::TAU).cos() * 0.75;
+
+        match self.gust_until {
+            Some(until) if now < until => return swell + 0.75,
+            Some(_) => self.gust_until = None,
+            // Gusts get more likely the heavier the rain already is
+            None if rng.gen_bool((0.002 * swell).min(1.0)) => {
+                let secs = rng.gen_range(1.0..3.0);
+                self.gust_until = Some(now + Duration::from_secs_f64(secs));
+            }
+            None => {}
+        }
+        swell
+    }
+}
+
@@ -0,0 +1,93 @@
+use rand::Rng;
+use std::time::Duration;
+
+use crate::{
+    buffer::PhosphorBuffer,
+    color::{ColorScheme, Sweep},
+    effect::Head,
+    platform::{Color, Instant},
+};
+
+/// Never keep more than this many glyphs stuck at once.
+const MAX_STUCK: usize = 8;
+
+struct StuckGlyph {
+    x: u16,
+    y: u16,
+    ch: char,
+    color: Color,
+    until: Instant, // Gives up on its own if no drop comes by to sweep it
+}
+
+/// "Dead pixel" easter egg: now and then a glyph a drop leaves behind stays
+/// frozen and glowing until a later drop's head sweeps over it.
+pub struct Stuck {
+    per_minute: f64,
+    glyphs: Vec<StuckGlyph>,
+}
+
+impl Stuck {
+    pub fn new(per_minute: f64) -> Self {
+        Stuck {
+            per_minute,
+            glyphs: vec![],
+        }
+    }
+
+    /// Advance by a frame that lasts `frame_ms`: drop glyphs that one of
+    /// `heads` swept away or that timed out, and maybe freeze a glyph some
+    /// trail has left lit in `buffer`.
+    pub fn tick(
+        &mut self,
+        heads: &[Head],
+        buffer: &PhosphorBuffer,
+        color_scheme: ColorScheme,
+        frame_ms: u64,
+        rng: &mut impl Rng,
+    ) {
+        let now = Instant::now();
+        self.glyphs.retain(|glyph| {
+            let swept = heads
+                .iter()
+                .any(|head| (head.x, head.y) == (glyph.x, glyph.y));
+            !swept && now < glyph.until
+        });
+
+        let chance = self.per_minute * frame_ms.max(1) as f64 / 60_000.0;
+        if buffer.width() == 0
+            || buffer.height() == 0
+            || self.glyphs.len() >= MAX_STUCK
+            || !rng.gen_bool(chance.clamp(0.0, 1.0))
+        {
+            return;
+        }
+        // Look around for a lit trail cell that isn't a head or stuck already
+        for _ in 0..20 {
+            let (x, y) = (
+                rng.gen_range(0..buffer.width()),
+                rng.gen_range(0..buffer.height()),
+            );
+            let cell = buffer.get(x, y);
+            let taken = heads.iter().any(|head| (head.x, head.y) == (x, y))
+                || self.glyphs.iter().any(|glyph| (glyph.x, glyph.y) == (x, y));
+            if cell.is_lit() && !taken {
+                self.glyphs.push(StuckGlyph {
+                    x,
+                    y,
+                    ch: cell.ch,
+                    // Frozen at the near-head glow rather than the dim tail color
+                    color: color_scheme.get_colors(1, 2, x, Sweep::Horizontal),
+                    until: now + Duration::from_secs(rng.gen_range(10..40)),
+                });
+                break;
+            }
+        }
+    }
+
+    /// Light the stuck glyphs on top of everything else.
+    pub fn stamp(&self, buffer: &mut PhosphorBuffer) {
+        for glyph in &self.glyphs {
+            buffer.stamp(glyph.x, glyph.y, glyph.ch, glyph.color);
+        }
+    }
+}
@@ -0,0 +1,131 @@
+//! `--sync-server` and `--sync-client`: several terminals, say a wall of
+//! monitors, showing the very same rain. The server runs it and tells its
+//! clients, a line of text at a time over TCP, how to follow along:
+//!
+//! ```text
+//! reset <SEED> <COLS> <ROWS>    Start over from SEED, on a COLS x ROWS screen
+//! tune <WORDS>                  The runtime keys changed, see `Tuning::words`
+//! tick <N>                      The rain has now moved N frames since the reset
+//! ```
+//!
+//! The rain is the same from the same seed, size and settings, so that's all
+//! a client needs. The server starts over whenever a client joins.
+
+use std::{
+    io::{self, BufRead, BufReader, Write},
+    net::{TcpListener, TcpStream},
+    sync::{
+        mpsc::{self, Receiver},
+        Arc, Mutex,
+    },
+    thread,
+    time::Duration,
+};
+
+use crate::settings::Tuning;
+
+/// Something the server said.
+pub enum Message {
+    Reset { seed: u64, cols: u16, rows: u16 },
+    Tune(Tuning),
+    Tick(u64),
+}
+
+impl Message {
+    fn parse(line: &str) -> Option<Self> {
+        let (word, rest) = line.trim().split_once(' ')?;
+        match word {
+            "reset" => {
+                let mut numbers = rest.split_whitespace();
+                let mut next = || numbers.next()?.parse::<u64>().ok();
+                let (seed, cols, rows) = (next()?, next()?, next()?);
+                Some(Message::Reset {
+                    seed,
+                    cols: cols.try_into().ok()?,
+                    rows: rows.try_into().ok()?,
+                })
+            }
+            "tune" => Tuning::from_words(rest).map(Message::Tune),
+            "tick" => rest.trim().parse().ok().map(Message::Tick),
+            _ => None,
+        }
+    }
+}
+
+/// The `--sync-server` end: takes clients as they come and sends them all
+/// the same lines.
+pub struct SyncServer {
+    clients: Arc<Mutex<Vec<TcpStream>>>,
+    joined: Arc<Mutex<bool>>, // Someone new since the last reset
+}
+
+impl SyncServer {
+    pub fn bind(addr: &str) -> io::Result<Self> {
+        let listener = TcpListener::bind(addr)?;
+        let clients = Arc::new(Mutex::new(vec![]));
+        let joined = Arc::new(Mutex::new(false));
+        let (list, flag) = (Arc::clone(&clients), Arc::clone(&joined));
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                // Ticks are small and late ones are no use, and a client
+                // that stops reading mustn't hold up the rain
+                let _ = stream.set_nodelay(true);
+                let _ = stream.set_write_timeout(Some(Duration::from_secs(1)));
+                list.lock().unwrap().push(stream);
+                *flag.lock().unwrap() = true;
+            }
+        });
+        Ok(SyncServer { clients, joined })
+    }
+
+    /// Whether a client joined since this was last asked, so everyone
+    /// needs to start over together.
+    pub fn joined(&self) -> bool {
+        std::mem::take(&mut *self.joined.lock().unwrap())
+    }
+
+    /// Send `line` to every client, dropping the ones that have gone.
+    pub fn send(&self, line: &str) {
+        let line = format!("{}\n", line);
+        self.clients
+            .lock()
+            .unwrap()
+            .retain_mut(|client| client.write_all(line.as_bytes()).is_ok());
+    }
+}
+
+/// The `--sync-client` end: what the server has said so far.
+pub struct SyncClient {
+    messages: Receiver<Option<Message>>, // None once the server's gone
+}
+
+impl SyncClient {
+    pub fn connect(addr: &str) -> io::Result<Self> {
+        let stream = TcpStream::connect(addr)?;
+        let (tx, messages) = mpsc::channel();
+        thread::spawn(move || {
+            for line in BufReader::new(stream).lines().map_while(Result::ok) {
+                if let Some(message) = Message::parse(&line) {
+                    if tx.send(Some(message)).is_err() {
+                        return;
+                    }
+                }
+            }
+            let _ = tx.send(None);
+        });
+        Ok(SyncClient { messages })
+    }
+
+    /// Everything the server has said since last time, and whether it's
+    /// still there.
+    pub fn messages(&self) -> (Vec<Message>, bool) {
+        let mut messages = vec![];
+        for message in self.messages.try_iter() {
+            match message {
+                Some(message) => messages.push(message),
+                None => return (messages, false),
+            }
+        }
+        (messages, true)
+    }
+}
@@ -0,0 +1,121 @@
+//! Taking over the terminal and handing it back, shared by the live rain
+//! and `matrix play`.
+
+use crossterm::{
+    cursor::{Hide, MoveTo, Show},
+    queue,
+    style::{Color, SetForegroundColor},
+    terminal::{self, Clear, ClearType, DisableLineWrap, EnableLineWrap},
+};
+use std::io::Write;
+
+/// The terminal, to write frames to. Colors and cursor moves are all escape
+/// codes, which the Windows console only follows with virtual terminal
+/// processing switched on; on the old conhost that can't, they're done
+/// with its own calls instead.
+pub fn output() -> impl Write {
+    #[cfg(windows)]
+    return crate::conhost::Conhost::new(std::io::stdout());
+    #[cfg(not(windows))]
+    std::io::stdout()
+}
+
+/// Whether the terminal follows escape codes, as all but the old Windows
+/// console do. Where it doesn't, it has 16 colors and, in its raster font,
+/// no katakana.
+pub fn supports_ansi() -> bool {
+    #[cfg(windows)]
+    return crossterm::ansi_support::supports_ansi();
+    #[cfg(not(windows))]
+    true
+}
+
+/// Switch to raw mode and queue the escapes that hide the cursor and clear
+/// the screen onto `out`.
+pub fn enter(out: &mut impl Write) -> std::io::Result<()> {
+    terminal::enable_raw_mode()?;
+    queue!(out, Hide, DisableLineWrap, Clear(ClearType::All))
+}
+
+/// Queue the escapes that put the terminal back the way we found it onto
+/// `out`, and leave raw mode.
+pub fn leave(out: &mut impl Write) -> std::io::Result<()> {
+    queue!(
+        out,
+        Show,
+        EnableLineWrap,
+        SetForegroundColor(Color::Reset),
+        Clear(ClearType::All),
+        MoveTo(0, 0)
+    )?;
+    terminal::disable_raw_mode()
+}
+
+/// Like `enter` for `--reserve-bottom`, where the rain takes the top `rows`
+/// rows and the shell keeps the `reserved` rows under them: no raw mode and
+/// the cursor stays, so the shell goes on working. Queues onto `out`.
+pub fn enter_above(out: &mut impl Write, rows: u16, reserved: u16) -> std::io::Result<()> {
+    reserve(out, rows, reserved)?;
+    // Where the prompt is can't be asked without reading the keyboard, so
+    // the shell carries on from the bottom
+    queue!(out, MoveTo(0, rows + reserved - 1))
+}
+
+/// Keep the bottom `reserved` rows scrolling by themselves under the
+/// `rows` rows of rain, and clear the rain's rows; again after a resize.
+pub fn reserve(out: &mut impl Write, rows: u16, reserved: u16) -> std::io::Result<()> {
+    // Setting the scrolling region sends the cursor home, so it's kept
+    write!(out, "\x1b7\x1b[{};{}r", rows + 1, rows + reserved)?;
+    for y in 0..rows {
+        queue!(out, MoveTo(0, y), Clear(ClearType::CurrentLine))?;
+    }
+    write!(out, "\x1b8")
+}
+
+/// Undo `enter_above`: the whole terminal scrolls again, with the rain's
+/// rows cleared and the shell's cursor left where it is.
+pub fn leave_above(out: &mut impl Write, rows: u16) -> std::io::Result<()> {
+    write!(out, "\x1b7\x1b[r")?;
+    queue!(out, SetForegroundColor(Color::Reset))?;
+    for y in 0..rows {
+        queue!(out, MoveTo(0, y), Clear(ClearType::CurrentLine))?;
+    }
+    write!(out, "\x1b8")
+}
+
+/// Save the terminal's title, so `pop_title` can put it back, and set it
+/// to `title`.
+pub fn push_title(out: &mut impl Write, title: &str) -> std::io::Result<()> {
+    write!(out, "\x1b[22;0t")?;
+    set_title(out, title)
+}
+
+pub fn set_title(out: &mut impl Write, title: &str) -> std::io::Result<()> {
+    write!(out, "\x1b]2;{}\x07", title)
+}
+
+/// Put back the title `push_title` saved.
+pub fn pop_title(out: &mut impl Write) -> std::io::Result<()> {
+    write!(out, "\x1b[23;0t")
+}
+
+/// Show `percent` in the tab or taskbar, in terminals that read OSC 9;4
+/// progress (Windows Terminal, ConEmu, WezTerm), or clear it with None.
+pub fn progress(out: &mut impl Write, percent: Option<u8>) -> std::io::Result<()> {
+    match percent {
+        Some(percent) => write!(out, "\x1b]9;4;1;{}\x07", percent.min(100)),
+        None => write!(out, "\x1b]9;4;0\x07"),
+    }
+}
+
+/// Pop up a desktop notification, in terminals that turn OSC 9 into one
+/// (iTerm2, kitty, WezTerm, Windows Terminal).
+pub fn notify(out: &mut impl Write, text: &str) -> std::io::Result<()> {
+    write!(out, "\x1b]9;{}\x07", text)
+}
+
+/// The same with OSC 777, which some other terminals read instead (foot,
+/// rxvt-unicode with its notify extension, Ghostty).
+pub fn notify_777(out: &mut impl Write, title: &str, text: &str) -> std::io::Result<()> {
+    write!(out, "\x1b]777;notify;{};{}\x07", title, text)
+}
@@ -0,0 +1,52 @@
+use std::path::PathBuf;
+use std::sync::mpsc::{channel, Receiver};
+
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+
+use crate::{cli, settings::Settings};
+
+/// `--watch`: the config file followed while the rain runs, so a scheme or
+/// glyph set can be worked on by saving the file and seeing it straight away.
+pub struct ConfigWatch {
+    path: PathBuf,
+    args: Vec<String>, // The command line, which still wins over the file
+    saved: Receiver<()>, // A message each time the file's written
+    _watcher: RecommendedWatcher, // Watching stops when this goes
+}
+
+impl ConfigWatch {
+    /// Start watching the config file the command line `args` would read.
+    pub fn start(args: Vec<String>) -> Result<Self, String> {
+        if args.iter().any(|arg| arg == "--no-config") {
+            return Err("--watch needs the config file, but --no-config skips it".to_string());
+        }
+        let path = cli::config_file(&args).ok_or("--watch needs a config file, but there's nowhere to find one")?;
+        let (sender, saved) = channel();
+        let name = path.file_name().map(ToOwned::to_owned);
+        let mut watcher = notify::recommended_watcher(move |event: notify::Result<Event>| {
+            let Ok(event) = event else { return };
+            let ours = event.paths.iter().any(|path| path.file_name() == name.as_deref());
+            if ours && matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_)) {
+                let _ = sender.send(());
+            }
+        })
+        .map_err(|err| format!("could not watch {}: {}", path.display(), err))?;
+        // The directory rather than the file, as editors often save by
+        // writing a new file over the old one
+        let dir = path.parent().filter(|dir| !dir.as_os_str().is_empty()).unwrap_or(".".as_ref());
+        watcher
+            .watch(dir, RecursiveMode::NonRecursive)
+            .map_err(|err| format!("could not watch {}: {}", dir.display(), err))?;
+        Ok(ConfigWatch { path, args, saved, _watcher: watcher })
+    }
+
+    /// The settings the file and command line make now, or why they can't,
+    /// if the file's been saved since last asked.
+    pub fn changed(&self) -> Option<Result<Settings, String>> {
+        // An editor's save is often several writes; they all come out as one
+        if self.saved.try_iter().count() == 0 {
+            return None;
+        }
+        Some(cli::reload(&self.path, &self.args))
+    }
+}
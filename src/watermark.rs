@@ -0,0 +1,96 @@
+use std::time::Duration;
+
+use crate::{
+    color::{brighten, Sweep},
+    drop::CHARS,
+    kiosk::noise,
+    overlay::{Canvas, Overlay, Z_WATERMARK},
+    platform::Instant,
+    render::Glyph,
+};
+
+/// How long the mark takes to form out of scrambled glyphs...
+const FORM: Duration = Duration::from_secs(3);
+/// ...stays readable...
+const HOLD: Duration = Duration::from_secs(40);
+/// ...and takes to scramble again before it re-forms.
+const SCRAMBLE: Duration = Duration::from_secs(3);
+
+/// `--watermark`: a short text, by default user@host, set into the rain near
+/// the bottom right corner in cells a little brighter than the trails
+/// around them. Now and then it scrambles and forms again, so it reads as
+/// part of the rain rather than a label stuck over it.
+pub struct Watermark {
+    text: Vec<char>,
+    noise: Vec<char>, // What it scrambles into, single width so no letter gets covered
+    started: Instant,
+}
+
+impl Watermark {
+    pub fn new(text: &str) -> Self {
+        Watermark {
+            text: text.chars().collect(),
+            noise: CHARS.iter().copied().filter(char::is_ascii_alphanumeric).collect(),
+            started: Instant::now(),
+        }
+    }
+}
+
+impl Overlay for Watermark {
+    fn z(&self) -> u8 {
+        Z_WATERMARK
+    }
+
+    fn draw(&self, canvas: &mut Canvas) {
+        let len = self.text.len().min(canvas.cols.saturating_sub(4) as usize) as u16;
+        if len == 0 || canvas.rows < 3 {
+            return;
+        }
+        let cycle = FORM + HOLD + SCRAMBLE;
+        let elapsed = Duration::from_millis((self.started.elapsed().as_millis() % cycle.as_millis()) as u64);
+        // How much of it reads as itself rather than noise
+        let formed = if elapsed < FORM {
+            elapsed.as_secs_f32() / FORM.as_secs_f32()
+        } else if elapsed < FORM + HOLD {
+            1.0
+        } else {
+            1.0 - (elapsed - FORM - HOLD).as_secs_f32() / SCRAMBLE.as_secs_f32()
+        };
+        let round = (self.started.elapsed().as_millis() / cycle.as_millis()) as u32;
+        let tumble = (elapsed.as_millis() / 120) as u32;
+        let (left, y) = (canvas.cols - 2 - len, canvas.rows - 2);
+        // Nothing double width next to it or in it, which would cover a
+        // letter
+        canvas.put(left - 1, y, Glyph::BLANK);
+        for (i, &ch) in self.text.iter().take(len as usize).enumerate() {
+            let x = left + i as u16;
+            let ch = match noise(i as u16, 0, round) < formed {
+                true => ch,
+                false => {
+                    let pick = noise(i as u16, 0, round ^ tumble.wrapping_mul(7919));
+                    self.noise[(pick * self.noise.len() as f32) as usize]
+                }
+            };
+            let glyph = match ch {
+                ' ' => Glyph::BLANK,
+                ch => Glyph::new(ch, brighten(canvas.scheme.get_colors(3, 10, x, Sweep::Horizontal), 0.1)),
+            };
+            canvas.put(x, y, glyph);
+        }
+    }
+}
+
+/// user@host, or whichever of the two can be found.
+pub fn user_at_host() -> Option<String> {
+    let user = ["USER", "USERNAME", "LOGNAME"]
+        .iter()
+        .find_map(|var| std::env::var(var).ok().filter(|name| !name.is_empty()));
+    #[cfg(not(target_arch = "wasm32"))]
+    let host = crate::motd::hostname();
+    #[cfg(target_arch = "wasm32")]
+    let host: Option<String> = None;
+    match (user, host) {
+        (Some(user), Some(host)) => Some(format!("{}@{}", user, host)),
+        (user, host) => user.or(host),
+    }
+}
@@ -0,0 +1,84 @@
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    cli,
+    matrix::{Matrix, MOUSE_BURST},
+    platform::{KeyCode, KeyEvent, KeyModifiers},
+};
+
+/// The rain in a browser, drawn by a terminal emulator such as xterm.js.
+/// The page drives it: call `tick` every `frameDelay` milliseconds and write
+/// the escape codes it returns to the terminal.
+#[wasm_bindgen]
+pub struct WebRain {
+    matrix: Matrix,
+    started: bool,
+}
+
+#[wasm_bindgen]
+impl WebRain {
+    /// A rain `cols` x `rows` cells big, with the same options as the
+    /// command line, e.g. `"-c rainbow -d 60"`. Options that don't make
+    /// sense leave the defaults.
+    #[wasm_bindgen(constructor)]
+    pub fn new(cols: u16, rows: u16, options: &str) -> WebRain {
+        let args: Vec<String> = options.split_whitespace().map(String::from).collect();
+        WebRain {
+            matrix: Matrix::with_size(cli::try_parse(&args).unwrap_or_default(), cols, rows),
+            started: false,
+        }
+    }
+
+    /// The terminal changed size.
+    pub fn resize(&mut self, cols: u16, rows: u16) {
+        self.matrix.resize(cols, rows);
+        self.started = false;
+    }
+
+    /// Pass on a key press, named like a DOM `KeyboardEvent.key`. Returns
+    /// false for the exit keys, in case the page wants to close the rain.
+    pub fn key(&mut self, key: &str, ctrl: bool) -> bool {
+        let code = match key {
+            "ArrowUp" => KeyCode::Up,
+            "ArrowDown" => KeyCode::Down,
+            "ArrowLeft" => KeyCode::Left,
+            "ArrowRight" => KeyCode::Right,
+            "Enter" => KeyCode::Enter,
+            "Escape" => KeyCode::Esc,
+            _ => match key.chars().next() {
+                Some(ch) if key.chars().count() == 1 => KeyCode::Char(ch),
+                _ => return true,
+            },
+        };
+        let modifiers = if ctrl {
+            KeyModifiers::CONTROL
+        } else {
+            KeyModifiers::NONE
+        };
+        self.matrix.handle_key(KeyEvent::new(code, modifiers))
+    }
+
+    /// Start a burst of drops at a cell, e.g. where the page was clicked.
+    pub fn click(&mut self, col: u16, row: u16) {
+        self.matrix.spawn_at(col, row, MOUSE_BURST);
+    }
+
+    /// Advance by one frame and return the escape codes that draw it.
+    pub fn tick(&mut self) -> String {
+        let mut out = vec![];
+        if !self.started {
+            // Hide the cursor and start from a clean screen
+            out.extend_from_slice(b"\x1b[?25l\x1b[2J");
+            self.started = true;
+        }
+        // Writing to a Vec can't fail
+        let _ = self.matrix.draw(&mut out);
+        String::from_utf8(out).unwrap_or_default()
+    }
+
+    /// Milliseconds to wait before the next `tick`.
+    #[wasm_bindgen(getter, js_name = frameDelay)]
+    pub fn frame_delay(&self) -> u32 {
+        self.matrix.frame_delay().as_millis() as u32
+    }
+}
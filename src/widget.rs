@@ -0,0 +1,80 @@
+use crossterm::style::Color as TermColor;
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Modifier},
+    widgets::{StatefulWidget, Widget},
+};
+
+use crate::{matrix::Matrix, render::Style};
+
+/// Draws the rain into a ratatui [`Rect`], e.g. as the background of a
+/// dashboard or a loading screen. The [`Matrix`] state only moves when the
+/// app calls [`Matrix::step`] from its own tick loop, and is resized to fit
+/// the area it's drawn into.
+///
+/// Cells without rain keep whatever background the area already had, so
+/// other widgets can be drawn on top.
+pub struct MatrixRainWidget;
+
+impl StatefulWidget for MatrixRainWidget {
+    type State = Matrix;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Matrix) {
+        if state.size() != (area.width, area.height) {
+            state.resize(area.width, area.height);
+        }
+        state.render(area, buf);
+    }
+}
+
+/// Draws the current frame as is, clipped to the area.
+impl Widget for &Matrix {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let (cols, rows) = self.size();
+        let frame = self.frame();
+        for y in 0..rows.min(area.height) {
+            for x in 0..cols.min(area.width) {
+                let glyph = &frame[y as usize * cols as usize + x as usize];
+                if glyph.ch == ' ' && glyph.bg == TermColor::Reset {
+                    continue;
+                }
+                let cell = buf.get_mut(area.x + x, area.y + y);
+                cell.set_char(glyph.ch).set_fg(convert(glyph.fg));
+                if glyph.bg != TermColor::Reset {
+                    cell.set_bg(convert(glyph.bg));
+                }
+                match glyph.style {
+                    Style::Plain => {}
+                    Style::Underline => cell.modifier.insert(Modifier::UNDERLINED),
+                    Style::Blink => cell.modifier.insert(Modifier::SLOW_BLINK),
+                }
+            }
+        }
+    }
+}
+
+/// crossterm's color as ratatui's.
+fn convert(color: TermColor) -> Color {
+    match color {
+        TermColor::Rgb { r, g, b } => Color::Rgb(r, g, b),
+        TermColor::AnsiValue(value) => Color::Indexed(value),
+        TermColor::Black => Color::Black,
+        TermColor::DarkRed => Color::Red,
+        TermColor::DarkGreen => Color::Green,
+        TermColor::DarkYellow => Color::Yellow,
+        TermColor::DarkBlue => Color::Blue,
+        TermColor::DarkMagenta => Color::Magenta,
+        TermColor::DarkCyan => Color::Cyan,
+        TermColor::Grey => Color::Gray,
+        TermColor::DarkGrey => Color::DarkGray,
+        TermColor::Red => Color::LightRed,
+        TermColor::Green => Color::LightGreen,
+        TermColor::Yellow => Color::LightYellow,
+        TermColor::Blue => Color::LightBlue,
+        TermColor::Magenta => Color::LightMagenta,
+        TermColor::Cyan => Color::LightCyan,
+        TermColor::White => Color::White,
+        TermColor::Reset => Color::Reset,
+    }
+}
@@ -0,0 +1,121 @@
+//! The rain as a zellij plugin, e.g. in a floating pane as a screensaver
+//! for a session. zellij asks for each frame and draws what the plugin
+//! prints, so this steps the rain on a timer and prints whole frames.
+//!
+//! ```kdl
+//! // In a zellij layout or keybinding
+//! floating_panes {
+//!     pane {
+//!         plugin location="file:/path/to/matrix-zellij.wasm" {
+//!             options "-c cyan --density 60"
+//!         }
+//!     }
+//! }
+//! ```
+
+use std::collections::BTreeMap;
+
+use zellij_tile::prelude::{
+    close_self, request_permission, set_timeout, subscribe, BareKey, Event, EventType,
+    KeyModifier, KeyWithModifier, PermissionType, ZellijPlugin,
+};
+
+use crate::{
+    cli,
+    frame::Frame,
+    matrix::Matrix,
+    platform::{KeyCode, KeyEvent, KeyModifiers},
+};
+
+/// The plugin's state: the rain, once zellij has said how big it is.
+#[derive(Default)]
+pub struct ZellijRain {
+    options: Vec<String>,
+    matrix: Option<Matrix>,
+}
+
+impl ZellijRain {
+    /// Have zellij send a timer event when the next frame is due.
+    fn next_frame(&self) {
+        let delay = self.matrix.as_ref().map_or(0.05, |matrix| matrix.frame_delay().as_secs_f64());
+        set_timeout(delay);
+    }
+}
+
+/// A zellij key as the rain's, if the rain has a use for it.
+fn key_event(key: &KeyWithModifier) -> Option<KeyEvent> {
+    let code = match key.bare_key {
+        BareKey::Up => KeyCode::Up,
+        BareKey::Down => KeyCode::Down,
+        BareKey::Left => KeyCode::Left,
+        BareKey::Right => KeyCode::Right,
+        BareKey::Enter => KeyCode::Enter,
+        BareKey::Esc => KeyCode::Esc,
+        BareKey::Backspace => KeyCode::Backspace,
+        BareKey::Tab if key.key_modifiers.contains(&KeyModifier::Shift) => KeyCode::BackTab,
+        BareKey::Tab => KeyCode::Tab,
+        BareKey::Char(ch) => KeyCode::Char(ch),
+        _ => return None,
+    };
+    let mut modifiers = KeyModifiers::NONE;
+    for (zellij, ours) in [
+        (KeyModifier::Ctrl, KeyModifiers::CONTROL),
+        (KeyModifier::Alt, KeyModifiers::ALT),
+        (KeyModifier::Shift, KeyModifiers::SHIFT),
+    ] {
+        if key.key_modifiers.contains(&zellij) {
+            modifiers |= ours;
+        }
+    }
+    Some(KeyEvent::new(code, modifiers))
+}
+
+impl ZellijPlugin for ZellijRain {
+    /// Take the command-line options from the plugin's `options`, and start
+    /// the frame timer.
+    fn load(&mut self, configuration: BTreeMap<String, String>) {
+        self.options = configuration
+            .get("options")
+            .map(|options| options.split_whitespace().map(String::from).collect())
+            .unwrap_or_default();
+        // To close the pane on an exit key
+        request_permission(&[PermissionType::ChangeApplicationState]);
+        subscribe(&[EventType::Timer, EventType::Key]);
+        self.next_frame();
+    }
+
+    fn update(&mut self, event: Event) -> bool {
+        match event {
+            Event::Timer(_) => {
+                if let Some(matrix) = self.matrix.as_mut() {
+                    matrix.step();
+                }
+                self.next_frame();
+                true
+            }
+            Event::Key(key) => {
+                let (Some(matrix), Some(key)) = (self.matrix.as_mut(), key_event(&key)) else {
+                    return false;
+                };
+                if !matrix.handle_key(key) {
+                    close_self();
+                }
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn render(&mut self, rows: usize, cols: usize) {
+        let (cols, rows) = (cols.min(u16::MAX as usize) as u16, rows.min(u16::MAX as usize) as u16);
+        let options = &self.options;
+        let matrix = self.matrix.get_or_insert_with(|| {
+            // Options that don't make sense leave the defaults, as in a browser
+            Matrix::with_size(cli::try_parse(options).unwrap_or_default(), cols, rows)
+        });
+        if matrix.size() != (cols, rows) {
+            matrix.resize(cols, rows);
+        }
+        print!("{}", Frame::new(cols, rows, matrix.frame()).to_ansi_string());
+    }
+}
@@ -0,0 +1,156 @@
+//! Golden frames: fixed scenarios run from a fixed seed, with the frames
+//! they render checked against the snapshots in `tests/snapshots`, so a
+//! change to the rendering can't change what the rain looks like without
+//! a snapshot changing with it.
+//!
+//! After a change that's meant to look different, regenerate them with
+//! `UPDATE_SNAPSHOTS=1 cargo test --test golden` and review the diff.
+//! Seeded runs only replay on the same word size, so they're for 64-bit.
+
+use std::{fs, path::PathBuf};
+
+use crossterm::{
+    event::{KeyCode, KeyEvent, KeyModifiers},
+    style::Color,
+};
+use matrix::{Frame, Matrix, Settings};
+
+/// Settings from the defaults rather than the command line, so nothing
+/// in the environment the tests run in gets into the frames.
+fn settings(seed: u64) -> Settings {
+    Settings {
+        seed: Some(seed),
+        ..Settings::default()
+    }
+}
+
+/// A frame as text: the glyphs, then each cell's color, a row a line.
+fn snapshot(frame: &Frame) -> String {
+    let mut out = format!("{}x{}\n", frame.width(), frame.height());
+    let cells: Vec<(char, Color)> = frame.cells().collect();
+    let rows: Vec<&[(char, Color)]> = cells.chunks(frame.width().max(1) as usize).collect();
+    for row in &rows {
+        out.extend(row.iter().map(|&(ch, _)| ch));
+        out.push('\n');
+    }
+    for row in &rows {
+        let colors: Vec<String> = row
+            .iter()
+            .map(|&(ch, color)| match (ch, color) {
+                (' ', _) => "-".to_string(),
+                (_, Color::Rgb { r, g, b }) => format!("{:02x}{:02x}{:02x}", r, g, b),
+                (_, color) => format!("{:?}", color).to_lowercase(),
+            })
+            .collect();
+        out.push_str(&colors.join(" "));
+        out.push('\n');
+    }
+    out
+}
+
+/// Check the frames a scenario took against its snapshot, or write the
+/// snapshot when `UPDATE_SNAPSHOTS` is set. A missing snapshot fails, so
+/// one deleted or misnamed can't pass unnoticed.
+fn check(name: &str, frames: &[(usize, Frame)]) {
+    let actual: String = frames
+        .iter()
+        .map(|(tick, frame)| format!("--- frame {} ---\n{}", tick, snapshot(frame)))
+        .collect();
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/snapshots")
+        .join(format!("{}.txt", name));
+    if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, &actual).unwrap();
+        return;
+    }
+    let Ok(expected) = fs::read_to_string(&path) else {
+        panic!("no snapshot at {}; run with UPDATE_SNAPSHOTS=1", path.display());
+    };
+    if let Some((line, (want, got))) = expected
+        .lines()
+        .zip(actual.lines())
+        .enumerate()
+        .find(|(_, (want, got))| want != got)
+    {
+        panic!(
+            "{} differs from {} at line {}:\nexpected: {}\n     got: {}\n\
+             (UPDATE_SNAPSHOTS=1 to accept the new frames)",
+            name,
+            path.display(),
+            line + 1,
+            want,
+            got
+        );
+    }
+    assert_eq!(
+        expected.lines().count(),
+        actual.lines().count(),
+        "{} has a different number of lines than {}",
+        name,
+        path.display()
+    );
+}
+
+/// Step `matrix` on to each of `at`, in order, keeping the frame there.
+fn run(matrix: &mut Matrix, from: usize, at: &[usize]) -> Vec<(usize, Frame)> {
+    let mut tick = from;
+    at.iter()
+        .map(|&until| {
+            while tick < until {
+                matrix.step();
+                tick += 1;
+            }
+            (tick, matrix.render_frame())
+        })
+        .collect()
+}
+
+#[test]
+fn default_rain() {
+    let mut matrix = Matrix::with_size(settings(1), 40, 12);
+    let frames = run(&mut matrix, 0, &[1, 10, 40]);
+    check("default_rain", &frames);
+}
+
+#[test]
+fn resize_mid_run() {
+    let mut matrix = Matrix::with_size(settings(2), 40, 12);
+    let mut frames = run(&mut matrix, 0, &[20]);
+    matrix.resize(28, 16);
+    frames.extend(run(&mut matrix, 20, &[20, 21, 40]));
+    matrix.resize(48, 8);
+    frames.extend(run(&mut matrix, 40, &[40, 60]));
+    check("resize_mid_run", &frames);
+}
+
+#[test]
+fn scheme_switch() {
+    let mut matrix = Matrix::with_size(settings(3), 40, 12);
+    let mut frames = run(&mut matrix, 0, &[20]);
+    // 2 for blue, which the rain fades into while the note saying so fades
+    // out, both in frames, so the test can catch them part way
+    assert!(matrix.handle_key(KeyEvent::new(KeyCode::Char('2'), KeyModifiers::NONE)));
+    frames.extend(run(&mut matrix, 20, &[22, 40]));
+    check("scheme_switch", &frames);
+}
+
+#[test]
+fn dense_chaos() {
+    let settings = Settings {
+        density: 1.0,
+        spawns_per_frame: 40,
+        min_speed: 1,
+        max_speed: 1,
+        max_length: 30,
+        wind: 0.4,
+        gravity: 0.006,
+        branch_chance: 0.05,
+        layers: true,
+        shimmer: 5.0,
+        ..settings(4)
+    };
+    let mut matrix = Matrix::with_size(settings, 40, 12);
+    let frames = run(&mut matrix, 0, &[10, 30, 80]);
+    check("dense_chaos", &frames);
+}
@@ -0,0 +1,55 @@
+//! The input path without a keyboard: key events fed straight in as
+//! Windows and the kitty keyboard protocol report them, with presses,
+//! repeats and releases, checking that each key acts once.
+
+use crossterm::event::{KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use matrix::{play, Matrix, Settings};
+
+fn key(code: KeyCode, kind: KeyEventKind) -> KeyEvent {
+    KeyEvent::new_with_kind(code, KeyModifiers::NONE, kind)
+}
+
+fn matrix() -> Matrix {
+    let settings = Settings {
+        seed: Some(1),
+        ..Settings::default()
+    };
+    Matrix::with_size(settings, 40, 12)
+}
+
+#[test]
+fn press_then_release_acts_once() {
+    let mut matrix = matrix();
+    let before = matrix.fps_target();
+    assert!(matrix.handle_key(key(KeyCode::Up, KeyEventKind::Press)));
+    let pressed = matrix.fps_target();
+    assert!(pressed > before, "Up didn't speed up: {} fps to {}", before, pressed);
+    assert!(matrix.handle_key(key(KeyCode::Up, KeyEventKind::Release)));
+    assert_eq!(matrix.fps_target(), pressed, "letting go of Up sped up again");
+}
+
+#[test]
+fn held_key_repeats_where_it_makes_sense() {
+    let mut matrix = matrix();
+    matrix.handle_key(key(KeyCode::Down, KeyEventKind::Press));
+    let pressed = matrix.fps_target();
+    matrix.handle_key(key(KeyCode::Down, KeyEventKind::Repeat));
+    assert!(matrix.fps_target() < pressed, "holding Down didn't keep slowing down");
+}
+
+#[test]
+fn exit_keys_quit_on_the_press_only() {
+    // The Enter that launched it comes up after it's started
+    assert!(matrix().handle_key(key(KeyCode::Enter, KeyEventKind::Release)));
+    // Held, it has quit already on the way down
+    assert!(matrix().handle_key(key(KeyCode::Char('q'), KeyEventKind::Repeat)));
+    assert!(!matrix().handle_key(key(KeyCode::Char('q'), KeyEventKind::Press)));
+}
+
+#[test]
+fn playback_stops_on_the_press_only() {
+    assert!(play::quits(key(KeyCode::Char('q'), KeyEventKind::Press)));
+    assert!(play::quits(key(KeyCode::Esc, KeyEventKind::Repeat)));
+    assert!(!play::quits(key(KeyCode::Enter, KeyEventKind::Release)));
+    assert!(!play::quits(key(KeyCode::Char('x'), KeyEventKind::Press)));
+}